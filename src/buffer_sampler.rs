@@ -0,0 +1,175 @@
+use super::implicit_buffer::{BufferFilter, WrapMode};
+use super::mapping::MappingRanges;
+
+// The methods that ImplicitBuffer and RgbaBuffer share, which set up their `sampler` field.
+macro_rules! buffer_sampler_methods {
+    () => {
+        pub fn set_filter(&mut self, f: $crate::BufferFilter) {
+            self.sampler.filter = f;
+        }
+
+        pub fn set_wrap(&mut self, w: $crate::WrapMode) {
+            self.sampler.wrap = w;
+        }
+
+        pub fn set_domain_x(&mut self, x0: f64, x1: f64) {
+            self.sampler.domain[0] = (x0, x1);
+        }
+
+        pub fn set_domain_y(&mut self, y0: f64, y1: f64) {
+            self.sampler.domain[1] = (y0, y1);
+        }
+
+        pub fn set_domain_z(&mut self, z0: f64, z1: f64) {
+            self.sampler.domain[2] = (z0, z1);
+        }
+
+        /// Sets the domain to the map ranges, the ones the buffer was mapped over.
+        pub fn set_domain_from_ranges(&mut self, ranges: &$crate::mapping::MappingRanges) {
+            self.sampler.set_domain_from_ranges(ranges);
+        }
+
+        pub fn get_filter(&self) -> $crate::BufferFilter {
+            self.sampler.filter
+        }
+
+        pub fn get_wrap(&self) -> $crate::WrapMode {
+            self.sampler.wrap
+        }
+
+        /// Returns the number of elements along x, y and z. 2D buffers are one element deep.
+        pub fn get_size(&self) -> [usize; 3] {
+            self.sampler.size
+        }
+    };
+}
+
+/// Maps domain coordinates onto the texels of a buffer of up to three dimensions, and gives the texels and weights that make up a filtered sample. It is shared by [`ImplicitBuffer`](struct.ImplicitBuffer.html) and [`RgbaBuffer`](struct.RgbaBuffer.html), whose texels are stored by x, then y, then z, as in the buffers of the mapping functions.
+pub struct BufferSampler {
+    pub size: [usize; 3],
+    pub domain: [(f64, f64); 3],
+    pub filter: BufferFilter,
+    pub wrap: WrapMode,
+}
+
+impl BufferSampler {
+    pub fn new(size: [usize; 3]) -> BufferSampler {
+        BufferSampler {
+            size,
+            domain: [(0.0, 1.0); 3],
+            filter: BufferFilter::Bilinear,
+            wrap: WrapMode::Clamp,
+        }
+    }
+
+    pub fn set_domain_from_ranges(&mut self, ranges: &MappingRanges) {
+        self.domain = [ranges.get_map_x(), ranges.get_map_y(), ranges.get_map_z()];
+    }
+
+    /// Calls `f` with the index and the weight of each texel of the sample at `p`. The weights sum to 1. Axes along which the buffer is a single texel are not filtered.
+    pub fn taps<F: FnMut(usize, f64)>(&self, p: [f64; 3], mut f: F) {
+        if self.size.contains(&0) {
+            return;
+        }
+        let (x, nx) = self.axis_taps(0, p[0]);
+        let (y, ny) = self.axis_taps(1, p[1]);
+        let (z, nz) = self.axis_taps(2, p[2]);
+        for &(ix, wx) in x[..nx].iter() {
+            for &(iy, wy) in y[..ny].iter() {
+                for &(iz, wz) in z[..nz].iter() {
+                    f((ix * self.size[1] + iy) * self.size[2] + iz, wx * wy * wz);
+                }
+            }
+        }
+    }
+
+    // The texels along one axis, and their weights. Texel k is centred on the coordinate that map_2d() and map_3d()
+    // give to element k over the domain, d0 + k / n * (d1 - d0).
+    fn axis_taps(&self, axis: usize, v: f64) -> ([(usize, f64); 4], usize) {
+        let n = self.size[axis];
+        let mut taps = [(0, 0.0); 4];
+        if n == 1 {
+            taps[0] = (0, 1.0);
+            return (taps, 1);
+        }
+        let (d0, d1) = self.domain[axis];
+        let t = (v - d0) / (d1 - d0) * n as f64;
+        match self.filter {
+            BufferFilter::Nearest => {
+                taps[0] = (wrap_index(self.wrap, t.round() as isize, n), 1.0);
+                (taps, 1)
+            }
+            BufferFilter::Bilinear => {
+                let i = t.floor();
+                let s = t - i;
+                taps[0] = (wrap_index(self.wrap, i as isize, n), 1.0 - s);
+                taps[1] = (wrap_index(self.wrap, i as isize + 1, n), s);
+                (taps, 2)
+            }
+            BufferFilter::Bicubic => {
+                // Catmull-Rom weights for the texels at i - 1, i, i + 1 and i + 2.
+                let i = t.floor();
+                let s = t - i;
+                let (s2, s3) = (s * s, s * s * s);
+                let weights = [
+                    0.5 * (-s3 + 2.0 * s2 - s),
+                    0.5 * (3.0 * s3 - 5.0 * s2 + 2.0),
+                    0.5 * (-3.0 * s3 + 4.0 * s2 + s),
+                    0.5 * (s3 - s2),
+                ];
+                for (k, &w) in weights.iter().enumerate() {
+                    taps[k] = (wrap_index(self.wrap, i as isize - 1 + k as isize, n), w);
+                }
+                (taps, 4)
+            }
+        }
+    }
+}
+
+/// Copies a 2D buffer, indexed `a[x][y]`, into a flat vector, with a sampler of its size. Panics if the buffer is not rectangular; `what` names the caller in the message.
+pub fn flatten_2d<T: Copy, Matrix: AsRef<[Row]>, Row: AsRef<[T]>>(what: &str, a: Matrix) -> (BufferSampler, Vec<T>) {
+    let a = a.as_ref();
+    let h = if a.is_empty() { 0 } else { a[0].as_ref().len() };
+    assert_rectangular(what, h, a.iter().map(|row| row.as_ref().len()));
+    let mut data = Vec::with_capacity(a.len() * h);
+    for row in a.iter() {
+        data.extend_from_slice(row.as_ref());
+    }
+    (BufferSampler::new([a.len(), h, 1]), data)
+}
+
+/// Copies a 3D buffer, indexed `a[x][y][z]`, into a flat vector, with a sampler of its size. Panics if the buffer is not rectangular; `what` names the caller in the message.
+pub fn flatten_3d<T: Copy, Matrix: AsRef<[Row]>, Row: AsRef<[Col]>, Col: AsRef<[T]>>(what: &str, a: Matrix) -> (BufferSampler, Vec<T>) {
+    let a = a.as_ref();
+    let h = if a.is_empty() { 0 } else { a[0].as_ref().len() };
+    let d = if h == 0 { 0 } else { a[0].as_ref()[0].as_ref().len() };
+    assert_rectangular(what, h, a.iter().map(|row| row.as_ref().len()));
+    assert_rectangular(what, d, a.iter().flat_map(|row| row.as_ref().iter().map(|col| col.as_ref().len())));
+    let mut data = Vec::with_capacity(a.len() * h * d);
+    for row in a.iter() {
+        for col in row.as_ref().iter() {
+            data.extend_from_slice(col.as_ref());
+        }
+    }
+    (BufferSampler::new([a.len(), h, d]), data)
+}
+
+/// Panics unless every one of `lengths` is `n`, the length of the first row of a buffer.
+pub fn assert_rectangular<I: Iterator<Item = usize>>(what: &str, n: usize, lengths: I) {
+    for len in lengths {
+        assert!(len == n, "{}: the buffer is not rectangular, it has rows of {} and {} elements", what, n, len);
+    }
+}
+
+/// Wraps the index `i` into `0..n` as the wrap mode says.
+pub fn wrap_index(mode: WrapMode, i: isize, n: usize) -> usize {
+    let n = n as isize;
+    match mode {
+        WrapMode::Clamp => i.clamp(0, n - 1) as usize,
+        WrapMode::Repeat => (((i % n) + n) % n) as usize,
+        WrapMode::Mirror => {
+            let m = ((i % (2 * n)) + 2 * n) % (2 * n);
+            (if m < n { m } else { 2 * n - 1 - m }) as usize
+        }
+    }
+}
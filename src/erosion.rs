@@ -0,0 +1,419 @@
+use super::random_gen::{PRNG, LCG, get_01};
+use super::ImplicitModule;
+use super::utility::clamp;
+use super::mapping::{map_2d, CoordinateMapper, MappingRanges};
+use super::buffer_sampler::assert_rectangular;
+
+/// The by-products of an erosion pass, in the shape of the height buffer: the first index runs along x and the second along y, as in the buffers of the mapping functions.
+#[derive(Clone, Debug, Default)]
+pub struct ErosionMaps {
+    /// The material laid down on each cell, in height units.
+    pub sediment: Vec<Vec<f64>>,
+    /// The water that passed over each cell, summed over every step of every droplet. Channels and rivers stand out in it.
+    pub flow: Vec<Vec<f64>>,
+}
+
+/// Hydraulic erosion by droplets, after Hans Theobald Beyer's "Implementation of a method for hydraulic erosion". Each droplet starts at a random cell and runs downhill. Its direction blends the slope with its previous direction, as set by the inertia. It picks up material while it can carry more than it holds, and drops it when it slows down or climbs, until it evaporates or leaves the map. Its capacity is `-dh * speed * water * capacity`, and never less than the minimum capacity. The cells are one unit apart, and heights are in the same units, so the amount of erosion depends on the scale of the heights: a heightmap in -1..1 over 512 cells is very flat, and may need scaling first.
+///
+/// The droplets come from a generator seeded with the given seed, so the same heights and settings always give the same result.
+///
+/// ```
+/// use anl::{ImplicitFractal, FractalType, BasisType, InterpType, ImplicitModule};
+/// use anl::mapping::{map_2d, MappingMode, MappingRanges};
+/// use anl::erosion::HydraulicErosion;
+///
+/// let mut terrain = ImplicitFractal::new(FractalType::FBM, BasisType::Gradient, InterpType::Quintic);
+/// terrain.set_seed(1);
+/// let mut heights = vec![vec![0.0; 64]; 64];
+/// map_2d(MappingMode::SeamlessNone, &mut heights, &mut terrain, &mut MappingRanges::new(), 0.0);
+/// for column in heights.iter_mut() {
+///     for h in column.iter_mut() {
+///         *h *= 20.0;
+///     }
+/// }
+///
+/// let erosion = HydraulicErosion::new().droplets(5000).seed(7);
+/// let mut eroded = heights.clone();
+/// let maps = erosion.erode(&mut eroded);
+/// assert_eq!(maps.flow.len(), 64);
+///
+/// let mut again = heights.clone();
+/// erosion.erode(&mut again);
+/// assert!(eroded == again);
+/// ```
+#[derive(Clone, Debug)]
+pub struct HydraulicErosion {
+    droplets: usize,
+    lifetime: usize,
+    inertia: f64,
+    capacity: f64,
+    min_capacity: f64,
+    deposition: f64,
+    erosion: f64,
+    evaporation: f64,
+    gravity: f64,
+    radius: usize,
+    seed: u32,
+}
+
+impl HydraulicErosion {
+    pub fn new() -> HydraulicErosion {
+        HydraulicErosion {
+            droplets: 50000,
+            lifetime: 30,
+            inertia: 0.05,
+            capacity: 4.0,
+            min_capacity: 0.01,
+            deposition: 0.3,
+            erosion: 0.3,
+            evaporation: 0.01,
+            gravity: 4.0,
+            radius: 3,
+            seed: 0,
+        }
+    }
+
+    /// Sets the number of droplets. Defaults to 50000.
+    pub fn droplets(mut self, n: usize) -> HydraulicErosion {
+        self.droplets = n;
+        self
+    }
+
+    /// Sets the number of steps a droplet takes at most. Defaults to 30.
+    pub fn lifetime(mut self, n: usize) -> HydraulicErosion {
+        self.lifetime = n;
+        self
+    }
+
+    /// Sets how much of its direction a droplet keeps at each step, from 0 (it follows the slope) to 1 (it goes straight on). Defaults to 0.05.
+    pub fn inertia(mut self, i: f64) -> HydraulicErosion {
+        self.inertia = i;
+        self
+    }
+
+    /// Sets how much material a droplet can carry for its speed, water and descent. Defaults to 4.
+    pub fn capacity(mut self, c: f64) -> HydraulicErosion {
+        self.capacity = c;
+        self
+    }
+
+    /// Sets the least a droplet can carry, so that it still erodes on flat ground. Defaults to 0.01.
+    pub fn min_capacity(mut self, c: f64) -> HydraulicErosion {
+        self.min_capacity = c;
+        self
+    }
+
+    /// Sets the fraction of the excess material a droplet drops at each step. Defaults to 0.3.
+    pub fn deposition(mut self, d: f64) -> HydraulicErosion {
+        self.deposition = d;
+        self
+    }
+
+    /// Sets the fraction of its free capacity a droplet fills at each step. Defaults to 0.3.
+    pub fn erosion(mut self, e: f64) -> HydraulicErosion {
+        self.erosion = e;
+        self
+    }
+
+    /// Sets the fraction of its water a droplet loses at each step. Defaults to 0.01.
+    pub fn evaporation(mut self, e: f64) -> HydraulicErosion {
+        self.evaporation = e;
+        self
+    }
+
+    /// Sets how fast droplets speed up going down. Defaults to 4.
+    pub fn gravity(mut self, g: f64) -> HydraulicErosion {
+        self.gravity = g;
+        self
+    }
+
+    /// Sets the radius, in cells, of the area a droplet erodes around itself. Larger radii carve wider, smoother channels. Defaults to 3.
+    pub fn radius(mut self, r: usize) -> HydraulicErosion {
+        self.radius = r;
+        self
+    }
+
+    pub fn seed(mut self, s: u32) -> HydraulicErosion {
+        self.seed = s;
+        self
+    }
+
+    /// Erodes the heights in place, and returns where the material went and where the water ran. Panics if the height buffer is not rectangular.
+    pub fn erode<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(&self, mut a: Matrix) -> ErosionMaps {
+        let (w, h) = buffer_size(&mut a);
+        assert_rectangular("HydraulicErosion::erode", h, a.as_mut().iter_mut().map(|column| column.as_mut().len()));
+        let mut map = Vec::with_capacity(w * h);
+        for column in a.as_mut().iter_mut() {
+            map.extend_from_slice(column.as_mut());
+        }
+        let mut sediment_map = vec![0.0; w * h];
+        let mut flow_map = vec![0.0; w * h];
+        if w < 2 || h < 2 {
+            return ErosionMaps { sediment: unflatten(&sediment_map, w, h), flow: unflatten(&flow_map, w, h) };
+        }
+
+        // The erosion brush: the cells within the radius, weighted by how close they are, and normalized.
+        let r = self.radius as isize;
+        let mut brush = Vec::new();
+        for dx in -r..r + 1 {
+            for dy in -r..r + 1 {
+                let weight = self.radius as f64 + 0.5 - ((dx * dx + dy * dy) as f64).sqrt();
+                if weight > 0.0 {
+                    brush.push((dx, dy, weight));
+                }
+            }
+        }
+        let total: f64 = brush.iter().map(|b| b.2).sum();
+        for b in brush.iter_mut() {
+            b.2 /= total;
+        }
+
+        let mut prng = LCG::new();
+        prng.set_seed(self.seed);
+        let (xmax, ymax) = ((w - 1) as f64, (h - 1) as f64);
+        for _ in 0..self.droplets {
+            let mut x = get_01(&mut prng) * xmax;
+            let mut y = get_01(&mut prng) * ymax;
+            let (mut dx, mut dy) = (0.0, 0.0);
+            let (mut speed, mut water, mut sediment) = (1.0f64, 1.0, 0.0);
+
+            for _ in 0..self.lifetime {
+                if x >= xmax || y >= ymax {
+                    break;
+                }
+                let (ix, iy) = (x as usize, y as usize);
+                let (u, v) = (x - ix as f64, y - iy as f64);
+                let (height, gx, gy) = height_and_gradient(&map, h, x, y);
+                flow_map[ix * h + iy] += water;
+
+                dx = dx * self.inertia - gx * (1.0 - self.inertia);
+                dy = dy * self.inertia - gy * (1.0 - self.inertia);
+                let len = (dx * dx + dy * dy).sqrt();
+                if len == 0.0 {
+                    break;
+                }
+                dx /= len;
+                dy /= len;
+                x += dx;
+                y += dy;
+                if x < 0.0 || y < 0.0 || x >= xmax || y >= ymax {
+                    break;
+                }
+
+                let dh = height_and_gradient(&map, h, x, y).0 - height;
+                let capacity = (-dh * speed * water * self.capacity).max(self.min_capacity);
+                if sediment > capacity || dh > 0.0 {
+                    // Uphill, the droplet fills the pit it leaves, if it can; otherwise it drops part of its excess.
+                    let amount = if dh > 0.0 { dh.min(sediment) } else { (sediment - capacity) * self.deposition };
+                    sediment -= amount;
+                    let corners = [(ix, iy, (1.0 - u) * (1.0 - v)), (ix + 1, iy, u * (1.0 - v)), (ix, iy + 1, (1.0 - u) * v), (ix + 1, iy + 1, u * v)];
+                    for &(cx, cy, weight) in corners.iter() {
+                        map[cx * h + cy] += amount * weight;
+                        sediment_map[cx * h + cy] += amount * weight;
+                    }
+                } else {
+                    // Never more than the drop in height, so that the droplet does not dig a pit behind itself.
+                    let amount = ((capacity - sediment) * self.erosion).min(-dh);
+                    let mut removed = 0.0;
+                    for &(bx, by, weight) in brush.iter() {
+                        let (cx, cy) = (ix as isize + bx, iy as isize + by);
+                        if cx >= 0 && cy >= 0 && (cx as usize) < w && (cy as usize) < h {
+                            map[cx as usize * h + cy as usize] -= amount * weight;
+                            removed += amount * weight;
+                        }
+                    }
+                    sediment += removed;
+                }
+
+                speed = (speed * speed - dh * self.gravity).max(0.0).sqrt();
+                water *= 1.0 - self.evaporation;
+            }
+        }
+
+        for (column, values) in a.as_mut().iter_mut().zip(map.chunks(h)) {
+            column.as_mut().copy_from_slice(values);
+        }
+        ErosionMaps { sediment: unflatten(&sediment_map, w, h), flow: unflatten(&flow_map, w, h) }
+    }
+}
+
+impl Default for HydraulicErosion {
+    fn default() -> Self {
+        HydraulicErosion::new()
+    }
+}
+
+/// Thermal weathering: wherever the drop from a cell to one of its eight neighbours is steeper than the talus slope, part of the excess slides down, so that cliffs crumble into scree slopes at the talus angle. Each iteration moves `rate * (steepest drop - talus)` off a cell, spread over its lower neighbours in proportion to how far they lie below the talus, after Olsen's "Realtime procedural terrain generation". The slopes are in height units per cell, the diagonals being √2 cells long. All the cells are updated together, so the result does not depend on the order of the cells.
+///
+/// A hardness between 0 and 1 can be given for each cell by a module with [`erode_with_hardness()`](#method.erode_with_hardness): a cell of hardness h gives up `1 - h` of what it would otherwise lose, and a cell of hardness 1 does not weather at all. Hardness outside of 0..1 is clamped to that range.
+///
+/// ```
+/// use anl::{ImplicitFractal, FractalType, BasisType, InterpType, ImplicitConstant, ImplicitModule};
+/// use anl::mapping::{map_2d, MappingMode, MappingRanges};
+/// use anl::erosion::ThermalErosion;
+///
+/// let mut terrain = ImplicitFractal::new(FractalType::RidgedMulti, BasisType::Gradient, InterpType::Quintic);
+/// terrain.set_seed(3);
+/// let mut heights = vec![vec![0.0; 64]; 64];
+/// map_2d(MappingMode::SeamlessNone, &mut heights, &mut terrain, &mut MappingRanges::new().map_x(0.0, 4.0).map_y(0.0, 4.0), 0.0);
+///
+/// let thermal = ThermalErosion::new().talus(0.01).iterations(20);
+/// let mut weathered = heights.clone();
+/// let scree = thermal.erode(&mut weathered);
+/// assert!(scree.iter().any(|column| column.iter().any(|&s| s > 0.0)));
+///
+/// // Bedrock of hardness 1 everywhere does not move.
+/// let mut bedrock = heights.clone();
+/// thermal.erode_with_hardness(&mut bedrock, MappingMode::SeamlessNone, &mut ImplicitConstant::new(1.0), &mut MappingRanges::new(), 0.0);
+/// assert!(bedrock == heights);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ThermalErosion {
+    talus: f64,
+    rate: f64,
+    iterations: usize,
+}
+
+impl ThermalErosion {
+    pub fn new() -> ThermalErosion {
+        ThermalErosion {
+            talus: 0.01,
+            rate: 0.5,
+            iterations: 50,
+        }
+    }
+
+    /// Sets the steepest stable slope, in height units per cell. Defaults to 0.01.
+    pub fn talus(mut self, t: f64) -> ThermalErosion {
+        self.talus = t;
+        self
+    }
+
+    /// Sets the fraction of the excess drop that slides down at each iteration, from 0 to 0.5. Higher rates settle faster, but past 0.5 the material overshoots and the slopes oscillate. Defaults to 0.5.
+    pub fn rate(mut self, r: f64) -> ThermalErosion {
+        self.rate = r;
+        self
+    }
+
+    /// Sets the number of iterations. Defaults to 50.
+    pub fn iterations(mut self, n: usize) -> ThermalErosion {
+        self.iterations = n;
+        self
+    }
+
+    /// Weathers the heights in place, and returns the material laid down on each cell, in the shape of the height buffer. Panics if the height buffer is not rectangular.
+    pub fn erode<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(&self, mut a: Matrix) -> Vec<Vec<f64>> {
+        let (_, h) = buffer_size(&mut a);
+        assert_rectangular("ThermalErosion::erode", h, a.as_mut().iter_mut().map(|column| column.as_mut().len()));
+        self.erode_flat(a, None)
+    }
+
+    /// Weathers the heights in place, with the hardness of each cell given by the module `m`. The module is sampled at the coordinates [`map_2d()`](../mapping/fn.map_2d.html) gives each cell for the same mapper, ranges and `z`, so it lines up with heights mapped that way. Returns the material laid down on each cell. Panics if the height buffer is not rectangular.
+    pub fn erode_with_hardness<Mapper: CoordinateMapper, Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(&self, mut a: Matrix, mapper: Mapper, m: &mut ImplicitModule, ranges: &mut MappingRanges, z: f64) -> Vec<Vec<f64>> {
+        let (w, h) = buffer_size(&mut a);
+        assert_rectangular("ThermalErosion::erode_with_hardness", h, a.as_mut().iter_mut().map(|column| column.as_mut().len()));
+        let mut hardness = vec![vec![0.0; h]; w];
+        map_2d(mapper, &mut hardness, m, ranges, z);
+        let mut flat = Vec::with_capacity(w * h);
+        for column in hardness.iter() {
+            flat.extend_from_slice(column);
+        }
+        self.erode_flat(a, Some(&flat))
+    }
+
+    fn erode_flat<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(&self, mut a: Matrix, hardness: Option<&[f64]>) -> Vec<Vec<f64>> {
+        let (w, h) = buffer_size(&mut a);
+        let mut map = Vec::with_capacity(w * h);
+        for column in a.as_mut().iter_mut() {
+            map.extend_from_slice(column.as_mut());
+        }
+        let mut deposit = vec![0.0; w * h];
+        let mut change = vec![0.0; w * h];
+        let neighbours: [(isize, isize, f64); 8] = [(-1, -1, ::std::f64::consts::SQRT_2), (0, -1, 1.0), (1, -1, ::std::f64::consts::SQRT_2), (-1, 0, 1.0), (1, 0, 1.0), (-1, 1, ::std::f64::consts::SQRT_2), (0, 1, 1.0), (1, 1, ::std::f64::consts::SQRT_2)];
+
+        for _ in 0..self.iterations {
+            for c in change.iter_mut() {
+                *c = 0.0;
+            }
+            for x in 0..w {
+                for y in 0..h {
+                    let i = x * h + y;
+                    // The excess of each lower neighbour over the talus, as a drop in height.
+                    let mut excess = [0.0; 8];
+                    let (mut total, mut steepest) = (0.0, 0.0f64);
+                    for (k, &(dx, dy, dist)) in neighbours.iter().enumerate() {
+                        let (nx, ny) = (x as isize + dx, y as isize + dy);
+                        if nx < 0 || ny < 0 || nx as usize >= w || ny as usize >= h {
+                            continue;
+                        }
+                        let d = map[i] - map[nx as usize * h + ny as usize] - self.talus * dist;
+                        if d > 0.0 {
+                            excess[k] = d;
+                            total += d;
+                            steepest = steepest.max(d);
+                        }
+                    }
+                    if total == 0.0 {
+                        continue;
+                    }
+                    // Hardness outside of 0..1 would make a cell gain material, or lose more than it has to give.
+                    let softness = hardness.map_or(1.0, |hd| 1.0 - clamp(hd[i], 0.0, 1.0));
+                    let moved = self.rate * steepest * softness;
+                    if moved <= 0.0 {
+                        continue;
+                    }
+                    change[i] -= moved;
+                    for (k, &(dx, dy, _)) in neighbours.iter().enumerate() {
+                        if excess[k] > 0.0 {
+                            let j = (x as isize + dx) as usize * h + (y as isize + dy) as usize;
+                            change[j] += moved * excess[k] / total;
+                        }
+                    }
+                }
+            }
+            for ((v, c), d) in map.iter_mut().zip(change.iter()).zip(deposit.iter_mut()) {
+                *v += *c;
+                if *c > 0.0 {
+                    *d += *c;
+                }
+            }
+        }
+
+        for (column, values) in a.as_mut().iter_mut().zip(map.chunks(h)) {
+            column.as_mut().copy_from_slice(values);
+        }
+        unflatten(&deposit, w, h)
+    }
+}
+
+impl Default for ThermalErosion {
+    fn default() -> Self {
+        ThermalErosion::new()
+    }
+}
+
+fn buffer_size<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(a: &mut Matrix) -> (usize, usize) {
+    let w = a.as_mut().len();
+    if w > 0 {
+        (w, a.as_mut()[0].as_mut().len())
+    } else {
+        (0, 0)
+    }
+}
+
+fn unflatten(values: &[f64], w: usize, h: usize) -> Vec<Vec<f64>> {
+    (0..w).map(|x| values[x * h..(x + 1) * h].to_vec()).collect()
+}
+
+// The bilinearly interpolated height at (x,y), and its gradient, in a map stored by columns of h cells. (x,y) must lie
+// inside the map, short of its last row and column.
+fn height_and_gradient(map: &[f64], h: usize, x: f64, y: f64) -> (f64, f64, f64) {
+    let (ix, iy) = (x as usize, y as usize);
+    let (u, v) = (x - ix as f64, y - iy as f64);
+    let (h00, h10) = (map[ix * h + iy], map[(ix + 1) * h + iy]);
+    let (h01, h11) = (map[ix * h + iy + 1], map[(ix + 1) * h + iy + 1]);
+    let height = h00 * (1.0 - u) * (1.0 - v) + h10 * u * (1.0 - v) + h01 * (1.0 - u) * v + h11 * u * v;
+    let gx = (h10 - h00) * (1.0 - v) + (h11 - h01) * v;
+    let gy = (h01 - h00) * (1.0 - u) + (h11 - h10) * u;
+    (height, gx, gy)
+}
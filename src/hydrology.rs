@@ -0,0 +1,314 @@
+//! Hydrology on height buffers: where water would pool, where it would run, and where it would gather into rivers.
+//!
+//! The buffers have the shape of those of the mapping functions: the first index runs along x and the second along y, and the cells are one unit apart. A usual pipeline fills the depressions of a mapped terrain, computes the flow directions and the flow accumulation, and extracts the rivers:
+//!
+//! ```
+//! use anl::{ImplicitFractal, FractalType, BasisType, InterpType, ImplicitModule, ImplicitPathDistance};
+//! use anl::mapping::{map_2d, MappingMode, MappingRanges};
+//! use anl::hydrology::{fill_depressions, flow_directions, flow_accumulation, extract_rivers, FlowMethod};
+//!
+//! let mut terrain = ImplicitFractal::new(FractalType::FBM, BasisType::Gradient, InterpType::Quintic);
+//! terrain.set_seed(5);
+//! let mut ranges = MappingRanges::new().map_x(0.0, 4.0).map_y(0.0, 4.0);
+//! let mut heights = vec![vec![0.0; 64]; 64];
+//! map_2d(MappingMode::SeamlessNone, &mut heights, &mut terrain, &mut ranges, 0.0);
+//!
+//! fill_depressions(&mut heights, 1e-6);
+//! let directions = flow_directions(&heights, FlowMethod::D8);
+//! let accumulation = flow_accumulation(&directions);
+//! let rivers = extract_rivers(&directions, &accumulation, 50.0);
+//! assert!(!rivers.is_empty());
+//!
+//! // The distance to the nearest river, in domain units, as a source for other modules.
+//! let paths = rivers.iter().map(|r| r.domain_points(&ranges, 64, 64)).collect();
+//! let mut near_river = ImplicitPathDistance::new(paths);
+//! assert!(near_river.get_2d(1.0, 1.0) >= 0.0);
+//! ```
+
+use super::mapping::MappingRanges;
+use super::buffer_sampler::assert_rectangular;
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::f64::consts::{FRAC_PI_4, SQRT_2};
+
+// The eight neighbours of a cell, counterclockwise from +x, so that neighbour k lies at an angle of k * PI / 4.
+const NEIGHBOURS: [(isize, isize); 8] = [(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)];
+
+/// The way the water of a cell is passed on to its neighbours.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FlowMethod {
+    /// All the water goes to the neighbour of steepest descent, among the eight. Gives sharp, single-cell channels, but the directions snap to multiples of 45°.
+    D8,
+    /// Tarboton's D-infinity: the water flows along the steepest descent of the eight triangular facets around the cell, in any direction, and is split between the two neighbours on either side of it. Gives smoother, more realistic dispersion on slopes.
+    DInfinity,
+}
+
+/// A river, from its source down to where it leaves the map, ends in a pit, or joins another river.
+#[derive(Clone, Debug, Default)]
+pub struct River {
+    /// The cells the river runs through, in cell coordinates.
+    pub points: Vec<[f64; 2]>,
+    /// The flow accumulation at each point.
+    pub flow: Vec<f64>,
+}
+
+impl River {
+    /// Returns the points in domain coordinates, for a buffer of `w` by `h` cells mapped over the x and y map ranges with [`map_2d()`](../mapping/fn.map_2d.html) and [`MappingMode::SeamlessNone`](../mapping/enum.MappingMode.html).
+    pub fn domain_points(&self, ranges: &MappingRanges, w: usize, h: usize) -> Vec<[f64; 2]> {
+        let (x0, x1) = ranges.get_map_x();
+        let (y0, y1) = ranges.get_map_y();
+        self.points.iter().map(|p| [x0 + p[0] / w as f64 * (x1 - x0), y0 + p[1] / h as f64 * (y1 - y0)]).collect()
+    }
+}
+
+// A cell in the priority queue of the depression filling, lowest first, and in order of index between equal heights.
+struct Cell(f64, usize);
+
+impl PartialEq for Cell {
+    fn eq(&self, other: &Cell) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Cell {}
+
+impl PartialOrd for Cell {
+    fn partial_cmp(&self, other: &Cell) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cell {
+    fn cmp(&self, other: &Cell) -> Ordering {
+        other.0.partial_cmp(&self.0).unwrap_or(Ordering::Equal).then(other.1.cmp(&self.1))
+    }
+}
+
+/// Fills the depressions of the heights in place, so that water can run off the map from every cell. This is the priority flood of Barnes, Lehman and Mulla: the map is flooded from its edges, and each cell is raised to at least `epsilon` above the cell it was reached from. With an `epsilon` of 0 the depressions become flats, which have no flow direction; a small positive `epsilon` gives them a slight slope towards their outlet instead.
+pub fn fill_depressions<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(mut a: Matrix, epsilon: f64) {
+    let w = a.as_mut().len();
+    if w == 0 {
+        return;
+    }
+    let h = a.as_mut()[0].as_mut().len();
+    assert_rectangular("fill_depressions", h, a.as_mut().iter_mut().map(|column| column.as_mut().len()));
+    let mut closed = vec![false; w * h];
+    let mut open = BinaryHeap::new();
+    for x in 0..w {
+        for y in 0..h {
+            if x == 0 || y == 0 || x == w - 1 || y == h - 1 {
+                closed[x * h + y] = true;
+                open.push(Cell(a.as_mut()[x].as_mut()[y], x * h + y));
+            }
+        }
+    }
+
+    while let Some(Cell(z, i)) = open.pop() {
+        let (x, y) = (i / h, i % h);
+        for &(dx, dy) in NEIGHBOURS.iter() {
+            let (nx, ny) = (x as isize + dx, y as isize + dy);
+            if nx < 0 || ny < 0 || nx as usize >= w || ny as usize >= h || closed[nx as usize * h + ny as usize] {
+                continue;
+            }
+            let (nx, ny) = (nx as usize, ny as usize);
+            closed[nx * h + ny] = true;
+            let v = &mut a.as_mut()[nx].as_mut()[ny];
+            if *v < z + epsilon {
+                *v = z + epsilon;
+            }
+            open.push(Cell(*v, nx * h + ny));
+        }
+    }
+}
+
+/// Returns the direction in which water leaves each cell, as an angle in radians from 0 to 2π, counterclockwise from +x towards +y in cell coordinates. Cells with no lower neighbour, such as pits and flats, get -1. Fill the depressions first for every cell to drain.
+pub fn flow_directions<Matrix: AsRef<[Row]>, Row: AsRef<[f64]>>(a: Matrix, method: FlowMethod) -> Vec<Vec<f64>> {
+    let a = a.as_ref();
+    let w = a.len();
+    let h = if w > 0 { a[0].as_ref().len() } else { 0 };
+    assert_rectangular("flow_directions", h, a.iter().map(|column| column.as_ref().len()));
+    let height = |x: isize, y: isize| {
+        if x < 0 || y < 0 || x as usize >= w || y as usize >= h {
+            None
+        } else {
+            Some(a[x as usize].as_ref()[y as usize])
+        }
+    };
+
+    let mut directions = vec![vec![-1.0; h]; w];
+    for (x, (column, out)) in a.iter().zip(directions.iter_mut()).enumerate() {
+        for (y, (&e0, d)) in column.as_ref().iter().zip(out.iter_mut()).enumerate() {
+            let (ix, iy) = (x as isize, y as isize);
+            let mut best = (0.0, -1.0);
+            match method {
+                FlowMethod::D8 => {
+                    for (k, &(dx, dy)) in NEIGHBOURS.iter().enumerate() {
+                        if let Some(e) = height(ix + dx, iy + dy) {
+                            let slope = (e0 - e) / if k % 2 == 0 { 1.0 } else { SQRT_2 };
+                            if slope > best.0 {
+                                best = (slope, k as f64 * FRAC_PI_4);
+                            }
+                        }
+                    }
+                }
+                FlowMethod::DInfinity => {
+                    // Facet k spans from neighbour k to neighbour k + 1; one of them is the cardinal neighbour e1, the
+                    // other the diagonal e2, and the angle within the facet is measured from e1.
+                    for k in 0..8 {
+                        let (c, d, sign) = if k % 2 == 0 { (k, k + 1, 1.0) } else { ((k + 1) % 8, k, -1.0) };
+                        let (e1, e2) = match (height(ix + NEIGHBOURS[c].0, iy + NEIGHBOURS[c].1), height(ix + NEIGHBOURS[d].0, iy + NEIGHBOURS[d].1)) {
+                            (Some(e1), Some(e2)) => (e1, e2),
+                            _ => continue,
+                        };
+                        let (s1, s2) = (e0 - e1, e1 - e2);
+                        let mut r = s2.atan2(s1);
+                        let mut s = (s1 * s1 + s2 * s2).sqrt();
+                        if r < 0.0 {
+                            r = 0.0;
+                            s = s1;
+                        } else if r > FRAC_PI_4 {
+                            r = FRAC_PI_4;
+                            s = (e0 - e2) / SQRT_2;
+                        }
+                        if s > best.0 {
+                            let angle = c as f64 * FRAC_PI_4 + sign * r;
+                            best = (s, if angle < 0.0 { angle + 8.0 * FRAC_PI_4 } else { angle });
+                        }
+                    }
+                }
+            }
+            *d = best.1;
+        }
+    }
+    directions
+}
+
+// The neighbours that receive the water of a cell flowing at the given angle, and their shares. A D8 angle, or a
+// D-infinity angle along a neighbour, gives a single receiver.
+fn receivers(angle: f64) -> [(usize, f64); 2] {
+    let t = angle / FRAC_PI_4;
+    let k = t.floor();
+    let f = t - k;
+    let k = k as usize % 8;
+    if f < 1e-9 {
+        [(k, 1.0), (k, 0.0)]
+    } else if f > 1.0 - 1e-9 {
+        [((k + 1) % 8, 1.0), (k, 0.0)]
+    } else {
+        [(k, 1.0 - f), ((k + 1) % 8, f)]
+    }
+}
+
+/// Returns the number of cells that drain through each cell, itself included, from flow directions given by [`flow_directions()`](fn.flow_directions.html). Water that flows off the map is lost. The accumulation is the upstream area of the cell, and its logarithm makes a good river map.
+pub fn flow_accumulation<Matrix: AsRef<[Row]>, Row: AsRef<[f64]>>(directions: Matrix) -> Vec<Vec<f64>> {
+    let directions = directions.as_ref();
+    let w = directions.len();
+    let h = if w > 0 { directions[0].as_ref().len() } else { 0 };
+    assert_rectangular("flow_accumulation", h, directions.iter().map(|column| column.as_ref().len()));
+    let target = |x: usize, y: usize, k: usize| {
+        let (nx, ny) = (x as isize + NEIGHBOURS[k].0, y as isize + NEIGHBOURS[k].1);
+        if nx < 0 || ny < 0 || nx as usize >= w || ny as usize >= h {
+            None
+        } else {
+            Some(nx as usize * h + ny as usize)
+        }
+    };
+
+    // Each cell is passed on once all its donors have been, in the order of a topological sort.
+    let mut donors = vec![0usize; w * h];
+    for (x, column) in directions.iter().enumerate() {
+        for (y, &angle) in column.as_ref().iter().enumerate() {
+            if angle >= 0.0 {
+                for &(k, share) in receivers(angle).iter() {
+                    if let (Some(j), true) = (target(x, y, k), share > 0.0) {
+                        donors[j] += 1;
+                    }
+                }
+            }
+        }
+    }
+    let mut accumulation = vec![1.0; w * h];
+    let mut ready: VecDeque<usize> = (0..w * h).filter(|&i| donors[i] == 0).collect();
+    while let Some(i) = ready.pop_front() {
+        let (x, y) = (i / h, i % h);
+        let angle = directions[x].as_ref()[y];
+        if angle < 0.0 {
+            continue;
+        }
+        for &(k, share) in receivers(angle).iter() {
+            if let (Some(j), true) = (target(x, y, k), share > 0.0) {
+                accumulation[j] += accumulation[i] * share;
+                donors[j] -= 1;
+                if donors[j] == 0 {
+                    ready.push_back(j);
+                }
+            }
+        }
+    }
+    (0..w).map(|x| accumulation[x * h..(x + 1) * h].to_vec()).collect()
+}
+
+/// Traces the rivers over the cells whose flow accumulation is at least `threshold`. Each river starts at a source, a cell with no upstream cell above the threshold, and follows the main flow direction down until it leaves the map, reaches a pit, falls below the threshold, or joins a river traced before it, the junction being its last point. With D-infinity directions, the main direction is the neighbour that receives the larger share.
+pub fn extract_rivers<Matrix: AsRef<[Row]>, Row: AsRef<[f64]>>(directions: Matrix, accumulation: Matrix, threshold: f64) -> Vec<River> {
+    let directions = directions.as_ref();
+    let accumulation = accumulation.as_ref();
+    let w = directions.len();
+    let h = if w > 0 { directions[0].as_ref().len() } else { 0 };
+    assert_rectangular("extract_rivers", h, directions.iter().chain(accumulation.iter()).map(|column| column.as_ref().len()));
+    assert!(accumulation.len() == w, "extract_rivers: the accumulation has {} columns, and the directions {}", accumulation.len(), w);
+    let downstream = |x: usize, y: usize| {
+        let angle = directions[x].as_ref()[y];
+        if angle < 0.0 {
+            return None;
+        }
+        let r = receivers(angle);
+        let k = if r[1].1 > r[0].1 { r[1].0 } else { r[0].0 };
+        let (nx, ny) = (x as isize + NEIGHBOURS[k].0, y as isize + NEIGHBOURS[k].1);
+        if nx < 0 || ny < 0 || nx as usize >= w || ny as usize >= h {
+            None
+        } else {
+            Some((nx as usize, ny as usize))
+        }
+    };
+    let flow = |x: usize, y: usize| accumulation[x].as_ref()[y];
+
+    let mut fed = vec![false; w * h];
+    for x in 0..w {
+        for y in 0..h {
+            if flow(x, y) >= threshold {
+                if let Some((nx, ny)) = downstream(x, y) {
+                    fed[nx * h + ny] = true;
+                }
+            }
+        }
+    }
+
+    let mut traced = vec![false; w * h];
+    let mut rivers = Vec::new();
+    for x in 0..w {
+        for y in 0..h {
+            if flow(x, y) < threshold || fed[x * h + y] {
+                continue;
+            }
+            let mut river = River::default();
+            let mut cell = Some((x, y));
+            while let Some((cx, cy)) = cell {
+                if flow(cx, cy) < threshold {
+                    break;
+                }
+                river.points.push([cx as f64, cy as f64]);
+                river.flow.push(flow(cx, cy));
+                if traced[cx * h + cy] {
+                    break;
+                }
+                traced[cx * h + cy] = true;
+                cell = downstream(cx, cy);
+            }
+            if river.points.len() > 1 {
+                rivers.push(river);
+            }
+        }
+    }
+    rivers
+}
@@ -174,6 +174,16 @@ impl ImplicitAutoCorrect {
 impl ImplicitModule for ImplicitAutoCorrect {
     fn set_seed(&mut self, _: u32) {}
 
+    // A line through the 2D function has the same distribution, so 1D uses the 2D correction.
+    fn get_1d(&mut self, x: f64) -> f64 {
+        match self.source {
+            Some(ref mut s) => {
+                let v = s.borrow_mut().get_1d(x);
+                clamp(v * self.scale2 + self.offset2, self.low, self.high)
+            }
+            None => 0.0,
+        }
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         match self.source {
             Some(ref mut s) => {
@@ -210,6 +220,22 @@ impl ImplicitModule for ImplicitAutoCorrect {
             None => 0.0,
         }
     }
+    // Other dimensions use the correction of the dimension get_nd() pads them to.
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        let (scale, offset) = match p.len() {
+            0..=2 => (self.scale2, self.offset2),
+            3 => (self.scale3, self.offset3),
+            4 => (self.scale4, self.offset4),
+            _ => (self.scale6, self.offset6),
+        };
+        match self.source {
+            Some(ref mut s) => {
+                let v = s.borrow_mut().get_nd(p);
+                clamp(v * scale + offset, self.low, self.high)
+            }
+            None => 0.0,
+        }
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -218,4 +244,10 @@ impl ImplicitModule for ImplicitAutoCorrect {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        if let Some(ref s) = self.source {
+            s.borrow_mut().set_footprint(f);
+        }
+    }
 }
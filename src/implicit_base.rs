@@ -10,11 +10,21 @@ pub const MAX_SOURCES: usize = 20;
 pub trait ImplicitModule {
     fn set_seed(&mut self, _: u32) {}
 
+    /// Evaluates the function along a line, for signals such as animation curves or camera shake. The default samples the 2D function at y=0; basis functions and fractals evaluate native 1D noise instead, and the modifiers pass the call on to their sources.
+    fn get_1d(&mut self, x: f64) -> f64 {
+        self.get_2d(x, 0.0)
+    }
+
     fn get_2d(&mut self, x: f64, y: f64) -> f64;
     fn get_3d(&mut self, x: f64, y: f64, z: f64) -> f64;
     fn get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64;
     fn get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64;
 
+    /// Evaluates the function at a point with any number of coordinates, such as a 5D parameter space or a seamless 3D volume that also loops in time (8D). The fixed-dimension methods above remain the fast paths, and the default forwards 2, 3, 4 and 6 coordinates to them, and 1 coordinate to [`get_1d()`](trait.ImplicitModule.html#method.get_1d). Other counts are padded with zeros up to the next of those dimensions (5D becomes 6D), and coordinates beyond the sixth are ignored. Modules that can do better override it: basis functions evaluate lattice noise in up to [`MAX_DIMENSIONS`](noise_gen/constant.MAX_DIMENSIONS.html) dimensions, fractals combine such octaves, and the modifiers and domain transforms pass the point on to their sources, up to the same limit.
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        get_nd_padded(self, p)
+    }
+
     fn spacing(&self) -> f64;
     fn set_deriv_spacing(&mut self, s: f64);
 
@@ -122,8 +132,36 @@ pub trait ImplicitModule {
         let maxval = self.get_6d(x, y, z, w, u, v + spacing);
         return (minval - maxval) / spacing;
     }
+
+    /// Returns the gradient of the 2D function at (x,y), for modules that can compute it analytically. Unlike the `get_d*()` methods above, which keep the original library's scaled finite differences, these are true partial derivatives. The default returns `None`, and callers fall back to finite differences.
+    fn get_analytic_gradient_2(&mut self, _: f64, _: f64) -> Option<[f64; 2]> {
+        None
+    }
+
+    /// Returns the gradient of the 3D function at (x,y,z), for modules that can compute it analytically. See [`get_analytic_gradient_2()`](trait.ImplicitModule.html#method.get_analytic_gradient_2).
+    fn get_analytic_gradient_3(&mut self, _: f64, _: f64, _: f64) -> Option<[f64; 3]> {
+        None
+    }
+
+    /// Sets the footprint of the following samples: the size, in domain units, of the area that one sample stands for, such as the size of a pixel of a texture. Band-limited modules like [`ImplicitFractal`](struct.ImplicitFractal.html) fade out and drop the detail that is too fine for the footprint, which would otherwise alias and shimmer. Modifiers pass the footprint on to their sources, and domain scaling adjusts it. The default ignores it; a footprint of 0 means point sampling, which is the initial state of every module.
+    fn set_footprint(&mut self, _: f64) {}
+}
+
+/// Evaluates `m` at `p` with the fixed-dimension method that fits: see [`get_nd()`](trait.ImplicitModule.html#method.get_nd). Modules that override `get_nd()` use it for the cases they cannot handle themselves.
+pub fn get_nd_padded<M: ImplicitModule + ?Sized>(m: &mut M, p: &[f64]) -> f64 {
+    let mut q = [0.0; 6];
+    let n = p.len().min(6);
+    q[..n].copy_from_slice(&p[..n]);
+    match p.len() {
+        1 => m.get_1d(q[0]),
+        0 | 2 => m.get_2d(q[0], q[1]),
+        3 => m.get_3d(q[0], q[1], q[2]),
+        4 => m.get_4d(q[0], q[1], q[2], q[3]),
+        _ => m.get_6d(q[0], q[1], q[2], q[3], q[4], q[5]),
+    }
 }
 
+#[derive(Clone)]
 pub struct ImplicitModuleBase {
     pub spacing: f64,
 }
@@ -135,12 +173,20 @@ impl Default for ImplicitModuleBase {
 }
 
 /// Many functions have "parameters" that affect their functionality, aside from any "source" function they might have. An example is the ImplicitSelect function. This function has a control source, a low source, a high source, a threshold parameter, and a falloff parameter. The threshold parameter is used to select between the output of lowSource and highSource, depending on if the value of controlSource is above or below threshold. All five of these inputs are instances of what ANL calls a "scalar parameter". A scalar parameter can be set to either a constant (double-precision) value, or to another function. Most will default to some sane double-precision value (ie, 0), but if desired they can be overridden with any constant or any implicit functional output. In this way, complex behaviors can be obtained through a relatively simple interface.
+#[derive(Clone)]
 pub enum ScalarParameter {
     Value(f64),
     Source(Rc<RefCell<ImplicitModule>>),
 }
 
 impl ScalarParameter {
+    pub fn get_1d(&mut self, x: f64) -> f64 {
+        match self {
+            &mut ScalarParameter::Value(val) => val,
+            &mut ScalarParameter::Source(ref mut s) => s.borrow_mut().get_1d(x),
+        }
+    }
+
     pub fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         match self {
             &mut ScalarParameter::Value(val) => val,
@@ -168,4 +214,17 @@ impl ScalarParameter {
             &mut ScalarParameter::Source(ref mut s) => s.borrow_mut().get_6d(x, y, z, w, u, v),
         }
     }
+
+    pub fn get_nd(&mut self, p: &[f64]) -> f64 {
+        match self {
+            &mut ScalarParameter::Value(val) => val,
+            &mut ScalarParameter::Source(ref mut s) => s.borrow_mut().get_nd(p),
+        }
+    }
+
+    pub fn set_footprint(&mut self, f: f64) {
+        if let ScalarParameter::Source(ref s) = *self {
+            s.borrow_mut().set_footprint(f);
+        }
+    }
 }
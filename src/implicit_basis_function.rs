@@ -1,10 +1,13 @@
 /// The documentation is taken from original [C++ library by Joshua Tippetts](http://accidentalnoise.sourceforge.net/docs.html).
 
-use super::implicit_base::ImplicitModuleBase;
+use super::implicit_base::{ImplicitModuleBase, ScalarParameter, get_nd_padded};
 use super::ImplicitModule;
 use super::noise_gen::*;
 use super::random_gen::*;
 
+use std::rc::Rc;
+use std::cell::RefCell;
+
 /// Basis function types.
 #[derive(Clone, Debug, PartialEq)]
 pub enum BasisType {
@@ -18,6 +21,14 @@ pub enum BasisType {
     Simplex,
     /// `White` noise is a representation of chaotic, random noise. Whereas the previous variants have all generated what is called a "continuously random" signal, WHITE noise generates a truly chaotic signal with no pattern at all.
     White,
+    /// `OpenSimplex2` is the fast variant of K.jpg's OpenSimplex2 noise. Like `Simplex` it sums the contributions of surrounding lattice vertices instead of interpolating a cell, but the lattices are chosen (a rotated body-centered cubic lattice in 3D, the A4 lattice in 4D) so that the diagonal artifacts of `Simplex` largely disappear. The interptype parameter is ignored. There is no 6D variant; 6D evaluation falls back to `Simplex`.
+    OpenSimplex2,
+    /// `OpenSimplex2S` is the smooth variant of `OpenSimplex2`. It uses a larger kernel radius, which considers more lattice vertices per sample and gives a smoother, less "dotty" result; in 4D a sample costs about one and a half times as much as with `OpenSimplex2`. The interptype parameter is ignored, and 6D evaluation falls back to `Simplex`.
+    OpenSimplex2S,
+    /// `Wavelet` is Cook and DeRose's wavelet noise. It is built from a precomputed tile of noise from which the coarse scales have been subtracted, so its energy is confined to a single octave. Fractals built from it therefore neither alias when rendered at a distance nor lose contrast, as fractals of `Gradient` noise tend to. The tile is evaluated with a quadratic B-spline, so the interptype parameter is ignored. The tiles are generated the first time each dimension is used, which takes up to a second for the 6D tile, and are scaled so that the spline through them spans [-1,1], within a percent. They repeat every 128, 64, 24 and 8 units in 2D, 3D, 4D and 6D respectively. The 6D tile is kept that small because its size grows as the sixth power of its width, so 6D wavelet noise repeats every 8 units along each axis, which shows at the frequencies of ordinary fractals; prefer another basis for large 6D domains, such as seamless mappings. The seed only selects an offset into the tile, so different seeds give shifted copies of the same pattern.
+    Wavelet,
+    /// `Flow` is Perlin and Neyret's flow noise: `Gradient` noise whose lattice gradients rotate as the time parameter set with [`set_time_value()`](struct.ImplicitBasisFunction.html#method.set_time_value) or [`set_time_module()`](struct.ImplicitBasisFunction.html#method.set_time_module) advances. Each gradient turns at its own rate, in its own direction, so that animating the time makes the pattern swirl and churn in place, where moving through a higher dimension would make it scroll. The time is an angle in radians; the fastest gradients complete a turn every 2π. It is well suited to lava, water and other flowing materials, especially in fractals with pseudo-advection (see [`ImplicitFractal::set_advection()`](struct.ImplicitFractal.html#method.set_advection)).
+    Flow,
 }
 
 /// Aside from being able to assignt he type of noise generated, the user can also assign the type of interpolation used. This parameter only applies to the variants of [`Value`](enum.BasisType.html#Value), [`Gradval`](enum.BasisType.html#Gradval) and [`Gradient`](enum.BasisType.html#Gradient). 
@@ -33,20 +44,59 @@ pub enum InterpType {
     Quintic,
 }
 
+/// The lattice hash used by the noise generators. The hash determines how many distinct values and gradients the lattice can produce.
+///
+/// Two basis types mostly ignore it. `OpenSimplex2` and `OpenSimplex2S` hash their 2D, 3D and 4D lattices with their own prime multipliers, so the hash only affects their 1D and 6D variants and, while periods are set, their periodic fallbacks. `Wavelet` noise samples a precomputed tile and does not use the hash at all.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HashType {
+    /// `Classic` is the original ANL hash: FNV-1A XOR-folded down to 8 bits. Value noise is quantized to 256 levels and gradients are taken from the 256-entry lookup tables. This is the default, and keeps output identical to earlier versions.
+    Classic,
+    /// `Pcg` is a full-range 32-bit PCG-style hash. Value noise is continuous-valued, gradients are uniformly distributed over the unit sphere of the dimension, and white noise no longer repeats across integer cells.
+    Pcg,
+}
+
 /// The BasisFunction function encapsulates basic noise generators. The function is customizable; you can choose what type of noise to generate and what interpolation style to use. The types of basis and interpolation functions are enumerated as `enum.BasisType.html` and `enum.InterpType.html` respectively.
 ///
 /// In order to mitigate the occurrence of grid-aligned artifacts, especially in fractal functions, basis functions incorporate a 2D axial rotation (for 2D variants) and a 3D axial rotation (for 3D and above variants) that are seeded to randomized axis/angles when the function is constructed. These randomized rotations alter the alignment of the function with the lattice boundaries, and serve in most cases to help obscure the grid artifacts.
 ///
+/// A basis function can also be made periodic with [`set_periods()`](struct.ImplicitBasisFunction.html#method.set_periods), wrapping its lattice so that it repeats every given (integer) number of units along each axis. The randomized rotations would break the repetition, so they are skipped while any period is set.
+///
+/// [`get_1d()`](trait.ImplicitModule.html#method.get_1d) evaluates native 1D noise, which is cheaper than sampling a line through 2D noise and, being unrotated, repeats exactly with the period of the x axis. The simplex types share one 1D variant, since all simplex lattices are the same in 1D; `Wavelet` and `Flow` noise sample their 2D variant at y=0.
+///
+/// Any number of coordinates can be passed to [`get_nd()`](trait.ImplicitModule.html#method.get_nd). `Value`, `Gradient`, `Gradval` and `White` noise are then evaluated on an N-dimensional lattice of up to [`MAX_DIMENSIONS`](noise_gen/constant.MAX_DIMENSIONS.html) axes, with the 3D rotation applied to the first three; the other types, and `Flow` noise, use the next larger dimension they support, up to 6D.
+///
 /// BasisFunction serves as the primary signal generator for ANL. 
+#[derive(Clone)]
 pub struct ImplicitBasisFunction {
     base: ImplicitModuleBase,
     scale: [f64; 4],
     offset: [f64; 4],
     interp: InterpFunc,
+    interp_deriv: InterpFunc,
     f2d: NoiseFunc2,
     f3d: NoiseFunc3,
     f4d: NoiseFunc4,
     f6d: NoiseFunc6,
+    pf2d: PeriodicNoiseFunc2,
+    pf3d: PeriodicNoiseFunc3,
+    pf4d: PeriodicNoiseFunc4,
+    pf6d: PeriodicNoiseFunc6,
+    f1d: Option<NoiseFunc1>,
+    pf1d: Option<PeriodicNoiseFunc1>,
+    fnd: Option<NoiseFuncN>,
+    pfnd: Option<PeriodicNoiseFuncN>,
+    g2d: Option<NoiseGradientFunc2>,
+    g3d: Option<NoiseGradientFunc3>,
+    ff2d: FlowNoiseFunc2,
+    ff3d: FlowNoiseFunc3,
+    ff4d: FlowNoiseFunc4,
+    ff6d: FlowNoiseFunc6,
+    fg2d: FlowGradientFunc2,
+    fg3d: FlowGradientFunc3,
+    time: ScalarParameter,
+    periods: [u32; 6],
+    btype: BasisType,
+    hash: HashType,
     seed: u32,
     rotmatrix: [[f64; 3]; 3],
     cos2d: f64,
@@ -60,10 +110,31 @@ impl Default for ImplicitBasisFunction {
             scale: [0.0; 4],
             offset: [0.0; 4],
             interp: unsafe { ::std::mem::uninitialized() },
+            interp_deriv: quintic_interp_deriv,
             f2d: unsafe { ::std::mem::uninitialized() },
             f3d: unsafe { ::std::mem::uninitialized() },
             f4d: unsafe { ::std::mem::uninitialized() },
             f6d: unsafe { ::std::mem::uninitialized() },
+            pf2d: gradient_noise_2d_periodic_with::<FnvHash>,
+            pf3d: gradient_noise_3d_periodic_with::<FnvHash>,
+            pf4d: gradient_noise_4d_periodic_with::<FnvHash>,
+            pf6d: gradient_noise_6d_periodic_with::<FnvHash>,
+            f1d: None,
+            pf1d: None,
+            fnd: None,
+            pfnd: None,
+            g2d: None,
+            g3d: None,
+            ff2d: flow_noise_2d_with::<FnvHash>,
+            ff3d: flow_noise_3d_with::<FnvHash>,
+            ff4d: flow_noise_4d_with::<FnvHash>,
+            ff6d: flow_noise_6d_with::<FnvHash>,
+            fg2d: flow_noise_2d_gradient_with::<FnvHash>,
+            fg3d: flow_noise_3d_gradient_with::<FnvHash>,
+            time: ScalarParameter::Value(0.0),
+            periods: [0; 6],
+            btype: BasisType::Gradient,
+            hash: HashType::Classic,
             seed: 0,
             rotmatrix: [[0.0; 3]; 3],
             cos2d: 0.0,
@@ -88,47 +159,237 @@ impl ImplicitBasisFunction {
     }
 
     pub fn set_type(&mut self, t: BasisType) {
-        match t {
+        match self.hash {
+            HashType::Classic => self.set_functions::<FnvHash>(&t),
+            HashType::Pcg => self.set_functions::<PcgHash>(&t),
+        }
+        self.btype = t.clone();
+        self.set_magic_numbers(t)
+    }
+
+    /// Selects the lattice hash backend. See [`HashType`](enum.HashType.html).
+    ///
+    /// `OpenSimplex2` and `OpenSimplex2S` ignore the hash in 2D, 3D and 4D, except while periods are set, and
+    /// `Wavelet` ignores it in every dimension.
+    pub fn set_hash(&mut self, hash: HashType) {
+        self.hash = hash;
+        let t = self.btype.clone();
+        self.set_type(t);
+    }
+
+    /// Sets the lattice periods along x, y, z, w, u and v, in that order. The function then repeats every `periods[k]`
+    /// units along axis k. Axes beyond the end of the slice, or with a period of 0, do not repeat; an empty slice
+    /// turns periodicity off and restores the randomized rotations.
+    ///
+    /// `Simplex`, `OpenSimplex2` and `OpenSimplex2S` are evaluated on lattices that can repeat along the axes (see
+    /// [`simplex_noise_2d_periodic_with()`](noise_gen/fn.simplex_noise_2d_periodic_with.html)), which look
+    /// slightly different from their non-periodic counterparts; in 2D the y period of these types must be even, and
+    /// odd periods are doubled. Their 4D and 6D variants fall back to periodic `Gradient` noise. `Wavelet` noise stays
+    /// continuous across the wrap, but is only approximately band-limited there.
+    pub fn set_periods(&mut self, periods: &[u32]) {
+        self.periods = [0; 6];
+        for (p, v) in self.periods.iter_mut().zip(periods.iter()) {
+            *p = *v;
+        }
+    }
+
+    /// Sets the time of `Flow` noise, as a constant. Other basis types ignore it.
+    pub fn set_time_value(&mut self, t: f64) {
+        self.time = ScalarParameter::Value(t);
+    }
+
+    /// Sets the time of `Flow` noise, driven by another function evaluated at the sampled coordinate. A time that
+    /// varies over space makes different regions churn at different speeds.
+    pub fn set_time_module(&mut self, m: Rc<RefCell<ImplicitModule>>) {
+        self.time = ScalarParameter::Source(m);
+    }
+
+    fn is_periodic(&self) -> bool {
+        self.periods.iter().any(|&p| p != 0)
+    }
+
+    fn set_functions<H: LatticeHash>(&mut self, t: &BasisType) {
+        self.ff2d = flow_noise_2d_with::<H>;
+        self.ff3d = flow_noise_3d_with::<H>;
+        self.ff4d = flow_noise_4d_with::<H>;
+        self.ff6d = flow_noise_6d_with::<H>;
+        self.fg2d = flow_noise_2d_gradient_with::<H>;
+        self.fg3d = flow_noise_3d_gradient_with::<H>;
+
+        // Only the lattice types and simplex noise have 1D variants, only the lattice types have N-dimensional variants,
+        // and only the interpolated ones have analytic gradients.
+        match *t {
             BasisType::Value => {
-                self.f2d = value_noise_2d;
-                self.f3d = value_noise_3d;
-                self.f4d = value_noise_4d;
-                self.f6d = value_noise_6d;
+                self.f1d = Some(value_noise_1d_with::<H>);
+                self.pf1d = Some(value_noise_1d_periodic_with::<H>);
             }
             BasisType::Gradient => {
-                self.f2d = gradient_noise_2d;
-                self.f3d = gradient_noise_3d;
-                self.f4d = gradient_noise_4d;
-                self.f6d = gradient_noise_6d;
+                self.f1d = Some(gradient_noise_1d_with::<H>);
+                self.pf1d = Some(gradient_noise_1d_periodic_with::<H>);
+            }
+            BasisType::Gradval => {
+                self.f1d = Some(gradval_noise_1d_with::<H>);
+                self.pf1d = Some(gradval_noise_1d_periodic_with::<H>);
+            }
+            BasisType::White => {
+                self.f1d = Some(white_noise_1d_with::<H>);
+                self.pf1d = Some(white_noise_1d_periodic_with::<H>);
+            }
+            BasisType::Simplex | BasisType::OpenSimplex2 | BasisType::OpenSimplex2S => {
+                self.f1d = Some(simplex_noise_1d_with::<H>);
+                self.pf1d = Some(simplex_noise_1d_periodic_with::<H>);
+            }
+            _ => {
+                self.f1d = None;
+                self.pf1d = None;
+            }
+        }
+        match *t {
+            BasisType::Value => {
+                self.fnd = Some(value_noise_nd_with::<H>);
+                self.pfnd = Some(value_noise_nd_periodic_with::<H>);
+            }
+            BasisType::Gradient => {
+                self.fnd = Some(gradient_noise_nd_with::<H>);
+                self.pfnd = Some(gradient_noise_nd_periodic_with::<H>);
+            }
+            BasisType::Gradval => {
+                self.fnd = Some(gradval_noise_nd_with::<H>);
+                self.pfnd = Some(gradval_noise_nd_periodic_with::<H>);
+            }
+            BasisType::White => {
+                self.fnd = Some(white_noise_nd_with::<H>);
+                self.pfnd = Some(white_noise_nd_periodic_with::<H>);
+            }
+            _ => {
+                self.fnd = None;
+                self.pfnd = None;
+            }
+        }
+        match *t {
+            BasisType::Value => {
+                self.g2d = Some(value_noise_2d_gradient_with::<H>);
+                self.g3d = Some(value_noise_3d_gradient_with::<H>);
+            }
+            BasisType::Gradient => {
+                self.g2d = Some(gradient_noise_2d_gradient_with::<H>);
+                self.g3d = Some(gradient_noise_3d_gradient_with::<H>);
+            }
+            BasisType::Gradval => {
+                self.g2d = Some(gradval_noise_2d_gradient_with::<H>);
+                self.g3d = Some(gradval_noise_3d_gradient_with::<H>);
+            }
+            _ => {
+                self.g2d = None;
+                self.g3d = None;
+            }
+        }
+
+        match *t {
+            BasisType::Value => {
+                self.f2d = value_noise_2d_with::<H>;
+                self.f3d = value_noise_3d_with::<H>;
+                self.f4d = value_noise_4d_with::<H>;
+                self.f6d = value_noise_6d_with::<H>;
+                self.pf2d = value_noise_2d_periodic_with::<H>;
+                self.pf3d = value_noise_3d_periodic_with::<H>;
+                self.pf4d = value_noise_4d_periodic_with::<H>;
+                self.pf6d = value_noise_6d_periodic_with::<H>;
+            }
+            // Flow noise is evaluated by the flow functions above, so these only keep the pointers valid.
+            BasisType::Gradient | BasisType::Flow => {
+                self.f2d = gradient_noise_2d_with::<H>;
+                self.f3d = gradient_noise_3d_with::<H>;
+                self.f4d = gradient_noise_4d_with::<H>;
+                self.f6d = gradient_noise_6d_with::<H>;
+                self.pf2d = gradient_noise_2d_periodic_with::<H>;
+                self.pf3d = gradient_noise_3d_periodic_with::<H>;
+                self.pf4d = gradient_noise_4d_periodic_with::<H>;
+                self.pf6d = gradient_noise_6d_periodic_with::<H>;
             }
             BasisType::Gradval => {
-                self.f2d = gradval_noise_2d;
-                self.f3d = gradval_noise_3d;
-                self.f4d = gradval_noise_4d;
-                self.f6d = gradval_noise_6d;
+                self.f2d = gradval_noise_2d_with::<H>;
+                self.f3d = gradval_noise_3d_with::<H>;
+                self.f4d = gradval_noise_4d_with::<H>;
+                self.f6d = gradval_noise_6d_with::<H>;
+                self.pf2d = gradval_noise_2d_periodic_with::<H>;
+                self.pf3d = gradval_noise_3d_periodic_with::<H>;
+                self.pf4d = gradval_noise_4d_periodic_with::<H>;
+                self.pf6d = gradval_noise_6d_periodic_with::<H>;
             }
             BasisType::White => {
-                self.f2d = white_noise_2d;
-                self.f3d = white_noise_3d;
-                self.f4d = white_noise_4d;
-                self.f6d = white_noise_6d;
+                self.f2d = white_noise_2d_with::<H>;
+                self.f3d = white_noise_3d_with::<H>;
+                self.f4d = white_noise_4d_with::<H>;
+                self.f6d = white_noise_6d_with::<H>;
+                self.pf2d = white_noise_2d_periodic_with::<H>;
+                self.pf3d = white_noise_3d_periodic_with::<H>;
+                self.pf4d = white_noise_4d_periodic_with::<H>;
+                self.pf6d = white_noise_6d_periodic_with::<H>;
             }
             BasisType::Simplex => {
-                self.f2d = simplex_noise_2d;
-                self.f3d = simplex_noise_3d;
-                self.f4d = simplex_noise_4d;
-                self.f6d = simplex_noise_6d;
+                self.f2d = simplex_noise_2d_with::<H>;
+                self.f3d = simplex_noise_3d_with::<H>;
+                self.f4d = simplex_noise_4d_with::<H>;
+                self.f6d = simplex_noise_6d_with::<H>;
+                self.pf2d = simplex_noise_2d_periodic_with::<H>;
+                self.pf3d = simplex_noise_3d_periodic_with::<H>;
+                self.pf4d = gradient_noise_4d_periodic_with::<H>;
+                self.pf6d = gradient_noise_6d_periodic_with::<H>;
+            }
+            // OpenSimplex2 hashes its lattice with its own prime multipliers, so only the 6D and periodic fallbacks honour
+            // the hash backend.
+            BasisType::OpenSimplex2 => {
+                self.f2d = opensimplex2_noise_2d;
+                self.f3d = opensimplex2_noise_3d;
+                self.f4d = opensimplex2_noise_4d;
+                self.f6d = simplex_noise_6d_with::<H>;
+                self.pf2d = simplex_noise_2d_periodic_with::<H>;
+                self.pf3d = simplex_noise_3d_periodic_with::<H>;
+                self.pf4d = gradient_noise_4d_periodic_with::<H>;
+                self.pf6d = gradient_noise_6d_periodic_with::<H>;
+            }
+            BasisType::OpenSimplex2S => {
+                self.f2d = opensimplex2s_noise_2d;
+                self.f3d = opensimplex2s_noise_3d;
+                self.f4d = opensimplex2s_noise_4d;
+                self.f6d = simplex_noise_6d_with::<H>;
+                self.pf2d = simplex_noise_2d_periodic_with::<H>;
+                self.pf3d = simplex_noise_3d_periodic_with::<H>;
+                self.pf4d = gradient_noise_4d_periodic_with::<H>;
+                self.pf6d = gradient_noise_6d_periodic_with::<H>;
+            }
+            BasisType::Wavelet => {
+                self.f2d = wavelet_noise_2d;
+                self.f3d = wavelet_noise_3d;
+                self.f4d = wavelet_noise_4d;
+                self.f6d = wavelet_noise_6d;
+                self.pf2d = wavelet_noise_2d_periodic;
+                self.pf3d = wavelet_noise_3d_periodic;
+                self.pf4d = wavelet_noise_4d_periodic;
+                self.pf6d = wavelet_noise_6d_periodic;
             }
         }
-        self.set_magic_numbers(t)
     }
 
     pub fn set_interp(&mut self, interp: InterpType) {
         match interp {
-            InterpType::None => self.interp = no_interp,
-            InterpType::Linear => self.interp = linear_interp,
-            InterpType::Cubic => self.interp = hermite_interp,
-            InterpType::Quintic => self.interp = quintic_interp,
+            InterpType::None => {
+                self.interp = no_interp;
+                self.interp_deriv = no_interp_deriv;
+            }
+            InterpType::Linear => {
+                self.interp = linear_interp;
+                self.interp_deriv = linear_interp_deriv;
+            }
+            InterpType::Cubic => {
+                self.interp = hermite_interp;
+                self.interp_deriv = hermite_interp_deriv;
+            }
+            InterpType::Quintic => {
+                self.interp = quintic_interp;
+                self.interp_deriv = quintic_interp_deriv;
+            }
         }
     }
 
@@ -146,6 +407,41 @@ impl ImplicitBasisFunction {
         self.rotmatrix[2][2] = 1.0 + (1.0 - angle.cos()) * (z * z - 1.0);
     }
 
+    // The time is treated as constant over space, so a time driven by a module has no analytic gradient.
+    fn flow_gradient_2d(&mut self, x: f64, y: f64) -> Option<[f64; 2]> {
+        let time = match self.time {
+            ScalarParameter::Value(t) if self.btype == BasisType::Flow => t,
+            _ => return None,
+        };
+        let p = [self.periods[0], self.periods[1]];
+        if self.is_periodic() {
+            return Some((self.fg2d)(x, y, p, time, self.seed, self.interp, self.interp_deriv));
+        }
+        let nx = x * self.cos2d - y * self.sin2d;
+        let ny = y * self.cos2d + x * self.sin2d;
+        let g = (self.fg2d)(nx, ny, p, time, self.seed, self.interp, self.interp_deriv);
+        Some([g[0] * self.cos2d + g[1] * self.sin2d, g[1] * self.cos2d - g[0] * self.sin2d])
+    }
+
+    fn flow_gradient_3d(&mut self, x: f64, y: f64, z: f64) -> Option<[f64; 3]> {
+        let time = match self.time {
+            ScalarParameter::Value(t) if self.btype == BasisType::Flow => t,
+            _ => return None,
+        };
+        let p = [self.periods[0], self.periods[1], self.periods[2]];
+        if self.is_periodic() {
+            return Some((self.fg3d)(x, y, z, p, time, self.seed, self.interp, self.interp_deriv));
+        }
+        let nx = (self.rotmatrix[0][0] * x) + (self.rotmatrix[1][0] * y) + (self.rotmatrix[2][0] * z);
+        let ny = (self.rotmatrix[0][1] * x) + (self.rotmatrix[1][1] * y) + (self.rotmatrix[2][1] * z);
+        let nz = (self.rotmatrix[0][2] * x) + (self.rotmatrix[1][2] * y) + (self.rotmatrix[2][2] * z);
+        let g = (self.fg3d)(nx, ny, nz, p, time, self.seed, self.interp, self.interp_deriv);
+        let r = &self.rotmatrix;
+        Some([r[0][0] * g[0] + r[0][1] * g[1] + r[0][2] * g[2],
+              r[1][0] * g[0] + r[1][1] * g[1] + r[1][2] * g[2],
+              r[2][0] * g[0] + r[2][1] * g[1] + r[2][2] * g[2]])
+    }
+
     pub fn set_magic_numbers(&mut self, btype: BasisType) {
         // This function is a damned hack.
         // The underlying noise functions don't return values in the range [-1,1] cleanly, and the ranges vary depending
@@ -162,7 +458,7 @@ impl ImplicitBasisFunction {
                 self.scale[3] = 1.0;
                 self.offset[3] = 0.0;
             }
-            BasisType::Gradient => {
+            BasisType::Gradient | BasisType::Flow => {
                 self.scale[0] = 1.86848;
                 self.offset[0] = -0.000118;
                 self.scale[1] = 1.85148;
@@ -192,7 +488,7 @@ impl ImplicitBasisFunction {
                 self.scale[3] = 1.0;
                 self.offset[3] = 0.0;
             }
-            BasisType::Simplex => {
+            BasisType::Simplex | BasisType::OpenSimplex2 | BasisType::OpenSimplex2S | BasisType::Wavelet => {
                 self.scale[0] = 1.0;
                 self.offset[0] = 0.0;
                 self.scale[1] = 1.0;
@@ -226,29 +522,124 @@ impl ImplicitModule for ImplicitBasisFunction {
         self.sin2d = angle.sin();
     }
 
+    fn get_1d(&mut self, x: f64) -> f64 {
+        let (f1d, pf1d) = match (self.f1d, self.pf1d) {
+            (Some(f), Some(pf)) => (f, pf),
+            _ => return self.get_2d(x, 0.0),
+        };
+        if self.periods[0] != 0 {
+            return pf1d(x, self.periods[0], self.seed, self.interp);
+        }
+        f1d(x, self.seed, self.interp)
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
+        if self.btype == BasisType::Flow {
+            let time = self.time.get_2d(x, y);
+            let p = [self.periods[0], self.periods[1]];
+            if self.is_periodic() {
+                return (self.ff2d)(x, y, p, time, self.seed, self.interp);
+            }
+            let nx = x * self.cos2d - y * self.sin2d;
+            let ny = y * self.cos2d + x * self.sin2d;
+            return (self.ff2d)(nx, ny, p, time, self.seed, self.interp);
+        }
+        if self.is_periodic() {
+            let p = &self.periods;
+            return (self.pf2d)(x, y, [p[0], p[1]], self.seed, self.interp);
+        }
         let nx = x * self.cos2d - y * self.sin2d;
         let ny = y * self.cos2d + x * self.sin2d;
         (self.f2d)(nx, ny, self.seed, self.interp)
     }
     fn get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
+        if self.btype == BasisType::Flow {
+            let time = self.time.get_3d(x, y, z);
+            let p = [self.periods[0], self.periods[1], self.periods[2]];
+            if self.is_periodic() {
+                return (self.ff3d)(x, y, z, p, time, self.seed, self.interp);
+            }
+            let nx = (self.rotmatrix[0][0] * x) + (self.rotmatrix[1][0] * y) + (self.rotmatrix[2][0] * z);
+            let ny = (self.rotmatrix[0][1] * x) + (self.rotmatrix[1][1] * y) + (self.rotmatrix[2][1] * z);
+            let nz = (self.rotmatrix[0][2] * x) + (self.rotmatrix[1][2] * y) + (self.rotmatrix[2][2] * z);
+            return (self.ff3d)(nx, ny, nz, p, time, self.seed, self.interp);
+        }
+        if self.is_periodic() {
+            let p = &self.periods;
+            return (self.pf3d)(x, y, z, [p[0], p[1], p[2]], self.seed, self.interp);
+        }
         let nx = (self.rotmatrix[0][0] * x) + (self.rotmatrix[1][0] * y) + (self.rotmatrix[2][0] * z);
         let ny = (self.rotmatrix[0][1] * x) + (self.rotmatrix[1][1] * y) + (self.rotmatrix[2][1] * z);
         let nz = (self.rotmatrix[0][2] * x) + (self.rotmatrix[1][2] * y) + (self.rotmatrix[2][2] * z);
         (self.f3d)(nx, ny, nz, self.seed, self.interp)
     }
     fn get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
+        if self.btype == BasisType::Flow {
+            let time = self.time.get_4d(x, y, z, w);
+            let p = [self.periods[0], self.periods[1], self.periods[2], self.periods[3]];
+            if self.is_periodic() {
+                return (self.ff4d)(x, y, z, w, p, time, self.seed, self.interp);
+            }
+            let nx = (self.rotmatrix[0][0] * x) + (self.rotmatrix[1][0] * y) + (self.rotmatrix[2][0] * z);
+            let ny = (self.rotmatrix[0][1] * x) + (self.rotmatrix[1][1] * y) + (self.rotmatrix[2][1] * z);
+            let nz = (self.rotmatrix[0][2] * x) + (self.rotmatrix[1][2] * y) + (self.rotmatrix[2][2] * z);
+            return (self.ff4d)(nx, ny, nz, w, p, time, self.seed, self.interp);
+        }
+        if self.is_periodic() {
+            let p = &self.periods;
+            return (self.pf4d)(x, y, z, w, [p[0], p[1], p[2], p[3]], self.seed, self.interp);
+        }
         let nx = (self.rotmatrix[0][0] * x) + (self.rotmatrix[1][0] * y) + (self.rotmatrix[2][0] * z);
         let ny = (self.rotmatrix[0][1] * x) + (self.rotmatrix[1][1] * y) + (self.rotmatrix[2][1] * z);
         let nz = (self.rotmatrix[0][2] * x) + (self.rotmatrix[1][2] * y) + (self.rotmatrix[2][2] * z);
         (self.f4d)(nx, ny, nz, w, self.seed, self.interp)
     }
     fn get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
+        if self.btype == BasisType::Flow {
+            let time = self.time.get_6d(x, y, z, w, u, v);
+            let p = self.periods;
+            if self.is_periodic() {
+                return (self.ff6d)(x, y, z, w, u, v, p, time, self.seed, self.interp);
+            }
+            let nx = (self.rotmatrix[0][0] * x) + (self.rotmatrix[1][0] * y) + (self.rotmatrix[2][0] * z);
+            let ny = (self.rotmatrix[0][1] * x) + (self.rotmatrix[1][1] * y) + (self.rotmatrix[2][1] * z);
+            let nz = (self.rotmatrix[0][2] * x) + (self.rotmatrix[1][2] * y) + (self.rotmatrix[2][2] * z);
+            return (self.ff6d)(nx, ny, nz, w, u, v, p, time, self.seed, self.interp);
+        }
+        if self.is_periodic() {
+            return (self.pf6d)(x, y, z, w, u, v, self.periods, self.seed, self.interp);
+        }
         let nx = (self.rotmatrix[0][0] * x) + (self.rotmatrix[1][0] * y) + (self.rotmatrix[2][0] * z);
         let ny = (self.rotmatrix[0][1] * x) + (self.rotmatrix[1][1] * y) + (self.rotmatrix[2][1] * z);
         let nz = (self.rotmatrix[0][2] * x) + (self.rotmatrix[1][2] * y) + (self.rotmatrix[2][2] * z);
         (self.f6d)(nx, ny, nz, w, u, v, self.seed, self.interp)
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        match p.len() {
+            1 => return self.get_1d(p[0]),
+            2 => return self.get_2d(p[0], p[1]),
+            3 => return self.get_3d(p[0], p[1], p[2]),
+            4 => return self.get_4d(p[0], p[1], p[2], p[3]),
+            6 => return self.get_6d(p[0], p[1], p[2], p[3], p[4], p[5]),
+            _ => {}
+        }
+        let (fnd, pfnd) = match (self.fnd, self.pfnd) {
+            (Some(f), Some(pf)) => (f, pf),
+            _ => return get_nd_padded(self, p),
+        };
+        if self.is_periodic() {
+            return pfnd(p, &self.periods, self.seed, self.interp);
+        }
+        let n = p.len().min(MAX_DIMENSIONS);
+        let mut q = [0.0; MAX_DIMENSIONS];
+        q[..n].copy_from_slice(&p[..n]);
+        if n >= 3 {
+            let (x, y, z) = (p[0], p[1], p[2]);
+            q[0] = (self.rotmatrix[0][0] * x) + (self.rotmatrix[1][0] * y) + (self.rotmatrix[2][0] * z);
+            q[1] = (self.rotmatrix[0][1] * x) + (self.rotmatrix[1][1] * y) + (self.rotmatrix[2][1] * z);
+            q[2] = (self.rotmatrix[0][2] * x) + (self.rotmatrix[1][2] * y) + (self.rotmatrix[2][2] * z);
+        }
+        fnd(&q[..n], self.seed, self.interp)
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -257,4 +648,38 @@ impl ImplicitModule for ImplicitBasisFunction {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn get_analytic_gradient_2(&mut self, x: f64, y: f64) -> Option<[f64; 2]> {
+        let g2d = match self.g2d {
+            Some(g) => g,
+            None => return self.flow_gradient_2d(x, y),
+        };
+        if self.is_periodic() {
+            let p = &self.periods;
+            return Some(g2d(x, y, [p[0], p[1]], self.seed, self.interp, self.interp_deriv));
+        }
+        let nx = x * self.cos2d - y * self.sin2d;
+        let ny = y * self.cos2d + x * self.sin2d;
+        let g = g2d(nx, ny, [0; 2], self.seed, self.interp, self.interp_deriv);
+        // Rotate the gradient back into the input frame.
+        Some([g[0] * self.cos2d + g[1] * self.sin2d, g[1] * self.cos2d - g[0] * self.sin2d])
+    }
+    fn get_analytic_gradient_3(&mut self, x: f64, y: f64, z: f64) -> Option<[f64; 3]> {
+        let g3d = match self.g3d {
+            Some(g) => g,
+            None => return self.flow_gradient_3d(x, y, z),
+        };
+        if self.is_periodic() {
+            let p = &self.periods;
+            return Some(g3d(x, y, z, [p[0], p[1], p[2]], self.seed, self.interp, self.interp_deriv));
+        }
+        let nx = (self.rotmatrix[0][0] * x) + (self.rotmatrix[1][0] * y) + (self.rotmatrix[2][0] * z);
+        let ny = (self.rotmatrix[0][1] * x) + (self.rotmatrix[1][1] * y) + (self.rotmatrix[2][1] * z);
+        let nz = (self.rotmatrix[0][2] * x) + (self.rotmatrix[1][2] * y) + (self.rotmatrix[2][2] * z);
+        let g = g3d(nx, ny, nz, [0; 3], self.seed, self.interp, self.interp_deriv);
+        let r = &self.rotmatrix;
+        Some([r[0][0] * g[0] + r[0][1] * g[1] + r[0][2] * g[2],
+              r[1][0] * g[0] + r[1][1] * g[1] + r[1][2] * g[2],
+              r[2][0] * g[0] + r[2][1] * g[1] + r[2][2] * g[2]])
+    }
 }
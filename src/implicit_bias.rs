@@ -39,6 +39,10 @@ impl ImplicitBias {
 impl ImplicitModule for ImplicitBias {
     fn set_seed(&mut self, _: u32) {}
 
+    fn get_1d(&mut self, x: f64) -> f64 {
+        let va = self.source.get_1d(x);
+        bias(self.bias.get_1d(x), va)
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         let va = self.source.get_2d(x, y);
         bias(self.bias.get_2d(x, y), va)
@@ -55,6 +59,10 @@ impl ImplicitModule for ImplicitBias {
         let va = self.source.get_6d(x, y, z, w, u, v);
         bias(self.bias.get_6d(x, y, z, w, u, v), va)
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        let va = self.source.get_nd(p);
+        bias(self.bias.get_nd(p), va)
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -63,4 +71,8 @@ impl ImplicitModule for ImplicitBias {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
 }
@@ -49,6 +49,12 @@ impl ImplicitBlend {
 impl ImplicitModule for ImplicitBlend {
     fn set_seed(&mut self, _: u32) {}
 
+    fn get_1d(&mut self, x: f64) -> f64 {
+        let v1 = self.low.get_1d(x);
+        let v2 = self.high.get_1d(x);
+        let blend = self.control.get_1d(x);
+        lerp(blend, v1, v2)
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         let v1 = self.low.get_2d(x, y);
         let v2 = self.high.get_2d(x, y);
@@ -74,6 +80,12 @@ impl ImplicitModule for ImplicitBlend {
         let blend = self.control.get_6d(x, y, z, w, u, v);
         lerp(blend, v1, v2)
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        let v1 = self.low.get_nd(p);
+        let v2 = self.high.get_nd(p);
+        let blend = self.control.get_nd(p);
+        lerp(blend, v1, v2)
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -82,4 +94,9 @@ impl ImplicitModule for ImplicitBlend {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.low.set_footprint(f);
+        self.high.set_footprint(f);
+    }
 }
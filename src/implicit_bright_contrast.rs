@@ -55,6 +55,18 @@ impl ImplicitBrightContrast {
 }
 
 impl ImplicitModule for ImplicitBrightContrast {
+    fn get_1d(&mut self, x: f64) -> f64 {
+        let mut c = self.source.get_1d(x);
+        // Apply brightness
+        c += self.bright.get_1d(x);
+
+        // Subtract threshold, scale by factor, add threshold
+        let threshold = self.threshold.get_1d(x);
+        c -= threshold;
+        c *= self.factor.get_1d(x);
+        c += threshold;
+        c
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         let mut v = self.source.get_2d(x, y);
         // Apply brightness
@@ -103,6 +115,18 @@ impl ImplicitModule for ImplicitBrightContrast {
         c += threshold;
         c
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        let mut c = self.source.get_nd(p);
+        // Apply brightness
+        c += self.bright.get_nd(p);
+
+        // Subtract threshold, scale by factor, add threshold
+        let threshold = self.threshold.get_nd(p);
+        c -= threshold;
+        c *= self.factor.get_nd(p);
+        c += threshold;
+        c
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -110,4 +134,8 @@ impl ImplicitModule for ImplicitBrightContrast {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
 }
@@ -0,0 +1,106 @@
+use super::implicit_base::ImplicitModuleBase;
+use super::ImplicitModule;
+use super::buffer_sampler::{BufferSampler, flatten_2d, flatten_3d};
+
+/// The filtering of the texels of a buffer between their centres.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BufferFilter {
+    /// The value of the nearest texel. Blocky, but keeps masks and ids crisp.
+    Nearest,
+    /// Linear interpolation between the two nearest texels along each axis.
+    Bilinear,
+    /// Catmull-Rom interpolation between the four nearest texels along each axis. Smoother than bilinear filtering, with a continuous gradient, but it may overshoot the texels a little near sharp edges.
+    Bicubic,
+}
+
+/// What a buffer gives outside of its domain.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WrapMode {
+    /// The edge texels extend forever.
+    Clamp,
+    /// The buffer tiles the plane.
+    Repeat,
+    /// The buffer tiles the plane, flipped every other tile, so that its edges meet seamlessly.
+    Mirror,
+}
+
+/// Samples a 2D or 3D buffer of values, such as a painted mask, a heightmap loaded from an image, or a buffer computed by the mapping functions and processed afterwards. This brings hand-made and precomputed data into a graph: a painted mask can be the control source of an [`ImplicitSelect`](struct.ImplicitSelect.html), for instance.
+///
+/// The buffer is indexed as those of the mapping functions, `a[x][y]` or `a[x][y][z]`, must be rectangular, or the constructors panic, and covers a domain that defaults to 0..1 along each axis. The element k of n along an axis sits at `d0 + k / n * (d1 - d0)` over the domain `d0..d1`, which is where [`map_2d()`](mapping/fn.map_2d.html) and [`map_3d()`](mapping/fn.map_3d.html) sample it with [`MappingMode::SeamlessNone`](mapping/enum.MappingMode.html), so a buffer mapped over some ranges and wrapped with [`set_domain_from_ranges()`](#method.set_domain_from_ranges) gives back its own values there. Between the elements the values are filtered, by default bilinearly, and outside of the domain they wrap as set, by default clamping to the edges. The z axis of a 2D buffer, and the axes beyond z, are ignored.
+///
+/// ```
+/// use anl::{ImplicitBuffer, ImplicitSelect, ImplicitModule, BufferFilter};
+/// use std::rc::Rc;
+/// use std::cell::RefCell;
+///
+/// // A painted mask: the left half is 0, the right half 1.
+/// let mask: Vec<Vec<f64>> = (0..8).map(|x| vec![if x < 4 { 0.0 } else { 1.0 }; 8]).collect();
+/// let mut buffer = ImplicitBuffer::with_2d(&mask);
+/// buffer.set_filter(BufferFilter::Nearest);
+///
+/// let mut select = ImplicitSelect::new();
+/// select.set_low_source_value(-1.0);
+/// select.set_high_source_value(1.0);
+/// select.set_threshold_source_value(0.5);
+/// select.set_control_source_module(Rc::new(RefCell::new(buffer)));
+/// assert_eq!(select.get_2d(0.1, 0.5), -1.0);
+/// assert_eq!(select.get_2d(0.9, 0.5), 1.0);
+/// ```
+pub struct ImplicitBuffer {
+    base: ImplicitModuleBase,
+    sampler: BufferSampler,
+    data: Vec<f64>,
+}
+
+impl ImplicitBuffer {
+    pub fn with_2d<Matrix: AsRef<[Row]>, Row: AsRef<[f64]>>(a: Matrix) -> ImplicitBuffer {
+        let (sampler, data) = flatten_2d("ImplicitBuffer::with_2d", a);
+        ImplicitBuffer {
+            base: Default::default(),
+            sampler,
+            data,
+        }
+    }
+
+    pub fn with_3d<Matrix: AsRef<[Row]>, Row: AsRef<[Col]>, Col: AsRef<[f64]>>(a: Matrix) -> ImplicitBuffer {
+        let (sampler, data) = flatten_3d("ImplicitBuffer::with_3d", a);
+        ImplicitBuffer {
+            base: Default::default(),
+            sampler,
+            data,
+        }
+    }
+
+    buffer_sampler_methods!();
+
+    fn sample(&self, x: f64, y: f64, z: f64) -> f64 {
+        let mut v = 0.0;
+        let data = &self.data;
+        self.sampler.taps([x, y, z], |i, w| v += data[i] * w);
+        v
+    }
+}
+
+impl ImplicitModule for ImplicitBuffer {
+    fn get_2d(&mut self, x: f64, y: f64) -> f64 {
+        let z = self.sampler.domain[2].0;
+        self.sample(x, y, z)
+    }
+    fn get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
+        self.sample(x, y, z)
+    }
+    fn get_4d(&mut self, x: f64, y: f64, z: f64, _: f64) -> f64 {
+        self.sample(x, y, z)
+    }
+    fn get_6d(&mut self, x: f64, y: f64, z: f64, _: f64, _: f64, _: f64) -> f64 {
+        self.sample(x, y, z)
+    }
+
+    fn spacing(&self) -> f64 {
+        self.base.spacing
+    }
+
+    fn set_deriv_spacing(&mut self, s: f64) {
+        self.base.spacing = s;
+    }
+}
@@ -29,14 +29,23 @@ impl Default for Cache {
     }
 }
 
+#[derive(Default)]
+struct CacheN {
+    p: Vec<f64>,
+    val: f64,
+    valid: bool,
+}
+
 // 
 pub struct ImplicitCache {
     base: ImplicitModuleBase,
     source: ScalarParameter,
+    c1: Cache,
     c2: Cache,
     c3: Cache,
     c4: Cache,
     c6: Cache,
+    cn: CacheN,
 }
 
 impl ImplicitCache {
@@ -44,10 +53,12 @@ impl ImplicitCache {
         ImplicitCache {
             base: Default::default(),
             source: source,
+            c1: Default::default(),
             c2: Default::default(),
             c3: Default::default(),
             c4: Default::default(),
             c6: Default::default(),
+            cn: Default::default(),
         }
     }
 }
@@ -55,6 +66,14 @@ impl ImplicitCache {
 impl ImplicitModule for ImplicitCache {
     fn set_seed(&mut self, _: u32) {}
 
+    fn get_1d(&mut self, x: f64) -> f64 {
+        if !self.c1.valid || self.c1.x != x {
+            self.c1.x = x;
+            self.c1.valid = true;
+            self.c1.val = self.source.get_1d(x);
+        }
+        self.c1.val
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         if !self.c2.valid || self.c2.x != x || self.c2.y != y {
             self.c2.x = x;
@@ -98,6 +117,15 @@ impl ImplicitModule for ImplicitCache {
         }
         self.c6.val
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        if !self.cn.valid || self.cn.p[..] != *p {
+            self.cn.p.clear();
+            self.cn.p.extend_from_slice(p);
+            self.cn.valid = true;
+            self.cn.val = self.source.get_nd(p);
+        }
+        self.cn.val
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -106,4 +134,15 @@ impl ImplicitModule for ImplicitCache {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+        // The cached values were sampled with the old footprint.
+        self.c1.valid = false;
+        self.c2.valid = false;
+        self.c3.valid = false;
+        self.c4.valid = false;
+        self.c6.valid = false;
+        self.cn.valid = false;
+    }
 }
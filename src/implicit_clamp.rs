@@ -38,6 +38,11 @@ impl ImplicitClamp {
 impl ImplicitModule for ImplicitClamp {
     fn set_seed(&mut self, _: u32) {}
 
+    fn get_1d(&mut self, x: f64) -> f64 {
+        clamp(self.source.borrow_mut().get_1d(x),
+              self.low,
+              self.high)
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         clamp(self.source.borrow_mut().get_2d(x, y), self.low, self.high)
     }
@@ -56,6 +61,11 @@ impl ImplicitModule for ImplicitClamp {
               self.low,
               self.high)
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        clamp(self.source.borrow_mut().get_nd(p),
+              self.low,
+              self.high)
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -64,4 +74,8 @@ impl ImplicitModule for ImplicitClamp {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.borrow_mut().set_footprint(f);
+    }
 }
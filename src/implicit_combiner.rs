@@ -54,6 +54,16 @@ impl ImplicitCombiner {
         }
     }
 
+    fn add_get_1d(&mut self, x: f64) -> f64 {
+        let mut value = 0.0;
+        for s in self.sources.iter() {
+            if let &Some(ref s) = s {
+                value += s.borrow_mut().get_1d(x);
+            }
+        }
+        value
+    }
+
     fn add_get_2d(&mut self, x: f64, y: f64) -> f64 {
         let mut value = 0.0;
         for s in self.sources.iter() {
@@ -94,6 +104,26 @@ impl ImplicitCombiner {
         value
     }
 
+    fn add_get_nd(&mut self, p: &[f64]) -> f64 {
+        let mut value = 0.0;
+        for s in self.sources.iter() {
+            if let &Some(ref s) = s {
+                value += s.borrow_mut().get_nd(p);
+            }
+        }
+        value
+    }
+
+    fn mul_get_1d(&mut self, x: f64) -> f64 {
+        let mut value = 1.0;
+        for s in self.sources.iter() {
+            if let &Some(ref s) = s {
+                value *= s.borrow_mut().get_1d(x);
+            }
+        }
+        value
+    }
+
     fn mul_get_2d(&mut self, x: f64, y: f64) -> f64 {
         let mut value = 1.0;
         for s in self.sources.iter() {
@@ -134,6 +164,51 @@ impl ImplicitCombiner {
         value
     }
 
+    fn mul_get_nd(&mut self, p: &[f64]) -> f64 {
+        let mut value = 1.0;
+        for s in self.sources.iter() {
+            if let &Some(ref s) = s {
+                value *= s.borrow_mut().get_nd(p);
+            }
+        }
+        value
+    }
+
+    fn min_get_1d(&mut self, x: f64) -> f64 {
+        let mut c = 0;
+
+        for s in self.sources.iter() {
+            if s.is_none() {
+                c += 1;
+            } else {
+                break;
+            }
+        }
+
+        if c == MAX_SOURCES {
+            return 0.0;
+        }
+
+        let mut mn = {
+            let mut b = self.sources[c].as_ref().unwrap().borrow_mut();
+            b.get_1d(x)
+        };
+
+        for d in c..MAX_SOURCES {
+            if let Some(ref s) = self.sources[d] {
+                let v = {
+                    let mut b = s.borrow_mut();
+                    b.get_1d(x)
+                };
+                if v < mn {
+                    mn = v
+                }
+            }
+        }
+
+        return mn;
+    }
+
     fn min_get_2d(&mut self, x: f64, y: f64) -> f64 {
         let mut c = 0;
 
@@ -275,6 +350,76 @@ impl ImplicitCombiner {
         return mn;
     }
 
+    fn min_get_nd(&mut self, p: &[f64]) -> f64 {
+        let mut c = 0;
+
+        for s in self.sources.iter() {
+            if s.is_none() {
+                c += 1;
+            } else {
+                break;
+            }
+        }
+
+        if c == MAX_SOURCES {
+            return 0.0;
+        }
+
+        let mut mn = {
+            let mut b = self.sources[c].as_ref().unwrap().borrow_mut();
+            b.get_nd(p)
+        };
+
+        for d in c..MAX_SOURCES {
+            if let Some(ref s) = self.sources[d] {
+                let v = {
+                    let mut b = s.borrow_mut();
+                    b.get_nd(p)
+                };
+                if v < mn {
+                    mn = v
+                }
+            }
+        }
+
+        return mn;
+    }
+
+    fn max_get_1d(&mut self, x: f64) -> f64 {
+        let mut c = 0;
+
+        for s in self.sources.iter() {
+            if s.is_none() {
+                c += 1;
+            } else {
+                break;
+            }
+        }
+
+        if c == MAX_SOURCES {
+            return 0.0;
+        }
+
+        let mut mn = {
+            let mut b = self.sources[c].as_ref().unwrap().borrow_mut();
+            b.get_1d(x)
+        };
+
+        for d in c..MAX_SOURCES {
+            if let Some(ref s) = self.sources[d] {
+                let v = {
+                    let mut b = s.borrow_mut();
+                    b.get_1d(x)
+                };
+                if v > mn {
+                    mn = v
+                }
+            }
+        }
+
+        return mn;
+    }
+
     fn max_get_2d(&mut self, x: f64, y: f64) -> f64 {
         let mut c = 0;
 
@@ -415,6 +560,56 @@ impl ImplicitCombiner {
         return mn;
     }
 
+    fn max_get_nd(&mut self, p: &[f64]) -> f64 {
+        let mut c = 0;
+
+        for s in self.sources.iter() {
+            if s.is_none() {
+                c += 1;
+            } else {
+                break;
+            }
+        }
+
+        if c == MAX_SOURCES {
+            return 0.0;
+        }
+
+        let mut mn = {
+            let mut b = self.sources[c].as_ref().unwrap().borrow_mut();
+            b.get_nd(p)
+        };
+
+        for d in c..MAX_SOURCES {
+            if let Some(ref s) = self.sources[d] {
+                let v = {
+                    let mut b = s.borrow_mut();
+                    b.get_nd(p)
+                };
+                if v > mn {
+                    mn = v
+                }
+            }
+        }
+
+        return mn;
+    }
+
+    fn avg_get_1d(&mut self, x: f64) -> f64 {
+        let mut count = 0.0;
+        let mut value = 0.0;
+        for s in self.sources.iter() {
+            if let &Some(ref s) = s {
+                value += {
+                    let mut b = s.borrow_mut();
+                    b.get_1d(x)
+                };
+                count += 1.0
+            }
+        }
+        if count == 0.0 { 0.0 } else { value / count }
+    }
+
     fn avg_get_2d(&mut self, x: f64, y: f64) -> f64 {
         let mut count = 0.0;
         let mut value = 0.0;
@@ -474,11 +669,35 @@ impl ImplicitCombiner {
         }
         if count == 0.0 { 0.0 } else { value / count }
     }
+
+    fn avg_get_nd(&mut self, p: &[f64]) -> f64 {
+        let mut count = 0.0;
+        let mut value = 0.0;
+        for s in self.sources.iter() {
+            if let &Some(ref s) = s {
+                value += {
+                    let mut b = s.borrow_mut();
+                    b.get_nd(p)
+                };
+                count += 1.0
+            }
+        }
+        if count == 0.0 { 0.0 } else { value / count }
+    }
 }
 
 impl ImplicitModule for ImplicitCombiner {
     fn set_seed(&mut self, _: u32) {}
 
+    fn get_1d(&mut self, x: f64) -> f64 {
+        match self.ctype {
+            CombinerType::Add => self.add_get_1d(x),
+            CombinerType::Mul => self.mul_get_1d(x),
+            CombinerType::Max => self.max_get_1d(x),
+            CombinerType::Min => self.min_get_1d(x),
+            CombinerType::Avg => self.avg_get_1d(x),
+        }
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         match self.ctype {
             CombinerType::Add => self.add_get_2d(x, y),
@@ -515,6 +734,15 @@ impl ImplicitModule for ImplicitCombiner {
             CombinerType::Avg => self.avg_get_6d(x, y, z, w, u, v),
         }
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        match self.ctype {
+            CombinerType::Add => self.add_get_nd(p),
+            CombinerType::Mul => self.mul_get_nd(p),
+            CombinerType::Max => self.max_get_nd(p),
+            CombinerType::Min => self.min_get_nd(p),
+            CombinerType::Avg => self.avg_get_nd(p),
+        }
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -523,4 +751,10 @@ impl ImplicitModule for ImplicitCombiner {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        for s in self.sources.iter().flatten() {
+            s.borrow_mut().set_footprint(f);
+        }
+    }
 }
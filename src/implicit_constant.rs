@@ -38,6 +38,13 @@ impl ImplicitModule for ImplicitConstant {
         self.constant
     }
 
+    fn get_analytic_gradient_2(&mut self, _: f64, _: f64) -> Option<[f64; 2]> {
+        Some([0.0; 2])
+    }
+    fn get_analytic_gradient_3(&mut self, _: f64, _: f64, _: f64) -> Option<[f64; 3]> {
+        Some([0.0; 3])
+    }
+
     fn spacing(&self) -> f64 {
         self.base.spacing
     }
@@ -29,6 +29,9 @@ impl ImplicitCos {
 }
 
 impl ImplicitModule for ImplicitCos {
+    fn get_1d(&mut self, x: f64) -> f64 {
+        self.source.get_1d(x).cos()
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         self.source.get_2d(x, y).cos()
     }
@@ -41,6 +44,9 @@ impl ImplicitModule for ImplicitCos {
     fn get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
         self.source.get_6d(x, y, z, w, u, v).cos()
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        self.source.get_nd(p).cos()
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -48,4 +54,8 @@ impl ImplicitModule for ImplicitCos {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
 }
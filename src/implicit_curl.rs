@@ -0,0 +1,188 @@
+use super::implicit_base::ImplicitModuleBase;
+use super::ImplicitModule;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+/// The component of the curl vector that [`ImplicitCurl`](struct.ImplicitCurl.html) returns when it is used as an implicit source.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CurlComponent {
+    X,
+    Y,
+    Z,
+}
+
+/// Curl computes the curl of a vector potential built from other functions, after Bridson et al., "Curl-Noise for Procedural Fluid Flow". The curl of any field is divergence-free, so feeding noise in as the potential gives a turbulent velocity field that neither sources nor sinks particles, suitable for driving particles and smoke.
+///
+/// In 2D the potential is the single scalar function set by [`set_potential_module()`](struct.ImplicitCurl.html#method.set_potential_module) (which is the z component of the vector potential), and the curl is (dψ/dy, -dψ/dx). In 3D the potential has three components, one function each, set by `set_x_potential_module()` and friends; uncorrelated sources, such as the same noise with different seeds, work best. The 4D and 6D variants differentiate only along x, y and z, so the extra coordinates can animate the field. A potential that is not set counts as zero.
+///
+/// Derivatives are taken analytically when the potential supports it (see [`ImplicitModule::get_analytic_gradient_2()`](trait.ImplicitModule.html#method.get_analytic_gradient_2)), and by central differences over the derivative spacing otherwise. The full vector is returned by `curl_2d()` through `curl_6d()`; when used as an implicit source, the module returns the component selected with [`set_component()`](struct.ImplicitCurl.html#method.set_component).
+pub struct ImplicitCurl {
+    base: ImplicitModuleBase,
+    potential: [Option<Rc<RefCell<ImplicitModule>>>; 3],
+    component: CurlComponent,
+}
+
+impl ImplicitCurl {
+    pub fn new() -> ImplicitCurl {
+        ImplicitCurl::with_component(CurlComponent::X)
+    }
+
+    pub fn with_component(component: CurlComponent) -> ImplicitCurl {
+        ImplicitCurl {
+            base: Default::default(),
+            potential: [None, None, None],
+            component,
+        }
+    }
+
+    pub fn set_component(&mut self, component: CurlComponent) {
+        self.component = component;
+    }
+
+    /// Sets the potential used by the 2D curl. This is the same as the z potential.
+    pub fn set_potential_module(&mut self, m: Rc<RefCell<ImplicitModule>>) {
+        self.potential[2] = Some(m);
+    }
+
+    pub fn set_x_potential_module(&mut self, m: Rc<RefCell<ImplicitModule>>) {
+        self.potential[0] = Some(m);
+    }
+    pub fn set_y_potential_module(&mut self, m: Rc<RefCell<ImplicitModule>>) {
+        self.potential[1] = Some(m);
+    }
+    pub fn set_z_potential_module(&mut self, m: Rc<RefCell<ImplicitModule>>) {
+        self.potential[2] = Some(m);
+    }
+
+    fn gradient_2(&self, which: usize, x: f64, y: f64) -> [f64; 2] {
+        match self.potential[which] {
+            None => [0.0; 2],
+            Some(ref p) => {
+                let mut p = p.borrow_mut();
+                if let Some(g) = p.get_analytic_gradient_2(x, y) {
+                    return g;
+                }
+                let h = self.base.spacing;
+                [(p.get_2d(x + h, y) - p.get_2d(x - h, y)) / (2.0 * h),
+                 (p.get_2d(x, y + h) - p.get_2d(x, y - h)) / (2.0 * h)]
+            }
+        }
+    }
+
+    fn gradient_3(&self, which: usize, x: f64, y: f64, z: f64) -> [f64; 3] {
+        match self.potential[which] {
+            None => [0.0; 3],
+            Some(ref p) => {
+                let mut p = p.borrow_mut();
+                if let Some(g) = p.get_analytic_gradient_3(x, y, z) {
+                    return g;
+                }
+                let h = self.base.spacing;
+                [(p.get_3d(x + h, y, z) - p.get_3d(x - h, y, z)) / (2.0 * h),
+                 (p.get_3d(x, y + h, z) - p.get_3d(x, y - h, z)) / (2.0 * h),
+                 (p.get_3d(x, y, z + h) - p.get_3d(x, y, z - h)) / (2.0 * h)]
+            }
+        }
+    }
+
+    fn gradient_4(&self, which: usize, x: f64, y: f64, z: f64, w: f64) -> [f64; 3] {
+        match self.potential[which] {
+            None => [0.0; 3],
+            Some(ref p) => {
+                let mut p = p.borrow_mut();
+                let h = self.base.spacing;
+                [(p.get_4d(x + h, y, z, w) - p.get_4d(x - h, y, z, w)) / (2.0 * h),
+                 (p.get_4d(x, y + h, z, w) - p.get_4d(x, y - h, z, w)) / (2.0 * h),
+                 (p.get_4d(x, y, z + h, w) - p.get_4d(x, y, z - h, w)) / (2.0 * h)]
+            }
+        }
+    }
+
+    fn gradient_6(&self, which: usize, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> [f64; 3] {
+        match self.potential[which] {
+            None => [0.0; 3],
+            Some(ref p) => {
+                let mut p = p.borrow_mut();
+                let h = self.base.spacing;
+                [(p.get_6d(x + h, y, z, w, u, v) - p.get_6d(x - h, y, z, w, u, v)) / (2.0 * h),
+                 (p.get_6d(x, y + h, z, w, u, v) - p.get_6d(x, y - h, z, w, u, v)) / (2.0 * h),
+                 (p.get_6d(x, y, z + h, w, u, v) - p.get_6d(x, y, z - h, w, u, v)) / (2.0 * h)]
+            }
+        }
+    }
+
+    pub fn curl_2d(&mut self, x: f64, y: f64) -> [f64; 2] {
+        let g = self.gradient_2(2, x, y);
+        [g[1], -g[0]]
+    }
+
+    pub fn curl_3d(&mut self, x: f64, y: f64, z: f64) -> [f64; 3] {
+        let gx = self.gradient_3(0, x, y, z);
+        let gy = self.gradient_3(1, x, y, z);
+        let gz = self.gradient_3(2, x, y, z);
+        curl(&gx, &gy, &gz)
+    }
+
+    pub fn curl_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> [f64; 3] {
+        let gx = self.gradient_4(0, x, y, z, w);
+        let gy = self.gradient_4(1, x, y, z, w);
+        let gz = self.gradient_4(2, x, y, z, w);
+        curl(&gx, &gy, &gz)
+    }
+
+    pub fn curl_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> [f64; 3] {
+        let gx = self.gradient_6(0, x, y, z, w, u, v);
+        let gy = self.gradient_6(1, x, y, z, w, u, v);
+        let gz = self.gradient_6(2, x, y, z, w, u, v);
+        curl(&gx, &gy, &gz)
+    }
+
+    fn select(&self, c: [f64; 3]) -> f64 {
+        match self.component {
+            CurlComponent::X => c[0],
+            CurlComponent::Y => c[1],
+            CurlComponent::Z => c[2],
+        }
+    }
+}
+
+// Curl of the potential whose components have the gradients gx, gy and gz.
+fn curl(gx: &[f64; 3], gy: &[f64; 3], gz: &[f64; 3]) -> [f64; 3] {
+    [gz[1] - gy[2], gx[2] - gz[0], gy[0] - gx[1]]
+}
+
+impl ImplicitModule for ImplicitCurl {
+    fn set_seed(&mut self, _: u32) {}
+
+    fn get_2d(&mut self, x: f64, y: f64) -> f64 {
+        let c = self.curl_2d(x, y);
+        self.select([c[0], c[1], 0.0])
+    }
+    fn get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
+        let c = self.curl_3d(x, y, z);
+        self.select(c)
+    }
+    fn get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
+        let c = self.curl_4d(x, y, z, w);
+        self.select(c)
+    }
+    fn get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
+        let c = self.curl_6d(x, y, z, w, u, v);
+        self.select(c)
+    }
+
+    fn spacing(&self) -> f64 {
+        self.base.spacing
+    }
+
+    fn set_deriv_spacing(&mut self, s: f64) {
+        self.base.spacing = s;
+    }
+
+    fn set_footprint(&mut self, f: f64) {
+        for p in self.potential.iter().flatten() {
+            p.borrow_mut().set_footprint(f);
+        }
+    }
+}
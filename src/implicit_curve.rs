@@ -61,6 +61,10 @@ impl ImplicitCurve {
 }
 
 impl ImplicitModule for ImplicitCurve {
+    fn get_1d(&mut self, x: f64) -> f64 {
+        let t = self.source.get_1d(x);
+        self.get(t)
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         let t = self.source.get_2d(x, y);
         self.get(t)
@@ -77,6 +81,10 @@ impl ImplicitModule for ImplicitCurve {
         let t = self.source.get_6d(x, y, z, w, u, v);
         self.get(t)
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        let t = self.source.get_nd(p);
+        self.get(t)
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -84,4 +92,8 @@ impl ImplicitModule for ImplicitCurve {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
 }
@@ -0,0 +1,164 @@
+use super::implicit_base::{ImplicitModuleBase, ScalarParameter};
+use super::ImplicitModule;
+use super::noise_gen::MAX_DIMENSIONS;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+// Offsets added to the sampling position of the warp source for each axis, so that axes sharing a source do not move
+// in lockstep. The rows are per axis; each warp level shifts them further by its own multiple of WARP_LEVEL_OFFSET.
+const WARP_AXIS_OFFSETS: [[f64; MAX_DIMENSIONS]; MAX_DIMENSIONS] = [
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [5.2, 1.3, 7.7, 3.1, 9.4, 2.6, 12.1, 6.8],
+    [11.9, 8.3, 2.8, 14.5, 6.2, 10.7, 4.4, 16.9],
+    [3.7, 15.1, 12.6, 5.9, 1.8, 13.3, 18.2, 9.6],
+    [17.4, 4.6, 9.9, 19.2, 13.8, 6.5, 2.3, 11.4],
+    [8.1, 19.7, 16.3, 10.4, 20.9, 4.2, 14.7, 1.5],
+    [14.2, 7.3, 21.5, 1.1, 11.6, 18.8, 5.7, 20.3],
+    [20.6, 12.9, 5.4, 16.7, 3.9, 8.8, 19.5, 7.2],
+];
+const WARP_LEVEL_OFFSET: [f64; MAX_DIMENSIONS] = [23.17, 31.43, 27.61, 19.89, 29.37, 25.53, 21.71, 33.19];
+
+/// DomainWarp displaces the input coordinates of a function by the output of other functions, as in the common `f(p + k * q(p))` construction. Each axis is displaced by one of the warp sources: axis k uses source `k % N`, where N is the number of sources added with [`add_warp_source()`](struct.ImplicitDomainWarp.html#method.add_warp_source), so a single fractal can drive every axis. The sampling position of every axis is offset by a fixed amount, which decorrelates the axes even when they share a source. The displacement is multiplied by the `strength`, which can be a constant or another function.
+///
+/// With more than one iteration the warp is nested: the displacement is computed at the already displaced position, as in `f(p + k * q(p + k * r(p)))`, with fresh offsets at every level. Each level costs one evaluation of the warp sources per axis. Unlike [`TranslateDomain`](struct.ImplicitTranslateDomain.html), which needs a source per axis, this module takes any number of sources and works the same way in 1D, 2D, 3D, 4D and 6D, and in any dimension up to [`MAX_DIMENSIONS`](noise_gen/constant.MAX_DIMENSIONS.html) through [`get_nd()`](trait.ImplicitModule.html#method.get_nd).
+pub struct ImplicitDomainWarp {
+    base: ImplicitModuleBase,
+    source: ScalarParameter,
+    warp: Vec<Rc<RefCell<ImplicitModule>>>,
+    strength: ScalarParameter,
+    iterations: u32,
+}
+
+impl ImplicitDomainWarp {
+    pub fn new() -> ImplicitDomainWarp {
+        ImplicitDomainWarp::with_strength(1.0)
+    }
+
+    pub fn with_strength(strength: f64) -> ImplicitDomainWarp {
+        ImplicitDomainWarp {
+            base: Default::default(),
+            source: ScalarParameter::Value(0.0),
+            warp: Vec::new(),
+            strength: ScalarParameter::Value(strength),
+            iterations: 1,
+        }
+    }
+
+    pub fn set_source_module(&mut self, m: Rc<RefCell<ImplicitModule>>) {
+        self.source = ScalarParameter::Source(m);
+    }
+
+    pub fn set_source_value(&mut self, v: f64) {
+        self.source = ScalarParameter::Value(v);
+    }
+
+    /// Adds a displacement source. Sources are assigned to the axes in the order they were added, wrapping around.
+    pub fn add_warp_source(&mut self, m: Rc<RefCell<ImplicitModule>>) {
+        self.warp.push(m);
+    }
+
+    pub fn clear_warp_sources(&mut self) {
+        self.warp.clear();
+    }
+
+    pub fn set_strength_module(&mut self, m: Rc<RefCell<ImplicitModule>>) {
+        self.strength = ScalarParameter::Source(m);
+    }
+
+    pub fn set_strength_value(&mut self, v: f64) {
+        self.strength = ScalarParameter::Value(v);
+    }
+
+    /// Sets the number of nested warp levels. 0 turns the warp off.
+    pub fn set_iterations(&mut self, n: u32) {
+        self.iterations = n;
+    }
+
+    fn warp(&mut self, p: &[f64]) -> [f64; MAX_DIMENSIONS] {
+        let n = p.len();
+        let mut q = [0.0; MAX_DIMENSIONS];
+        q[..n].copy_from_slice(p);
+        if self.warp.is_empty() {
+            return q;
+        }
+
+        let strength = match n {
+            1 => self.strength.get_1d(p[0]),
+            2 => self.strength.get_2d(p[0], p[1]),
+            3 => self.strength.get_3d(p[0], p[1], p[2]),
+            4 => self.strength.get_4d(p[0], p[1], p[2], p[3]),
+            6 => self.strength.get_6d(p[0], p[1], p[2], p[3], p[4], p[5]),
+            _ => self.strength.get_nd(p),
+        };
+
+        let mut s = [0.0; MAX_DIMENSIONS];
+        for level in 0..self.iterations {
+            let mut d = [0.0; MAX_DIMENSIONS];
+            for (axis, da) in d.iter_mut().enumerate().take(n) {
+                for k in 0..n {
+                    s[k] = q[k] + WARP_AXIS_OFFSETS[axis][k] + WARP_LEVEL_OFFSET[k] * level as f64;
+                }
+                let mut m = self.warp[axis % self.warp.len()].borrow_mut();
+                *da = match n {
+                    1 => m.get_1d(s[0]),
+                    2 => m.get_2d(s[0], s[1]),
+                    3 => m.get_3d(s[0], s[1], s[2]),
+                    4 => m.get_4d(s[0], s[1], s[2], s[3]),
+                    6 => m.get_6d(s[0], s[1], s[2], s[3], s[4], s[5]),
+                    _ => m.get_nd(&s[..n]),
+                };
+            }
+            for k in 0..n {
+                q[k] = p[k] + strength * d[k];
+            }
+        }
+        q
+    }
+}
+
+impl ImplicitModule for ImplicitDomainWarp {
+    fn set_seed(&mut self, _: u32) {}
+
+    fn get_1d(&mut self, x: f64) -> f64 {
+        let q = self.warp(&[x]);
+        self.source.get_1d(q[0])
+    }
+    fn get_2d(&mut self, x: f64, y: f64) -> f64 {
+        let q = self.warp(&[x, y]);
+        self.source.get_2d(q[0], q[1])
+    }
+    fn get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
+        let q = self.warp(&[x, y, z]);
+        self.source.get_3d(q[0], q[1], q[2])
+    }
+    fn get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
+        let q = self.warp(&[x, y, z, w]);
+        self.source.get_4d(q[0], q[1], q[2], q[3])
+    }
+    fn get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
+        let q = self.warp(&[x, y, z, w, u, v]);
+        self.source.get_6d(q[0], q[1], q[2], q[3], q[4], q[5])
+    }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        let n = p.len().min(MAX_DIMENSIONS);
+        let q = self.warp(&p[..n]);
+        self.source.get_nd(&q[..n])
+    }
+
+    fn spacing(&self) -> f64 {
+        self.base.spacing
+    }
+
+    fn set_deriv_spacing(&mut self, s: f64) {
+        self.base.spacing = s;
+    }
+
+    // The warp sources are sampled in the same domain as the source, so they get the same footprint.
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+        for m in self.warp.iter() {
+            m.borrow_mut().set_footprint(f);
+        }
+    }
+}
@@ -60,4 +60,8 @@ impl ImplicitModule for ImplicitExtractRgbaChannel {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
 }
@@ -26,6 +26,9 @@ impl ImplicitFloor {
 }
 
 impl ImplicitModule for ImplicitFloor {
+    fn get_1d(&mut self, x: f64) -> f64 {
+        self.source.get_1d(x).floor()
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         self.source.get_2d(x, y).floor()
     }
@@ -38,6 +41,9 @@ impl ImplicitModule for ImplicitFloor {
     fn get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
         self.source.get_6d(x, y, z, w, u, v).floor()
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        self.source.get_nd(p).floor()
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -45,4 +51,8 @@ impl ImplicitModule for ImplicitFloor {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
 }
\ No newline at end of file
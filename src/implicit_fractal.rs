@@ -4,6 +4,7 @@ use super::implicit_base::*;
 use super::ImplicitModule;
 use super::implicit_basis_function::*;
 use super::utility::{min, max};
+use super::noise_gen::MAX_DIMENSIONS;
 
 use std::rc::Rc;
 use std::cell::RefCell;
@@ -15,75 +16,237 @@ pub enum FractalType {
     Multi,
     HybridMulti,
     DecarpentierSwiss,
+    Erosion,
 }
 
-/// Fractals are a special type of combiner that combine up to 20 noise sources using fractal methods pioneered by Perlin, Musgrave, and friends. They come in various types (specifiable through [`set_type()`](struct.ImplicitFractal.html#set_type) or in the constructor). Each fractal has up to 20 built-in [`BasisFunction`](struct.ImplicitBasisFunction.html)s whose basistype and interptype can be set via the provided methods. Additionally, you can obtain a pointer to any source of the fractal via [`get_basis()`](struct.ImplicitFractal.html#get_basis). Any source module in the fractal may also be overridden by an external noise function via overrideSource(). The complexity of this system serves a purpose: "generic" fractals of a given type, with basis functions of all the same type, can easily be instance just by construction, yet more complex behavior can be produced by overriding layers with external sources, to build up very complex fractals, if so desired.
+/// Fractals are a special type of combiner that combine any number of noise sources using fractal methods pioneered by Perlin, Musgrave, and friends. They come in various types (specifiable through [`set_type()`](struct.ImplicitFractal.html#set_type) or in the constructor). The octaves share one built-in [`BasisFunction`](struct.ImplicitBasisFunction.html), whose basistype and interptype can be set via the provided methods, until the fractal is made periodic with [`set_periods()`](struct.ImplicitFractal.html#method.set_periods), which gives every octave a basis function of its own. Additionally, you can obtain a pointer to any source of the fractal via [`get_basis()`](struct.ImplicitFractal.html#get_basis). Any source module in the fractal may also be overridden by an external noise function via overrideSource(). The complexity of this system serves a purpose: "generic" fractals of a given type, with basis functions of all the same type, can easily be instance just by construction, yet more complex behavior can be produced by overriding layers with external sources, to build up very complex fractals, if so desired.
 /// Fractals are highly parameterized. The two most useful parameters are `numoctaves` which determines how many layers contribute to the fractal, and `frequency` which specifies the density of the function. Frequency mimics combining a [`ScaleDomain`](struct.ImplicitScaleDomain.html) function to the source, multiplying the input coordinates by frequency before calculating the function. Other parameters that control the fractal are `offset`, `lacunarity`, `gain` and `H`. These parameters can have subtle, drastic, or no effect on the fractal, depending on the type, and they are typically best left alone. 
+///
+/// The number of octaves can be fractional (see [`set_fractional_octaves()`](struct.ImplicitFractal.html#method.set_fractional_octaves)), in which case the last octave is blended in by the fractional part, so that animating the octave count for level of detail does not make detail pop in. The amplitude, frequency and rotation of each octave can also be set explicitly, overriding the values derived from `gain`, `H`, `frequency` and `lacunarity`.
+///
+/// The fractal is band-limited to the footprint passed through [`set_footprint()`](trait.ImplicitModule.html#method.set_footprint): octaves are faded out as their frequency goes from a quarter to half a cycle per footprint, and the octaves above that are not evaluated at all, so that distant or minified renderings do not shimmer. Sources set with [`override_source()`](#method.override_source) receive the footprint too, scaled by the frequency of their octave. The mapping functions set the footprint to the size of a pixel.
+///
+/// The `Erosion` type is an FBM whose octaves are dampened by the slope of the octaves up to them, after Inigo Quilez: each octave is divided by `1 + offset * |d|^2`, where `d` is the sum of the gradients of that octave and the coarser ones. Detail is smoothed away on steep slopes and kept in the valleys and on the flat tops, which gives more natural looking mountains than plain FBM. The `offset` sets the strength of the dampening (1 by default, and 0 gives plain FBM). The gradients are analytic for 2D and 3D basis functions that support them and are taken by finite differences, at the derivative spacing, otherwise.
 #[allow(non_snake_case)]
 pub struct ImplicitFractal {
     base: ImplicitModuleBase,
-    basis: [Rc<RefCell<ImplicitBasisFunction>>; MAX_SOURCES],
-    source: [Option<Rc<RefCell<ImplicitModule>>>; MAX_SOURCES],
-    exparray: [f64; MAX_SOURCES],
-    correct: [[f64; 2]; MAX_SOURCES],
+    basis: Vec<Rc<RefCell<ImplicitBasisFunction>>>,
+    source: Vec<Option<Rc<RefCell<ImplicitModule>>>>,
+    exparray: Vec<f64>,
+    correct: Vec<[f64; 2]>,
     offset: f64,
     gain: f64,
     H: f64,
     frequency: f64,
     lacunarity: f64,
-    numoctaves: u32,
+    octaves: f64,
+    amplitudes: Vec<f64>,
+    frequencies: Vec<f64>,
+    rotations: Vec<f64>,
+    periods: Vec<u32>,
+    shared_basis: bool,
+    advection: f64,
+    footprint: f64,
+    basis_type: BasisType,
+    interp_type: InterpType,
+    hash: HashType,
+    seed: Option<u32>,
+    flow_time: Option<ScalarParameter>,
     ftype: FractalType,
 }
 
 impl Default for ImplicitFractal {
     fn default() -> Self {
-        let mut f = ImplicitFractal {
+        // The octaves share one basis, as in the original library, until periods are set (see unshare_basis()).
+        let imp = Rc::new(RefCell::new(ImplicitBasisFunction::default()));
+
+        ImplicitFractal {
             base: Default::default(),
-            basis: unsafe { ::std::mem::uninitialized() },
-            source: unsafe { ::std::mem::uninitialized() },
-            exparray: [0.0; MAX_SOURCES],
-            correct: [[0.0; 2]; MAX_SOURCES],
+            basis: vec![imp; MAX_SOURCES],
+            source: vec![None; MAX_SOURCES],
+            exparray: vec![0.0; MAX_SOURCES],
+            correct: vec![[0.0; 2]; MAX_SOURCES],
             offset: 0.0,
             gain: 0.0,
             H: 0.0,
             frequency: 0.0,
             lacunarity: 0.0,
-            numoctaves: 0,
+            octaves: 0.0,
+            amplitudes: Vec::new(),
+            frequencies: Vec::new(),
+            rotations: Vec::new(),
+            periods: Vec::new(),
+            shared_basis: true,
+            advection: 0.0,
+            footprint: 0.0,
+            basis_type: BasisType::Gradient,
+            interp_type: InterpType::Quintic,
+            hash: HashType::Classic,
+            seed: None,
+            flow_time: None,
             ftype: FractalType::FBM,
-        };
-
-        let imp = Rc::new(RefCell::new(ImplicitBasisFunction::default()));
-
-        for s in f.basis.iter_mut() {
-            unsafe { ::std::ptr::write(s, imp.clone()) }
         }
-
-        for s in f.source.iter_mut() {
-            unsafe {
-                ::std::ptr::write(s, None);
-            }
-        }
-
-        f
     }
 }
 
 #[allow(unused_assignments, non_snake_case)]
 impl ImplicitFractal {
     pub fn set_num_octaves(&mut self, n: u32) {
-        self.numoctaves = if n >= MAX_SOURCES as u32 {
-            (MAX_SOURCES - 1) as u32
-        } else {
-            n
+        self.set_fractional_octaves(n as f64);
+    }
+
+    /// Sets a possibly fractional number of octaves. The octaves below the integer part contribute fully, and the
+    /// next one is weighted by the fractional part, so that the fractal changes continuously with the count. For the
+    /// types whose output is rescaled to [-1,1], the rescaling is blended in the same way.
+    pub fn set_fractional_octaves(&mut self, n: f64) {
+        self.octaves = if n > 0.0 { n } else { 0.0 };
+        let count = self.octaves.ceil() as usize;
+        self.ensure_sources(count);
+    }
+
+    /// Sets the amplitude of each octave, overriding the weight that the fractal type derives from `gain` or `H`.
+    /// Octaves beyond the end of the slice keep their derived weight; an empty slice removes the overrides. The types
+    /// that remap their output to [-1,1] include the overrides in the rescaling.
+    pub fn set_octave_amplitudes(&mut self, a: &[f64]) {
+        self.amplitudes = a.to_vec();
+        self.calc_weights();
+    }
+
+    /// Sets the frequency of each octave, overriding `frequency * lacunarity^i`. Octaves beyond the end of the slice
+    /// keep their derived frequency; an empty slice removes the overrides. The types weighted by `H` derive the
+    /// amplitude of an overridden octave from its frequency, `(f / frequency)^-H`, and rescale their output to match.
+    /// Periodic basis functions only repeat if the overrides follow the lacunarity.
+    pub fn set_octave_frequencies(&mut self, f: &[f64]) {
+        self.frequencies = f.to_vec();
+        self.calc_weights();
+        if self.footprint > 0.0 {
+            self.forward_footprint();
         }
     }
 
+    /// Sets a rotation angle, in radians, of each octave's coordinates in the XY plane. Rotating the octaves against
+    /// each other hides the lattice alignment of their sources. Octaves beyond the end of the slice are not rotated;
+    /// an empty slice removes the rotations.
+    pub fn set_octave_rotations(&mut self, r: &[f64]) {
+        self.rotations = r.to_vec();
+    }
+
     pub fn set_frequency(&mut self, f: f64) {
         self.frequency = f;
+        if self.footprint > 0.0 {
+            self.forward_footprint();
+        }
     }
 
     pub fn set_lacunarity(&mut self, l: f64) {
-        self.lacunarity = l
+        self.lacunarity = l;
+        if self.footprint > 0.0 {
+            self.forward_footprint();
+        }
+        if !self.periods.is_empty() {
+            self.update_periods();
+        }
+    }
+
+    /// Makes the built-in basis functions periodic (see [`ImplicitBasisFunction::set_periods()`](struct.ImplicitBasisFunction.html#method.set_periods)). The period of each octave is scaled by the lacunarity, so with an integer lacunarity every octave repeats at the same size and the fractal tiles every `periods[k] / frequency` units along axis k. An empty slice turns periodicity off.
+    ///
+    /// Until the periods are first set, the octaves share a single basis function, and what is set on it through [`get_basis()`](#method.get_basis) or [`set_source_type()`](#method.set_source_type) applies to all of them. Setting the periods gives each octave a copy of it, so those settings are kept:
+    ///
+    /// ```
+    /// use anl::{ImplicitFractal, FractalType, BasisType, InterpType, ImplicitModule};
+    ///
+    /// let mut a = ImplicitFractal::new(FractalType::FBM, BasisType::Gradient, InterpType::Quintic);
+    /// a.set_source_type(0, BasisType::Value, InterpType::Linear);
+    /// a.set_seed(3);
+    /// a.set_periods(&[4, 4]);
+    ///
+    /// let mut b = ImplicitFractal::new(FractalType::FBM, BasisType::Value, InterpType::Linear);
+    /// b.set_seed(3);
+    /// b.set_periods(&[4, 4]);
+    /// assert_eq!(a.get_2d(0.3, 0.7), b.get_2d(0.3, 0.7));
+    /// ```
+    pub fn set_periods(&mut self, periods: &[u32]) {
+        self.periods = periods.to_vec();
+        if !self.periods.is_empty() && self.shared_basis {
+            self.unshare_basis();
+        }
+        self.update_periods();
+    }
+
+    // Gives every octave a basis function of its own, a copy of the shared one, so that each octave can have its own
+    // period while keeping whatever was set on the shared basis. The octaves whose source was the shared basis are
+    // pointed at their new one. This changes the output, since the octaves are then seeded apart.
+    fn unshare_basis(&mut self) {
+        let shared = self.basis[0].clone();
+        for c in 0..self.basis.len() {
+            let mut b = shared.borrow().clone();
+            if let Some(seed) = self.seed {
+                b.set_seed(seed + c as u32 * 300);
+            }
+            let b = Rc::new(RefCell::new(b));
+            let uses_shared = match self.source[c] {
+                Some(ref s) => Rc::as_ptr(s) as *const u8 == Rc::as_ptr(&shared) as *const u8,
+                None => false,
+            };
+            if uses_shared {
+                self.source[c] = Some(b.clone());
+            }
+            self.basis[c] = b;
+        }
+        self.shared_basis = false;
+    }
+
+    // A basis function for octave c, set up as the fractal-wide setters did the others.
+    fn new_basis(&self, c: usize) -> ImplicitBasisFunction {
+        let mut b = ImplicitBasisFunction::default();
+        b.set_hash(self.hash.clone());
+        b.set_type(self.basis_type.clone());
+        b.set_interp(self.interp_type.clone());
+        if let Some(seed) = self.seed {
+            b.set_seed(seed + c as u32 * 300);
+        }
+        match self.flow_time {
+            Some(ScalarParameter::Value(t)) => b.set_time_value(t),
+            Some(ScalarParameter::Source(ref m)) => b.set_time_module(m.clone()),
+            None => {}
+        }
+        b
+    }
+
+    fn update_periods(&mut self) {
+        let mut scale = 1.0;
+        for b in self.basis.iter() {
+            let p: Vec<u32> = self.periods.iter().map(|&p| (p as f64 * scale).round() as u32).collect();
+            b.borrow_mut().set_periods(&p);
+            scale *= self.lacunarity;
+        }
+    }
+
+    /// Sets the amount of pseudo-advection, after Perlin and Neyret's flow noise. Each octave is then sampled at a
+    /// position displaced against the amplitude-weighted gradient of the octaves below it, by `a` times that gradient
+    /// in the octave's own lattice units, so that fine detail appears to be carried along by the large-scale flow.
+    /// It is most convincing with [`Flow`](enum.BasisType.html#variant.Flow) sources animated through
+    /// [`set_flow_time_value()`](struct.ImplicitFractal.html#method.set_flow_time_value). The default of 0 turns it
+    /// off. It applies to the `FBM` type only; in 4D and 6D only x, y and z are displaced.
+    pub fn set_advection(&mut self, a: f64) {
+        self.advection = a;
+    }
+
+    /// Sets the time of every built-in basis function of type `Flow`
+    /// (see [`ImplicitBasisFunction::set_time_value()`](struct.ImplicitBasisFunction.html#method.set_time_value)).
+    pub fn set_flow_time_value(&mut self, t: f64) {
+        for b in self.basis.iter() {
+            b.borrow_mut().set_time_value(t);
+        }
+        self.flow_time = Some(ScalarParameter::Value(t));
+    }
+
+    /// Drives the time of every built-in basis function of type `Flow` with another function.
+    pub fn set_flow_time_module(&mut self, m: Rc<RefCell<ImplicitModule>>) {
+        for b in self.basis.iter() {
+            b.borrow_mut().set_time_module(m.clone());
+        }
+        self.flow_time = Some(ScalarParameter::Source(m));
     }
 
     pub fn set_gain(&mut self, g: f64) {
@@ -100,7 +263,7 @@ impl ImplicitFractal {
 
     pub fn new(ftype: FractalType, basistype: BasisType, interptype: InterpType) -> ImplicitFractal {
         let mut v = ImplicitFractal {
-            numoctaves: 8,
+            octaves: 8.0,
             frequency: 1.0,
             lacunarity: 2.0,
             ..Default::default()
@@ -120,39 +283,51 @@ impl ImplicitFractal {
                 self.H = 1.0;
                 self.gain = 0.5;
                 self.offset = 0.0;
-                self.fbm_calc_weights();
             }
             FractalType::RidgedMulti => {
                 self.H = 0.9;
                 self.gain = 0.5;
                 self.offset = 1.0;
-                self.ridged_multi_calc_weights();
             }
             FractalType::Billow => {
                 self.H = 1.0;
                 self.gain = 0.5;
                 self.offset = 0.0;
-                self.billow_calc_weights();
             }
             FractalType::Multi => {
                 self.H = 1.0;
                 self.offset = 0.0;
                 self.gain = 0.0;
-                self.multi_calc_weights();
             }
             FractalType::HybridMulti => {
                 self.H = 0.25;
                 self.gain = 1.0;
                 self.offset = 0.7;
-                self.hybrid_multi_calc_weights();
             }
             FractalType::DecarpentierSwiss => {
                 self.H = 0.9;
                 self.gain = 0.6;
                 self.offset = 0.15;
-                self.decarpentier_swiss_calc_weights();
+            }
+            FractalType::Erosion => {
+                self.H = 1.0;
+                self.gain = 0.5;
+                self.offset = 1.0;
             }
         }
+        self.calc_weights();
+    }
+
+    fn calc_weights(&mut self) {
+        match self.ftype {
+            FractalType::FBM => self.fbm_calc_weights(),
+            FractalType::RidgedMulti => self.ridged_multi_calc_weights(),
+            FractalType::Billow => self.billow_calc_weights(),
+            FractalType::Multi => self.multi_calc_weights(),
+            FractalType::HybridMulti => self.hybrid_multi_calc_weights(),
+            FractalType::DecarpentierSwiss => self.decarpentier_swiss_calc_weights(),
+            FractalType::Erosion => self.fbm_calc_weights(),
+        }
     }
 
     pub fn set_all_source_types(&mut self, basis_type: BasisType, interp: InterpType) {
@@ -161,53 +336,81 @@ impl ImplicitFractal {
             bi.set_type(basis_type.clone());
             bi.set_interp(interp.clone());
         }
+        self.basis_type = basis_type;
+        self.interp_type = interp;
     }
 
-    pub fn set_source_type(&mut self, which: usize, basis_type: BasisType, interp: InterpType) {
-        if which < MAX_SOURCES {
-            let mut bw = self.basis[which].borrow_mut();
-            bw.set_type(basis_type);
-            bw.set_interp(interp);
+    pub fn set_all_source_hashes(&mut self, hash: HashType) {
+        for i in self.basis.iter_mut() {
+            i.borrow_mut().set_hash(hash.clone());
         }
+        self.hash = hash;
+    }
+
+    pub fn set_source_type(&mut self, which: usize, basis_type: BasisType, interp: InterpType) {
+        self.ensure_sources(which + 1);
+        let mut bw = self.basis[which].borrow_mut();
+        bw.set_type(basis_type);
+        bw.set_interp(interp);
     }
 
     pub fn override_source(&mut self, which: usize, b: Option<Rc<RefCell<ImplicitModule>>>) {
-        if which < MAX_SOURCES {
-            self.source[which] = b;
+        self.ensure_sources(which + 1);
+        self.source[which] = b;
+        if self.footprint > 0.0 {
+            self.forward_footprint();
         }
     }
 
     pub fn reset_source(&mut self, which: usize) {
-        if which < MAX_SOURCES {
-            self.source[which] = Some(self.basis[which].clone())
-        }
+        self.ensure_sources(which + 1);
+        self.source[which] = Some(self.basis[which].clone())
     }
 
     pub fn reset_all_sources(&mut self) {
-        for c in 0..MAX_SOURCES {
+        for c in 0..self.source.len() {
             self.source[c] = Some(self.basis[c].clone());
         }
     }
 
     pub fn get_basis(&mut self, which: usize) -> Option<Rc<RefCell<ImplicitBasisFunction>>> {
-        if which < MAX_SOURCES {
-            Some(self.basis[which].clone())
-        } else {
-            None
+        self.ensure_sources(which + 1);
+        Some(self.basis[which].clone())
+    }
+
+    // Grows the octave storage to at least n octaves. New octaves get the shared basis function, or one of their own
+    // set up like the others were by the fractal-wide setters.
+    fn ensure_sources(&mut self, n: usize) {
+        if n <= self.basis.len() {
+            return;
+        }
+        while self.basis.len() < n {
+            let b = if self.shared_basis {
+                self.basis[0].clone()
+            } else {
+                Rc::new(RefCell::new(self.new_basis(self.basis.len())))
+            };
+            self.source.push(Some(b.clone()));
+            self.basis.push(b);
+        }
+        self.exparray.resize(n, 0.0);
+        self.correct.resize(n, [0.0; 2]);
+        self.calc_weights();
+        if !self.periods.is_empty() {
+            self.update_periods();
         }
     }
 
     fn fbm_calc_weights(&mut self) {
-        for i in 0..MAX_SOURCES {
-            self.exparray[i] = self.lacunarity.powf(-(i as f64) * self.H);
-        }
+        self.spectral_weights();
 
         // Calculate scale/bias pairs by guessing at minimum and maximum values and remapping to [-1,1]
         let mut minvalue = 0.0;
         let mut maxvalue = 0.0;
-        for i in 0..MAX_SOURCES {
-            minvalue += -1.0 * self.exparray[i];
-            maxvalue += 1.0 * self.exparray[i];
+        for i in 0..self.exparray.len() {
+            let e = self.octave_amplitude(i, self.exparray[i]);
+            minvalue += -1.0 * e;
+            maxvalue += 1.0 * e;
 
             let A = -1.0;
             let B = 1.0;
@@ -219,16 +422,15 @@ impl ImplicitFractal {
     }
 
     fn ridged_multi_calc_weights(&mut self) {
-        for i in 0..MAX_SOURCES {
-            self.exparray[i] = self.lacunarity.powf(-(i as f64) * self.H);
-        }
+        self.spectral_weights();
 
         // Calculate scale/bias pairs by guessing at minimum and maximum values and remapping to [-1,1]
         let mut minvalue = 0.0;
         let mut maxvalue = 0.0;
-        for i in 0..MAX_SOURCES {
-            minvalue += (self.offset - 1.0) * (self.offset - 1.0) * self.exparray[i];
-            maxvalue += (self.offset) * (self.offset) * self.exparray[i];
+        for i in 0..self.exparray.len() {
+            let e = self.octave_amplitude(i, self.exparray[i]);
+            minvalue += (self.offset - 1.0) * (self.offset - 1.0) * e;
+            maxvalue += (self.offset) * (self.offset) * e;
 
             let A = -1.0;
             let B = 1.0;
@@ -240,16 +442,15 @@ impl ImplicitFractal {
     }
 
     pub fn decarpentier_swiss_calc_weights(&mut self) {
-        for i in 0..MAX_SOURCES {
-            self.exparray[i] = self.lacunarity.powf(-(i as f64) * self.H);
-        }
+        self.spectral_weights();
 
         // Calculate scale/bias pairs by guessing at minimum and maximum values and remapping to [-1,1]
         let mut minvalue = 0.0;
         let mut maxvalue = 0.0;
-        for i in 0..MAX_SOURCES {
-            minvalue += (self.offset - 1.0) * (self.offset - 1.0) * self.exparray[i];
-            maxvalue += (self.offset) * (self.offset) * self.exparray[i];
+        for i in 0..self.exparray.len() {
+            let e = self.octave_amplitude(i, self.exparray[i]);
+            minvalue += (self.offset - 1.0) * (self.offset - 1.0) * e;
+            maxvalue += (self.offset) * (self.offset) * e;
 
             let A = -1.0;
             let B = 1.0;
@@ -261,16 +462,15 @@ impl ImplicitFractal {
     }
 
     pub fn billow_calc_weights(&mut self) {
-        for i in 0..MAX_SOURCES {
-            self.exparray[i] = self.lacunarity.powf(-(i as f64) * self.H);
-        }
+        self.spectral_weights();
 
         // Calculate scale/bias pairs by guessing at minimum and maximum values and remapping to [-1,1]
         let mut minvalue = 0.0;
         let mut maxvalue = 0.0;
-        for i in 0..MAX_SOURCES {
-            minvalue += -1.0 * self.exparray[i];
-            maxvalue += 1.0 * self.exparray[i];
+        for i in 0..self.exparray.len() {
+            let e = self.octave_amplitude(i, self.exparray[i]);
+            minvalue += -1.0 * e;
+            maxvalue += 1.0 * e;
 
             let A = -1.0;
             let B = 1.0;
@@ -282,16 +482,15 @@ impl ImplicitFractal {
     }
 
     pub fn multi_calc_weights(&mut self) {
-        for i in 0..MAX_SOURCES {
-            self.exparray[i] = self.lacunarity.powf(-(i as f64) * self.H);
-        }
+        self.spectral_weights();
 
         // Calculate scale/bias pairs by guessing at minimum and maximum values and remapping to [-1,1]
         let mut minvalue = 1.0;
         let mut maxvalue = 1.0;
-        for i in 0..MAX_SOURCES {
-            minvalue *= -1.0 * self.exparray[i] + 1.0;
-            maxvalue *= 1.0 * self.exparray[i] + 1.0;
+        for i in 0..self.exparray.len() {
+            let e = self.octave_amplitude(i, self.exparray[i]);
+            minvalue *= -1.0 * e + 1.0;
+            maxvalue *= 1.0 * e + 1.0;
 
             let A = -1.0;
             let B = 1.0;
@@ -304,16 +503,15 @@ impl ImplicitFractal {
     }
 
     pub fn hybrid_multi_calc_weights(&mut self) {
-        for i in 0..MAX_SOURCES {
-            self.exparray[i] = self.lacunarity.powf(-(i as f64) * self.H);
-        }
+        self.spectral_weights();
 
         // Calculate scale/bias pairs by guessing at minimum and maximum values and remapping to [-1,1]
         let A = -1.0;
         let B = 1.0;
 
-        let mut minvalue = self.offset - 1.0;
-        let mut maxvalue = self.offset + 1.0;
+        let e = if self.exparray.is_empty() { 1.0 } else { self.octave_amplitude(0, self.exparray[0]) };
+        let mut minvalue = (self.offset - 1.0) * e;
+        let mut maxvalue = (self.offset + 1.0) * e;
         let mut weightmin = self.gain * minvalue;
         let mut weightmax = self.gain * maxvalue;
 
@@ -322,7 +520,7 @@ impl ImplicitFractal {
         self.correct[0][0] = scale;
         self.correct[0][1] = bias;
 
-        for i in 1..MAX_SOURCES {
+        for i in 1..self.exparray.len() {
             if weightmin > 1.0 {
                 weightmin = 1.0
             }
@@ -330,11 +528,12 @@ impl ImplicitFractal {
                 weightmax = 1.0
             }
 
-            let mut signal = (self.offset - 1.0) * self.exparray[i];
+            let e = self.octave_amplitude(i, self.exparray[i]);
+            let mut signal = (self.offset - 1.0) * e;
             minvalue += signal * weightmin;
             weightmin *= self.gain * signal;
 
-            signal = (self.offset + 1.0) * self.exparray[i];
+            signal = (self.offset + 1.0) * e;
             maxvalue += signal * weightmax;
             weightmax *= self.gain * signal;
 
@@ -345,884 +544,575 @@ impl ImplicitFractal {
         }
     }
 
-    pub fn fbm_get_2d(&mut self, x: f64, y: f64) -> f64 {
-        let mut sum = 0.0;
-        let mut amp = 1.0;
-
-        let mut x = x;
-        let mut y = y;
-
-        x *= self.frequency;;
-        y *= self.frequency;;
-
-        for i in 0..self.numoctaves as usize {
-            if let Some(ref ii) = self.source[i] {
-                let n = ii.borrow_mut().get_2d(x, y);
-                sum += n * amp;
-                amp *= self.gain;
-
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-            }
+    // The amplitudes that the types weighted by H derive for each octave: frequency^-H, relative to the first octave.
+    // An octave with its own frequency gets the amplitude of that frequency.
+    fn spectral_weights(&mut self) {
+        for i in 0..self.exparray.len() {
+            self.exparray[i] = match self.frequencies.get(i) {
+                Some(&f) if self.frequency != 0.0 => (f / self.frequency).abs().powf(-self.H),
+                _ => self.lacunarity.powf(-(i as f64) * self.H),
+            };
         }
-        sum
     }
 
-    pub fn fbm_get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
-        let mut sum = 0.0;
-        let mut amp = 1.0;
-
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
-
-        x *= self.frequency;;
-        y *= self.frequency;;
-        z *= self.frequency;;
-
-        for i in 0..self.numoctaves as usize {
-            if let Some(ref ii) = self.source[i] {
-                let n = ii.borrow_mut().get_3d(x, y, z);
-                sum += n * amp;
-                amp *= self.gain;
-
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-                z *= self.lacunarity;
-            }
+    fn octave_count(&self) -> usize {
+        let count = *min(&(self.octaves.ceil() as usize), &self.source.len());
+        if self.footprint > 0.0 && self.frequencies.is_empty() && self.lacunarity > 1.0 {
+            // With derived frequencies, every octave after the first one that is filtered out entirely is finer still.
+            return (0..count).position(|i| self.footprint_fade(i) <= 0.0).unwrap_or(count);
         }
-        sum
+        count
     }
 
-    pub fn fbm_get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
-        let mut sum = 0.0;
-        let mut amp = 1.0;
-
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
-        let mut w = w;
-
-        x *= self.frequency;;
-        y *= self.frequency;;
-        z *= self.frequency;;
-        w *= self.frequency;;
-
-        for i in 0..self.numoctaves as usize {
-            if let Some(ref ii) = self.source[i] {
-                let n = ii.borrow_mut().get_4d(x, y, z, w);
-                sum += n * amp;
-                amp *= self.gain;
-
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-                z *= self.lacunarity;
-                w *= self.lacunarity;
-            }
+    fn octave_frequency(&self, i: usize) -> f64 {
+        match self.frequencies.get(i) {
+            Some(&f) => f,
+            None => self.frequency * self.lacunarity.powi(i as i32),
         }
-        sum
     }
 
-    pub fn fbm_get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
-        let mut sum = 0.0;
-        let mut amp = 1.0;
-
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
-        let mut w = w;
-        let mut u = u;
-        let mut v = v;
-
-        x *= self.frequency;;
-        y *= self.frequency;;
-        z *= self.frequency;;
-        w *= self.frequency;;
-        u *= self.frequency;;
-        v *= self.frequency;;
-
-        for i in 0..self.numoctaves as usize {
-            if let Some(ref ii) = self.source[i] {
-                // let n = self.source[i].get_4d(x,y,z,w);
-                let n = ii.borrow_mut().get_6d(x, y, z, w, u, v);
-                sum += n * amp;
-                amp *= self.gain;
-
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-                z *= self.lacunarity;
-                w *= self.lacunarity;
-                u *= self.lacunarity;
-                v *= self.lacunarity;
+    // Passes the footprint on to the sources that override octaves. Octave i samples its source at the octave's
+    // frequency, so in the source's coordinates the footprint is that many times larger.
+    fn forward_footprint(&self) {
+        for (i, s) in self.source.iter().enumerate() {
+            if let Some(ref s) = *s {
+                if Rc::as_ptr(s) as *const u8 != Rc::as_ptr(&self.basis[i]) as *const u8 {
+                    s.borrow_mut().set_footprint(self.footprint * self.octave_frequency(i).abs());
+                }
             }
         }
-        sum
     }
 
-    pub fn multi_get_2d(&mut self, x: f64, y: f64) -> f64 {
-        let mut value = 1.0;
-
-        let mut x = x;
-        let mut y = y;
-
-        x *= self.frequency;
-        y *= self.frequency;
-
-        for i in 0..self.numoctaves as usize {
-            if let Some(ref ii) = self.source[i] {
-                value *= ii.borrow_mut().get_2d(x, y) * self.exparray[i] + 1.0;
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-            }
+    // Fades octave i out as its frequency goes from a quarter to half a cycle per footprint, the Nyquist limit.
+    fn footprint_fade(&self, i: usize) -> f64 {
+        if self.footprint <= 0.0 {
+            return 1.0;
         }
-
-        value * self.correct[self.numoctaves as usize - 1][0] + self.correct[self.numoctaves as usize - 1][1]
+        let cycles = self.octave_frequency(i).abs() * self.footprint;
+        *max(&0.0, min(&(2.0 - 4.0 * cycles), &1.0))
     }
 
-    pub fn multi_get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
-        let mut value = 1.0;
-
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
-        let mut w = w;
-
-        x *= self.frequency;
-        y *= self.frequency;
-        z *= self.frequency;
-        w *= self.frequency;
+    // Weight of octave i: 1 for the full octaves and the fractional part for the last, faded by the footprint.
+    fn octave_weight(&self, i: usize) -> f64 {
+        let w = self.octaves - i as f64;
+        (if w < 1.0 { w } else { 1.0 }) * self.footprint_fade(i)
+    }
 
-        for i in 0..self.numoctaves as usize {
-            if let Some(ref ii) = self.source[i] {
-                value *= ii.borrow_mut().get_4d(x, y, z, w) * self.exparray[i] + 1.0;
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-                z *= self.lacunarity;
-                w *= self.lacunarity;
-            }
+    fn octave_amplitude(&self, i: usize, derived: f64) -> f64 {
+        match self.amplitudes.get(i) {
+            Some(&a) => a,
+            None => derived,
         }
-
-        value * self.correct[self.numoctaves as usize - 1][0] + self.correct[self.numoctaves as usize - 1][1]
     }
 
-    pub fn multi_get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
-        let mut value = 1.0;
-
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
-
-        x *= self.frequency;
-        y *= self.frequency;
-        z *= self.frequency;
-
-        for i in 0..self.numoctaves as usize {
-            if let Some(ref ii) = self.source[i] {
-                value *= ii.borrow_mut().get_3d(x, y, z) * self.exparray[i] + 1.0;
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-                z *= self.lacunarity;
+    // Coordinates of octave i: the running coordinates q, scaled by frequency and lacunarity, unless the octave has
+    // its own frequency, and then rotated if it has a rotation. 1D octaves have nothing to rotate.
+    fn octave_coords(&self, i: usize, p: &[f64], q: &[f64; MAX_DIMENSIONS]) -> [f64; MAX_DIMENSIONS] {
+        let mut c = *q;
+        if let Some(&f) = self.frequencies.get(i) {
+            for (ck, pk) in c.iter_mut().zip(p.iter()) {
+                *ck = pk * f;
             }
         }
+        if p.len() < 2 {
+            return c;
+        }
+        if let Some(&angle) = self.rotations.get(i) {
+            let (s, co) = angle.sin_cos();
+            let (x, y) = (c[0], c[1]);
+            c[0] = x * co - y * s;
+            c[1] = x * s + y * co;
+        }
+        c
+    }
 
-        return value * self.correct[self.numoctaves as usize - 1][0] + self.correct[self.numoctaves as usize - 1][1];
+    // Turns a gradient taken in the coordinates of octave i back into the fractal's coordinates.
+    fn unrotate(&self, i: usize, g: &mut [f64]) {
+        if g.len() < 2 {
+            return;
+        }
+        if let Some(&angle) = self.rotations.get(i) {
+            let (s, co) = angle.sin_cos();
+            let (gx, gy) = (g[0], g[1]);
+            g[0] = gx * co + gy * s;
+            g[1] = gy * co - gx * s;
+        }
     }
 
-    pub fn multi_get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
-        let mut value = 1.0;
+    fn start_coords(&self, p: &[f64]) -> [f64; MAX_DIMENSIONS] {
+        let mut q = [0.0; MAX_DIMENSIONS];
+        for (qk, pk) in q.iter_mut().zip(p.iter()) {
+            *qk = pk * self.frequency;
+        }
+        q
+    }
 
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
-        let mut w = w;
-        let mut u = u;
-        let mut v = v;
-
-        x *= self.frequency;
-        y *= self.frequency;
-        z *= self.frequency;
-        w *= self.frequency;
-        u *= self.frequency;
-        v *= self.frequency;
-
-        for i in 0..self.numoctaves as usize {
-            if let Some(ref ii) = self.source[i] {
-                value *= ii.borrow_mut().get_6d(x, y, z, w, u, v) * self.exparray[i] + 1.0;
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-                z *= self.lacunarity;
-                w *= self.lacunarity;
-                u *= self.lacunarity;
-                v *= self.lacunarity;
-            }
+    fn next_coords(&self, q: &mut [f64; MAX_DIMENSIONS]) {
+        for qk in q.iter_mut() {
+            *qk *= self.lacunarity;
         }
+    }
 
-        return value * self.correct[self.numoctaves as usize - 1][0] + self.correct[self.numoctaves as usize - 1][1];
+    // Blends the scale/bias pair of the last full octave count into the next one by the fractional part.
+    fn correction(&self) -> [f64; 2] {
+        let count = self.octaves.ceil() as usize;
+        let full = self.octaves.floor() as usize;
+        if count == 0 {
+            return [1.0, 0.0];
+        }
+        let c1 = self.correct[count - 1];
+        if full == count || full == 0 {
+            return c1;
+        }
+        let c0 = self.correct[full - 1];
+        let t = self.octaves - full as f64;
+        [c0[0] + (c1[0] - c0[0]) * t, c0[1] + (c1[1] - c0[1]) * t]
     }
 
-    pub fn billow_get_2d(&mut self, x: f64, y: f64) -> f64 {
+    fn fbm(&self, p: &[f64]) -> f64 {
+        let n = p.len();
         let mut sum = 0.0;
         let mut amp = 1.0;
+        let mut q = self.start_coords(p);
 
-        let mut x = x;
-        let mut y = y;
-
-        x *= self.frequency;
-        y *= self.frequency;
-
-        for i in 0..self.numoctaves as usize {
+        let mut adv = [0.0; 3];
+        for i in 0..self.octave_count() {
             if let Some(ref ii) = self.source[i] {
-                let n = ii.borrow_mut().get_2d(x, y);
-                sum += (2.0 * n.abs() - 1.0) * amp;
+                let mut c = self.octave_coords(i, p, &q);
+                for k in 0..*min(&n, &3) {
+                    c[k] -= self.advection * adv[k];
+                }
+                let a = self.octave_amplitude(i, amp) * self.octave_weight(i);
+                let mut s = ii.borrow_mut();
+                let v = sample(&mut *s, &c[..n]);
+                if self.advection != 0.0 {
+                    let g = source_gradient(&mut *s, &c[..n], self.base.spacing, *min(&n, &3));
+                    advect(&mut adv, &g, a);
+                }
+                sum += v * a;
                 amp *= self.gain;
-
-                x *= self.lacunarity;
-                y *= self.lacunarity;
+                self.next_coords(&mut q);
             }
         }
         sum
     }
 
-    pub fn billow_get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
-        let mut sum = 0.0;
+    fn fbm_gradient(&self, p: &[f64]) -> Option<[f64; 3]> {
+        if self.advection != 0.0 {
+            return None;
+        }
+        let n = p.len();
+        let mut grad = [0.0; 3];
         let mut amp = 1.0;
+        let mut freq = self.frequency;
+        let mut q = self.start_coords(p);
 
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
-        let mut w = w;
-
-        x *= self.frequency;
-        y *= self.frequency;
-        z *= self.frequency;
-        w *= self.frequency;
-
-        for i in 0..self.numoctaves as usize {
+        for i in 0..self.octave_count() {
             if let Some(ref ii) = self.source[i] {
-                let n = ii.borrow_mut().get_4d(x, y, z, w);
-                sum += (2.0 * n.abs() - 1.0) * amp;
+                let c = self.octave_coords(i, p, &q);
+                let mut g = match n {
+                    2 => {
+                        let g = ii.borrow_mut().get_analytic_gradient_2(c[0], c[1])?;
+                        [g[0], g[1], 0.0]
+                    }
+                    _ => ii.borrow_mut().get_analytic_gradient_3(c[0], c[1], c[2])?,
+                };
+                self.unrotate(i, &mut g);
+                let a = self.octave_amplitude(i, amp) * self.octave_weight(i);
+                let f = match self.frequencies.get(i) {
+                    Some(&f) => f,
+                    None => freq,
+                };
+                for k in 0..n {
+                    grad[k] += g[k] * a * f;
+                }
                 amp *= self.gain;
-
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-                z *= self.lacunarity;
-                w *= self.lacunarity;
+                freq *= self.lacunarity;
+                self.next_coords(&mut q);
             }
         }
-        sum
+        Some(grad)
     }
 
-    pub fn billow_get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
-        let mut sum = 0.0;
-        let mut amp = 1.0;
-
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
-
-        x *= self.frequency;
-        y *= self.frequency;
-        z *= self.frequency;
+    fn multi(&self, p: &[f64]) -> f64 {
+        let n = p.len();
+        let mut value = 1.0;
+        let mut q = self.start_coords(p);
 
-        for i in 0..self.numoctaves as usize {
+        for i in 0..self.octave_count() {
             if let Some(ref ii) = self.source[i] {
-                let n = ii.borrow_mut().get_3d(x, y, z);
-                sum += (2.0 * n.abs() - 1.0) * amp;
-                amp *= self.gain;
-
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-                z *= self.lacunarity;
+                let c = self.octave_coords(i, p, &q);
+                let a = self.octave_amplitude(i, self.exparray[i]) * self.octave_weight(i);
+                value *= sample(&mut *ii.borrow_mut(), &c[..n]) * a + 1.0;
+                self.next_coords(&mut q);
             }
         }
-        sum
+
+        let correct = self.correction();
+        value * correct[0] + correct[1]
     }
 
-    pub fn billow_get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
+    fn billow(&self, p: &[f64]) -> f64 {
+        let n = p.len();
         let mut sum = 0.0;
         let mut amp = 1.0;
+        let mut q = self.start_coords(p);
 
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
-        let mut w = w;
-        let mut u = u;
-        let mut v = v;
-
-        x *= self.frequency;
-        y *= self.frequency;
-        z *= self.frequency;
-        w *= self.frequency;
-        u *= self.frequency;
-        v *= self.frequency;
-
-        for i in 0..self.numoctaves as usize {
+        for i in 0..self.octave_count() {
             if let Some(ref ii) = self.source[i] {
-                let n = ii.borrow_mut().get_6d(x, y, z, w, u, v);
-                sum += (2.0 * n.abs() - 1.0) * amp;
+                let c = self.octave_coords(i, p, &q);
+                let v = sample(&mut *ii.borrow_mut(), &c[..n]);
+                sum += (2.0 * v.abs() - 1.0) * (self.octave_amplitude(i, amp) * self.octave_weight(i));
                 amp *= self.gain;
-
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-                z *= self.lacunarity;
-                w *= self.lacunarity;
-                u *= self.lacunarity;
-                v *= self.lacunarity;
+                self.next_coords(&mut q);
             }
         }
         sum
     }
 
-    pub fn ridged_multi_get_2d(&mut self, x: f64, y: f64) -> f64 {
-        // let mut sum = 0.0;
-        // let mut amp = 1.0;
-        //
-        // x *= self.frequency;
-        // y *= self.frequency;
-        //
-        // for i in 0..self.numoctaves as usize {
-        // if let Some(ref ii) = self.source[i] {
-        // let n = ii.borrow_mut().get_2d(x, y);
-        // n = 1.0 - n.abs();
-        // sum += amp * n;
-        // amp *= self.gain;
-        //
-        // x *= self.lacunarity;
-        // y *= self.lacunarity;
-        // }
-        // }
-        // sum
+    fn ridged_multi(&self, p: &[f64]) -> f64 {
+        let n = p.len();
         let mut result = 0.0;
         let mut signal = 0.0;
+        let mut q = self.start_coords(p);
 
-        let mut x = x;
-        let mut y = y;
-
-        x *= self.frequency;
-        y *= self.frequency;
-
-        for i in 0..self.numoctaves as usize {
+        for i in 0..self.octave_count() {
             if let Some(ref ii) = self.source[i] {
-                signal = ii.borrow_mut().get_2d(x, y);
+                let c = self.octave_coords(i, p, &q);
+                signal = sample(&mut *ii.borrow_mut(), &c[..n]);
                 signal = self.offset - signal.abs();
                 signal *= signal;
-                result += signal * self.exparray[i];
-
-                x *= self.lacunarity;
-                y *= self.lacunarity;
+                result += signal * (self.octave_amplitude(i, self.exparray[i]) * self.octave_weight(i));
+                self.next_coords(&mut q);
             }
         }
 
-        result * self.correct[self.numoctaves as usize - 1][0] + self.correct[self.numoctaves as usize - 1][1]
+        let correct = self.correction();
+        result * correct[0] + correct[1]
     }
 
-    pub fn ridged_multi_get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
-        let mut result = 0.0;
-        let mut signal = 0.0;
-
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
-        let mut w = w;
-
-        x *= self.frequency;
-        y *= self.frequency;
-        z *= self.frequency;
-        w *= self.frequency;
+    fn hybrid_multi(&self, p: &[f64]) -> f64 {
+        let n = p.len();
+        let mut value: f64 = 1.0;
+        let mut signal: f64;
+        let mut weight: f64;
+        let mut q = self.start_coords(p);
 
-        for i in 0..self.numoctaves as usize {
-            if let Some(ref ii) = self.source[i] {
-                signal = ii.borrow_mut().get_4d(x, y, z, w);
-                signal = self.offset - signal.abs();
-                signal *= signal;
-                result += signal * self.exparray[i];
+        if let Some(ref oo) = self.source[0] {
+            // The first octave has always been sampled in the xy plane only, whatever the dimension; that is kept so
+            // that existing HybridMulti output does not change.
+            let c = self.octave_coords(0, p, &q);
+            signal = (sample(&mut *oo.borrow_mut(), &c[..*min(&n, &2)]) + self.offset) * self.octave_amplitude(0, self.exparray[0]);
+            value = signal * self.octave_weight(0);
+            weight = self.gain * value;
+            self.next_coords(&mut q);
 
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-                z *= self.lacunarity;
-                w *= self.lacunarity;
+            for i in 1..self.octave_count() {
+                if weight > 1.0 {
+                    weight = 1.0
+                }
+                if let Some(ref ii) = self.source[i] {
+                    let c = self.octave_coords(i, p, &q);
+                    signal = (sample(&mut *ii.borrow_mut(), &c[..n]) + self.offset) * self.octave_amplitude(i, self.exparray[i]);
+                    value += weight * signal * self.octave_weight(i);
+                    weight *= self.gain * signal;
+                    self.next_coords(&mut q);
+                }
             }
         }
 
-        result * self.correct[self.numoctaves as usize - 1][0] + self.correct[self.numoctaves as usize - 1][1]
+        let correct = self.correction();
+        value * correct[0] + correct[1]
     }
 
-    pub fn ridged_multi_get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
-        // let mut sum = 0.0;
-        // let mut amp = 1.0;
-        //
-        // x *= self.frequency;
-        // y *= self.frequency;
-        // z *= self.frequency;
-        //
-        // for i in 0..self.numoctaves as usize {
-        // if let Some(ref ii) = self.source[i] {
-        // let n = ii.borrow_mut().get_3d(x, y, z);
-        // n = 1.0 - n.abs();
-        // sum += amp * n;
-        // amp *= self.gain;
-        //
-        // x *= self.lacunarity;
-        // y *= self.lacunarity;
-        // z *= self.lacunarity;
-        // }
-        // }
-        // sum
-
-        let mut result = 0.0;
-        let mut signal = 0.0;
-
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
-
-        x *= self.frequency;
-        y *= self.frequency;
-        z *= self.frequency;
+    fn decarpentier_swiss(&self, p: &[f64]) -> f64 {
+        let n = p.len();
+        let mut sum = 0.0;
+        let mut amp = 1.0;
+        let mut erosion = 1.0;
+        let mut dsum = [0.0; MAX_DIMENSIONS];
+        let mut q = self.start_coords(p);
 
-        for i in 0..self.numoctaves as usize {
+        for i in 0..self.octave_count() {
             if let Some(ref ii) = self.source[i] {
-                signal = ii.borrow_mut().get_3d(x, y, z);
-                signal = self.offset - signal.abs();
-                signal *= signal;
-                result += signal * self.exparray[i];
-
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-                z *= self.lacunarity;
+                let mut c = self.octave_coords(i, p, &q);
+                for (ck, dk) in c.iter_mut().zip(dsum.iter()).take(n) {
+                    *ck += self.offset * dk;
+                }
+                let a = match self.amplitudes.get(i) {
+                    Some(&a) => a * erosion,
+                    None => amp,
+                } * self.octave_weight(i);
+                let mut bii = ii.borrow_mut();
+                let v = sample(&mut *bii, &c[..n]);
+                sum += a * (1.0 - v.abs());
+                for (k, dk) in dsum.iter_mut().enumerate().take(n) {
+                    *dk += a * sample_deriv(&mut *bii, &c[..n], k) * -v;
+                }
+                let e = *(max(&0.0, min(&sum, &1.0)));
+                amp *= self.gain * e;
+                erosion *= e;
+                self.next_coords(&mut q);
             }
         }
-
-        result * self.correct[self.numoctaves as usize - 1][0] + self.correct[self.numoctaves as usize - 1][1]
+        sum
     }
 
-    pub fn ridged_multi_get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
-        let mut result = 0.0;
-        let mut signal = 0.0;
+    // Quilez's derivative-dampened fBm: every octave is divided by 1 + offset * |d|^2, where d is the sum of the
+    // gradients of the octaves up to and including it, so that detail fades on steep slopes and collects in the
+    // valleys.
+    fn erosion(&self, p: &[f64]) -> f64 {
+        let n = p.len();
+        let mut sum = 0.0;
+        let mut amp = 1.0;
+        let mut dsum = [0.0; MAX_DIMENSIONS];
+        let mut q = self.start_coords(p);
 
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
-        let mut w = w;
-        let mut u = u;
-        let mut v = v;
-
-        x *= self.frequency;
-        y *= self.frequency;
-        z *= self.frequency;
-        w *= self.frequency;
-        u *= self.frequency;
-        v *= self.frequency;
-
-        for i in 0..self.numoctaves as usize {
+        for i in 0..self.octave_count() {
             if let Some(ref ii) = self.source[i] {
-                signal = ii.borrow_mut().get_6d(x, y, z, w, u, v);
-                signal = self.offset - signal.abs();
-                signal *= signal;
-                result += signal * self.exparray[i];
-
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-                z *= self.lacunarity;
-                w *= self.lacunarity;
-                u *= self.lacunarity;
-                v *= self.lacunarity;
+                let c = self.octave_coords(i, p, &q);
+                let w = self.octave_weight(i);
+                let mut s = ii.borrow_mut();
+                let v = sample(&mut *s, &c[..n]);
+                let mut g = source_gradient(&mut *s, &c[..n], self.base.spacing, n);
+                self.unrotate(i, &mut g[..n]);
+                for (dk, gk) in dsum.iter_mut().zip(g.iter()) {
+                    *dk += gk * w;
+                }
+                let slope: f64 = dsum.iter().map(|d| d * d).sum();
+                sum += v * self.octave_amplitude(i, amp) * w / (1.0 + self.offset * slope);
+                amp *= self.gain;
+                self.next_coords(&mut q);
             }
         }
-
-        return result * self.correct[self.numoctaves as usize - 1][0] + self.correct[self.numoctaves as usize - 1][1];
+        sum
     }
 
-    pub fn hybrid_multi_get_2d(&mut self, x: f64, y: f64) -> f64 {
-        let mut value: f64 = 1.0;
-        let mut signal: f64;
-        let mut weight: f64;
-
-        let mut x = x;
-        let mut y = y;
+    pub fn fbm_get_2d(&mut self, x: f64, y: f64) -> f64 {
+        self.fbm(&[x, y])
+    }
 
-        x *= self.frequency;
-        y *= self.frequency;
+    /// Analytic gradient of the FBM fractal. Returns `None` if any octave source cannot provide one, or if pseudo-advection is on.
+    pub fn fbm_gradient_2d(&mut self, x: f64, y: f64) -> Option<[f64; 2]> {
+        let g = self.fbm_gradient(&[x, y])?;
+        Some([g[0], g[1]])
+    }
 
-        if let Some(ref oo) = self.source[0] {
-            value = oo.borrow_mut().get_2d(x, y) + self.offset;
-            weight = self.gain * value;
-            x *= self.lacunarity;
-            y *= self.lacunarity;
+    /// Analytic gradient of the FBM fractal. Returns `None` if any octave source cannot provide one, or if pseudo-advection is on.
+    pub fn fbm_gradient_3d(&mut self, x: f64, y: f64, z: f64) -> Option<[f64; 3]> {
+        self.fbm_gradient(&[x, y, z])
+    }
 
-            for i in 1..self.numoctaves as usize {
-                if weight > 1.0 {
-                    weight = 1.0
-                }
-                if let Some(ref ii) = self.source[i] {
-                    signal = (ii.borrow_mut().get_2d(x, y) + self.offset) * self.exparray[i];
-                    value += weight * signal;
-                    weight *= self.gain * signal;
-                    x *= self.lacunarity;
-                    y *= self.lacunarity;
-                }
-            }
-        }
+    pub fn fbm_get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
+        self.fbm(&[x, y, z])
+    }
 
-        value * self.correct[self.numoctaves as usize - 1][0] + self.correct[self.numoctaves as usize - 1][1]
+    pub fn fbm_get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
+        self.fbm(&[x, y, z, w])
     }
 
-    pub fn hybrid_multi_get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
-        let mut value: f64 = 1.0;
-        let mut signal: f64;
-        let mut weight: f64;
+    pub fn fbm_get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
+        self.fbm(&[x, y, z, w, u, v])
+    }
 
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
+    pub fn multi_get_2d(&mut self, x: f64, y: f64) -> f64 {
+        self.multi(&[x, y])
+    }
 
-        x *= self.frequency;
-        y *= self.frequency;
-        z *= self.frequency;
+    pub fn multi_get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
+        self.multi(&[x, y, z, w])
+    }
 
-        if let Some(ref oo) = self.source[0] {
-            value = oo.borrow_mut().get_2d(x, y) + self.offset;
-            weight = self.gain * value;
-            x *= self.lacunarity;
-            y *= self.lacunarity;
-            z *= self.lacunarity;
+    pub fn multi_get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
+        self.multi(&[x, y, z])
+    }
 
-            for i in 1..self.numoctaves as usize {
-                if weight > 1.0 {
-                    weight = 1.0
-                }
-                if let Some(ref ii) = self.source[i] {
-                    signal = (ii.borrow_mut().get_3d(x, y, z) + self.offset) * self.exparray[i];
-                    value += weight * signal;
-                    weight *= self.gain * signal;
-                    x *= self.lacunarity;
-                    y *= self.lacunarity;
-                    z *= self.lacunarity;
-                }
-            }
-        }
+    pub fn multi_get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
+        self.multi(&[x, y, z, w, u, v])
+    }
 
-        value * self.correct[self.numoctaves as usize - 1][0] + self.correct[self.numoctaves as usize - 1][1]
+    pub fn billow_get_2d(&mut self, x: f64, y: f64) -> f64 {
+        self.billow(&[x, y])
     }
 
-    pub fn hybrid_multi_get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
-        let mut value: f64 = 1.0;
-        let mut signal: f64;
-        let mut weight: f64;
+    pub fn billow_get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
+        self.billow(&[x, y, z, w])
+    }
 
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
-        let mut w = w;
+    pub fn billow_get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
+        self.billow(&[x, y, z])
+    }
 
-        x *= self.frequency;
-        y *= self.frequency;
-        z *= self.frequency;
-        w *= self.frequency;
+    pub fn billow_get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
+        self.billow(&[x, y, z, w, u, v])
+    }
 
-        if let Some(ref oo) = self.source[0] {
-            value = oo.borrow_mut().get_2d(x, y) + self.offset;
-            weight = self.gain * value;
-            x *= self.lacunarity;
-            y *= self.lacunarity;
-            z *= self.lacunarity;
-            w *= self.lacunarity;
+    pub fn ridged_multi_get_2d(&mut self, x: f64, y: f64) -> f64 {
+        self.ridged_multi(&[x, y])
+    }
 
-            for i in 1..self.numoctaves as usize {
-                if weight > 1.0 {
-                    weight = 1.0
-                }
-                if let Some(ref ii) = self.source[i] {
-                    signal = (ii.borrow_mut().get_4d(x, y, z, w) + self.offset) * self.exparray[i];
-                    value += weight * signal;
-                    weight *= self.gain * signal;
-                    x *= self.lacunarity;
-                    y *= self.lacunarity;
-                    z *= self.lacunarity;
-                    w *= self.lacunarity;
-                }
-            }
-        }
+    pub fn ridged_multi_get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
+        self.ridged_multi(&[x, y, z, w])
+    }
 
-        value * self.correct[self.numoctaves as usize - 1][0] + self.correct[self.numoctaves as usize - 1][1]
+    pub fn ridged_multi_get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
+        self.ridged_multi(&[x, y, z])
     }
 
-    pub fn hybrid_multi_get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
-        let mut value: f64 = 1.0;
-        let mut signal: f64;
-        let mut weight: f64;
+    pub fn ridged_multi_get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
+        self.ridged_multi(&[x, y, z, w, u, v])
+    }
 
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
-        let mut w = w;
-        let mut u = u;
-        let mut v = v;
+    pub fn hybrid_multi_get_2d(&mut self, x: f64, y: f64) -> f64 {
+        self.hybrid_multi(&[x, y])
+    }
 
-        x *= self.frequency;
-        y *= self.frequency;
-        z *= self.frequency;
-        w *= self.frequency;
-        u *= self.frequency;
-        v *= self.frequency;
+    pub fn hybrid_multi_get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
+        self.hybrid_multi(&[x, y, z])
+    }
 
-        if let Some(ref oo) = self.source[0] {
-            value = oo.borrow_mut().get_2d(x, y) + self.offset;
-            weight = self.gain * value;
-            x *= self.lacunarity;
-            y *= self.lacunarity;
-            z *= self.lacunarity;
-            w *= self.lacunarity;
-            u *= self.lacunarity;
-            v *= self.lacunarity;
-
-            for i in 1..self.numoctaves as usize {
-                if weight > 1.0 {
-                    weight = 1.0
-                }
-                if let Some(ref ii) = self.source[i] {
-                    signal = (ii.borrow_mut().get_6d(x, y, z, w, u, v) + self.offset) * self.exparray[i];
-                    value += weight * signal;
-                    weight *= self.gain * signal;
-                    x *= self.lacunarity;
-                    y *= self.lacunarity;
-                    z *= self.lacunarity;
-                    w *= self.lacunarity;
-                    u *= self.lacunarity;
-                    v *= self.lacunarity;
-                }
-            }
-        }
+    pub fn hybrid_multi_get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
+        self.hybrid_multi(&[x, y, z, w])
+    }
 
-        value * self.correct[self.numoctaves as usize - 1][0] + self.correct[self.numoctaves as usize - 1][1]
+    pub fn hybrid_multi_get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
+        self.hybrid_multi(&[x, y, z, w, u, v])
     }
 
     pub fn decarpentier_swiss_get_2d(&mut self, x: f64, y: f64) -> f64 {
-        let mut sum = 0.0;
-        let mut amp = 1.0;
-        let mut dx_sum = 0.0;
-        let mut dy_sum = 0.0;
+        self.decarpentier_swiss(&[x, y])
+    }
 
-        let mut x = x;
-        let mut y = y;
+    pub fn decarpentier_swiss_get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
+        self.decarpentier_swiss(&[x, y, z, w])
+    }
 
-        x *= self.frequency;
-        y *= self.frequency;
+    pub fn decarpentier_swiss_get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
+        self.decarpentier_swiss(&[x, y, z])
+    }
 
-        for i in 0..self.numoctaves as usize {
-            if let Some(ref ii) = self.source[i] {
-                let mut bii = ii.borrow_mut();
-                let n = bii.get_2d(x + self.offset * dx_sum, y + self.offset * dy_sum);
-                let dx = bii.get_dx_2(x + self.offset * dx_sum, y + self.offset * dy_sum);
-                let dy = bii.get_dy_2(x + self.offset * dx_sum, y + self.offset * dy_sum);
-                sum += amp * (1.0 - n.abs());
-                dx_sum += amp * dx * -n;
-                dy_sum += amp * dy * -n;
-                amp *= self.gain * *(max(&0.0, min(&sum, &1.0)));
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-            }
-        }
-        sum
+    pub fn decarpentier_swiss_get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
+        self.decarpentier_swiss(&[x, y, z, w, u, v])
     }
 
-    pub fn decarpentier_swiss_get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
-        let mut sum = 0.0;
-        let mut amp = 1.0;
-        let mut dx_sum = 0.0;
-        let mut dy_sum = 0.0;
-        let mut dz_sum = 0.0;
-        let mut dw_sum = 0.0;
-
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
-        let mut w = w;
-
-        x *= self.frequency;
-        y *= self.frequency;
-        z *= self.frequency;
-        w *= self.frequency;
-
-        for i in 0..self.numoctaves as usize {
-            if let Some(ref ii) = self.source[i] {
-                let mut bii = ii.borrow_mut();
-                let n = bii.get_4d(x + self.offset * dx_sum,
-                                   y + self.offset * dy_sum,
-                                   z + self.offset * dz_sum,
-                                   w + self.offset * dw_sum);
-                let dx = bii.get_dx_4(x + self.offset * dx_sum,
-                                      y + self.offset * dy_sum,
-                                      z + self.offset * dz_sum,
-                                      w + self.offset * dw_sum);
-                let dy = bii.get_dy_4(x + self.offset * dx_sum,
-                                      y + self.offset * dy_sum,
-                                      z + self.offset * dz_sum,
-                                      w + self.offset * dw_sum);
-                let dz = bii.get_dz_4(x + self.offset * dx_sum,
-                                      y + self.offset * dy_sum,
-                                      z + self.offset * dz_sum,
-                                      w + self.offset * dw_sum);
-                let dw = bii.get_dw_4(x + self.offset * dx_sum,
-                                      y + self.offset * dy_sum,
-                                      z + self.offset * dz_sum,
-                                      w + self.offset * dw_sum);
-                sum += amp * (1.0 - n.abs());
-                dx_sum += amp * dx * -n;
-                dy_sum += amp * dy * -n;
-                dz_sum += amp * dz * -n;
-                dw_sum += amp * dw * -n;
-                amp *= self.gain * *(max(&0.0, min(&sum, &1.0)));
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-                z *= self.lacunarity;
-                w *= self.lacunarity;
-            }
-        }
-        sum
+    pub fn erosion_get_2d(&mut self, x: f64, y: f64) -> f64 {
+        self.erosion(&[x, y])
     }
 
-    pub fn decarpentier_swiss_get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
-        let mut sum = 0.0;
-        let mut amp = 1.0;
-        let mut dx_sum = 0.0;
-        let mut dy_sum = 0.0;
-        let mut dz_sum = 0.0;
+    pub fn erosion_get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
+        self.erosion(&[x, y, z])
+    }
+
+    pub fn erosion_get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
+        self.erosion(&[x, y, z, w])
+    }
 
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
+    pub fn erosion_get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
+        self.erosion(&[x, y, z, w, u, v])
+    }
+}
 
-        x *= self.frequency;
-        y *= self.frequency;
-        z *= self.frequency;
+fn sample(s: &mut ImplicitModule, p: &[f64]) -> f64 {
+    match p.len() {
+        1 => s.get_1d(p[0]),
+        2 => s.get_2d(p[0], p[1]),
+        3 => s.get_3d(p[0], p[1], p[2]),
+        4 => s.get_4d(p[0], p[1], p[2], p[3]),
+        6 => s.get_6d(p[0], p[1], p[2], p[3], p[4], p[5]),
+        _ => s.get_nd(p),
+    }
+}
 
-        for i in 0..self.numoctaves as usize {
-            if let Some(ref ii) = self.source[i] {
-                let mut bii = ii.borrow_mut();
-                let n = bii.get_3d(x + self.offset * dx_sum,
-                                   y + self.offset * dy_sum,
-                                   z + self.offset * dz_sum);
-                let dx = bii.get_dx_3(x + self.offset * dx_sum,
-                                      y + self.offset * dy_sum,
-                                      z + self.offset * dz_sum);
-                let dy = bii.get_dy_3(x + self.offset * dx_sum,
-                                      y + self.offset * dy_sum,
-                                      z + self.offset * dz_sum);
-                let dz = bii.get_dz_3(x + self.offset * dx_sum,
-                                      y + self.offset * dy_sum,
-                                      z + self.offset * dz_sum);
-                sum += amp * (1.0 - n.abs());
-                dx_sum += amp * dx * -n;
-                dy_sum += amp * dy * -n;
-                dz_sum += amp * dz * -n;
-                amp *= self.gain * *(max(&0.0, min(&sum, &1.0)));
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-                z *= self.lacunarity;
-            }
+// The derivative of s along the given axis, in the get_dx_*() convention.
+fn sample_deriv(s: &mut ImplicitModule, p: &[f64], axis: usize) -> f64 {
+    match (p.len(), axis) {
+        (2, 0) => s.get_dx_2(p[0], p[1]),
+        (2, _) => s.get_dy_2(p[0], p[1]),
+        (3, 0) => s.get_dx_3(p[0], p[1], p[2]),
+        (3, 1) => s.get_dy_3(p[0], p[1], p[2]),
+        (3, _) => s.get_dz_3(p[0], p[1], p[2]),
+        (4, 0) => s.get_dx_4(p[0], p[1], p[2], p[3]),
+        (4, 1) => s.get_dy_4(p[0], p[1], p[2], p[3]),
+        (4, 2) => s.get_dz_4(p[0], p[1], p[2], p[3]),
+        (4, _) => s.get_dw_4(p[0], p[1], p[2], p[3]),
+        (6, 0) => s.get_dx_6(p[0], p[1], p[2], p[3], p[4], p[5]),
+        (6, 1) => s.get_dy_6(p[0], p[1], p[2], p[3], p[4], p[5]),
+        (6, 2) => s.get_dz_6(p[0], p[1], p[2], p[3], p[4], p[5]),
+        (6, 3) => s.get_dw_6(p[0], p[1], p[2], p[3], p[4], p[5]),
+        (6, 4) => s.get_du_6(p[0], p[1], p[2], p[3], p[4], p[5]),
+        (6, _) => s.get_dv_6(p[0], p[1], p[2], p[3], p[4], p[5]),
+        (n, _) => {
+            let h = s.spacing();
+            let mut a = [0.0; MAX_DIMENSIONS];
+            let mut b = [0.0; MAX_DIMENSIONS];
+            a[..n].copy_from_slice(p);
+            b[..n].copy_from_slice(p);
+            a[axis] -= h;
+            b[axis] += h;
+            (s.get_nd(&a[..n]) - s.get_nd(&b[..n])) / h
         }
-        return sum;
     }
+}
 
-    pub fn decarpentier_swiss_get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
-        let mut sum = 0.0;
-        let mut amp = 1.0;
-        let mut dx_sum = 0.0;
-        let mut dy_sum = 0.0;
-        let mut dz_sum = 0.0;
-        let mut dw_sum = 0.0;
-        let mut du_sum = 0.0;
-        let mut dv_sum = 0.0;
-
-        let mut x = x;
-        let mut y = y;
-        let mut z = z;
-        let mut w = w;
-        let mut u = u;
-        let mut v = v;
-
-        x *= self.frequency;
-        y *= self.frequency;
-        z *= self.frequency;
-        w *= self.frequency;
-        u *= self.frequency;
-        v *= self.frequency;
-
-        for i in 0..self.numoctaves as usize {
-            if let Some(ref ii) = self.source[i] {
-                let mut bii = ii.borrow_mut();
-                let n = bii.get_6d(x + self.offset * dx_sum,
-                                   y + self.offset * dy_sum,
-                                   z + self.offset * dz_sum,
-                                   w + self.offset * dw_sum,
-                                   u + self.offset * du_sum,
-                                   v + self.offset * dv_sum);
-                let dx = bii.get_dx_6(x + self.offset * dx_sum,
-                                      y + self.offset * dy_sum,
-                                      z + self.offset * dx_sum,
-                                      w + self.offset * dw_sum,
-                                      u + self.offset * du_sum,
-                                      v + self.offset * dv_sum);
-                let dy = bii.get_dy_6(x + self.offset * dx_sum,
-                                      y + self.offset * dy_sum,
-                                      z + self.offset * dz_sum,
-                                      w + self.offset * dw_sum,
-                                      u + self.offset * du_sum,
-                                      v + self.offset * dv_sum);
-                let dz = bii.get_dz_6(x + self.offset * dx_sum,
-                                      y + self.offset * dy_sum,
-                                      z + self.offset * dz_sum,
-                                      w + self.offset * dw_sum,
-                                      u + self.offset * du_sum,
-                                      v + self.offset * dv_sum);
-                let dw = bii.get_dw_6(x + self.offset * dx_sum,
-                                      y + self.offset * dy_sum,
-                                      z + self.offset * dz_sum,
-                                      w + self.offset * dw_sum,
-                                      u + self.offset * du_sum,
-                                      v + self.offset * dv_sum);
-                let du = bii.get_du_6(x + self.offset * dx_sum,
-                                      y + self.offset * dy_sum,
-                                      z + self.offset * dz_sum,
-                                      w + self.offset * dw_sum,
-                                      u + self.offset * du_sum,
-                                      v + self.offset * dv_sum);
-                let dv = bii.get_dv_6(x + self.offset * dx_sum,
-                                      y + self.offset * dy_sum,
-                                      z + self.offset * dz_sum,
-                                      w + self.offset * dw_sum,
-                                      u + self.offset * du_sum,
-                                      v + self.offset * dv_sum);
-                sum += amp * (1.0 - n.abs());
-                dx_sum += amp * dx * -n;
-                dy_sum += amp * dy * -n;
-                dz_sum += amp * dz * -n;
-                dw_sum += amp * dw * -n;
-                du_sum += amp * du * -n;
-                dv_sum += amp * dv * -n;
-                amp *= self.gain * *(max(&0.0, min(&sum, &1.0)));
-                x *= self.lacunarity;
-                y *= self.lacunarity;
-                z *= self.lacunarity;
-                w *= self.lacunarity;
-                u *= self.lacunarity;
-                v *= self.lacunarity;
+// Gradient of an octave source along its first `axes` axes, used for pseudo-advection and erosion. It is analytic
+// where the source supports it, and taken by central differences otherwise.
+fn source_gradient(s: &mut ImplicitModule, p: &[f64], h: f64, axes: usize) -> [f64; MAX_DIMENSIONS] {
+    match p.len() {
+        2 => {
+            if let Some(g) = s.get_analytic_gradient_2(p[0], p[1]) {
+                let mut r = [0.0; MAX_DIMENSIONS];
+                r[..2].copy_from_slice(&g);
+                return r;
             }
         }
-        sum
+        3 => {
+            if let Some(g) = s.get_analytic_gradient_3(p[0], p[1], p[2]) {
+                let mut r = [0.0; MAX_DIMENSIONS];
+                r[..3].copy_from_slice(&g);
+                return r;
+            }
+        }
+        _ => {}
+    }
+
+    let mut g = [0.0; MAX_DIMENSIONS];
+    let mut a = [0.0; MAX_DIMENSIONS];
+    let mut b = [0.0; MAX_DIMENSIONS];
+    for (k, gk) in g.iter_mut().enumerate().take(axes) {
+        a[..p.len()].copy_from_slice(p);
+        b[..p.len()].copy_from_slice(p);
+        a[k] += h;
+        b[k] -= h;
+        *gk = (sample(s, &a[..p.len()]) - sample(s, &b[..p.len()])) / (2.0 * h);
+    }
+    g
+}
+
+fn advect(adv: &mut [f64; 3], g: &[f64], amp: f64) {
+    for (a, g) in adv.iter_mut().zip(g.iter()) {
+        *a += g * amp;
     }
 }
 
 impl ImplicitModule for ImplicitFractal {
     fn set_seed(&mut self, seed: u32) {
-        for c in 0..MAX_SOURCES as u32 {
+        for c in 0..self.source.len() as u32 {
             if let Some(ref cc) = self.source[c as usize] {
                 cc.borrow_mut().set_seed(seed + c * 300)
             }
         }
+        self.seed = Some(seed);
+    }
+
+    // Evaluation is band-limited to the footprint: see ImplicitModule::set_footprint().
+    fn set_footprint(&mut self, f: f64) {
+        self.footprint = f.abs();
+        self.forward_footprint();
     }
 
+    fn get_1d(&mut self, x: f64) -> f64 {
+        self.get_nd(&[x])
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         match self.ftype {
             FractalType::FBM => self.fbm_get_2d(x, y),
@@ -1231,6 +1121,7 @@ impl ImplicitModule for ImplicitFractal {
             FractalType::Multi => self.multi_get_2d(x, y),
             FractalType::HybridMulti => self.hybrid_multi_get_2d(x, y),
             FractalType::DecarpentierSwiss => self.decarpentier_swiss_get_2d(x, y),
+            FractalType::Erosion => self.erosion_get_2d(x, y),
         }
     }
     fn get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
@@ -1241,6 +1132,7 @@ impl ImplicitModule for ImplicitFractal {
             FractalType::Multi => self.multi_get_3d(x, y, z),
             FractalType::HybridMulti => self.hybrid_multi_get_3d(x, y, z),
             FractalType::DecarpentierSwiss => self.decarpentier_swiss_get_3d(x, y, z),
+            FractalType::Erosion => self.erosion_get_3d(x, y, z),
         }
     }
     fn get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
@@ -1251,6 +1143,7 @@ impl ImplicitModule for ImplicitFractal {
             FractalType::Multi => self.multi_get_4d(x, y, z, w),
             FractalType::HybridMulti => self.hybrid_multi_get_4d(x, y, z, w),
             FractalType::DecarpentierSwiss => self.decarpentier_swiss_get_4d(x, y, z, w),
+            FractalType::Erosion => self.erosion_get_4d(x, y, z, w),
         }
     }
     fn get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
@@ -1261,6 +1154,19 @@ impl ImplicitModule for ImplicitFractal {
             FractalType::Multi => self.multi_get_6d(x, y, z, w, u, v),
             FractalType::HybridMulti => self.hybrid_multi_get_6d(x, y, z, w, u, v),
             FractalType::DecarpentierSwiss => self.decarpentier_swiss_get_6d(x, y, z, w, u, v),
+            FractalType::Erosion => self.erosion_get_6d(x, y, z, w, u, v),
+        }
+    }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        let p = &p[..p.len().min(MAX_DIMENSIONS)];
+        match self.ftype {
+            FractalType::FBM => self.fbm(p),
+            FractalType::RidgedMulti => self.ridged_multi(p),
+            FractalType::Billow => self.billow(p),
+            FractalType::Multi => self.multi(p),
+            FractalType::HybridMulti => self.hybrid_multi(p),
+            FractalType::DecarpentierSwiss => self.decarpentier_swiss(p),
+            FractalType::Erosion => self.erosion(p),
         }
     }
 
@@ -1271,4 +1177,17 @@ impl ImplicitModule for ImplicitFractal {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn get_analytic_gradient_2(&mut self, x: f64, y: f64) -> Option<[f64; 2]> {
+        match self.ftype {
+            FractalType::FBM => self.fbm_gradient_2d(x, y),
+            _ => None,
+        }
+    }
+    fn get_analytic_gradient_3(&mut self, x: f64, y: f64, z: f64) -> Option<[f64; 3]> {
+        match self.ftype {
+            FractalType::FBM => self.fbm_gradient_3d(x, y, z),
+            _ => None,
+        }
+    }
 }
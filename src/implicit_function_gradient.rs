@@ -1,5 +1,6 @@
 use super::implicit_base::ImplicitModuleBase;
 use super::{ImplicitModule, ScalarParameter};
+use super::noise_gen::MAX_DIMENSIONS;
 
 use std::rc::Rc;
 use std::cell::RefCell;
@@ -46,6 +47,12 @@ impl ImplicitFunctionGradient {
 }
 
 impl ImplicitModule for ImplicitFunctionGradient {
+    fn get_1d(&mut self, x: f64) -> f64 {
+        match self.axis {
+            FunctionGradientAxis::X => (self.source.get_1d(x - self.spacing) - self.source.get_1d(x + self.spacing)) / self.spacing,
+            _ => 0.0,
+        }
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         match self.axis {
             FunctionGradientAxis::X => (self.source.get_2d(x - self.spacing, y) - self.source.get_2d(x + self.spacing, y)) / self.spacing,
@@ -86,6 +93,27 @@ impl ImplicitModule for ImplicitFunctionGradient {
             FunctionGradientAxis::V => (self.source.get_6d(x, y, z, w, u, v - self.spacing) - self.source.get_6d(x, y, z, w, u, v + self.spacing)) / self.spacing,
         }
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        let axis = match self.axis {
+            FunctionGradientAxis::X => 0,
+            FunctionGradientAxis::Y => 1,
+            FunctionGradientAxis::Z => 2,
+            FunctionGradientAxis::W => 3,
+            FunctionGradientAxis::U => 4,
+            FunctionGradientAxis::V => 5,
+        };
+        let n = p.len().min(MAX_DIMENSIONS);
+        if axis >= n {
+            return 0.0;
+        }
+        let mut a = [0.0; MAX_DIMENSIONS];
+        let mut b = [0.0; MAX_DIMENSIONS];
+        a[..n].copy_from_slice(&p[..n]);
+        b[..n].copy_from_slice(&p[..n]);
+        a[axis] -= self.spacing;
+        b[axis] += self.spacing;
+        (self.source.get_nd(&a[..n]) - self.source.get_nd(&b[..n])) / self.spacing
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -93,4 +121,8 @@ impl ImplicitModule for ImplicitFunctionGradient {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
 }
@@ -55,6 +55,9 @@ impl ImplicitGain {
 }
 
 impl ImplicitModule for ImplicitGain {
+    fn get_1d(&mut self, x: f64) -> f64 {
+        gain(self.gain.get_1d(x), self.source.get_1d(x))
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         gain(self.gain.get_2d(x,y), self.source.get_2d(x,y))    	
     }
@@ -67,6 +70,9 @@ impl ImplicitModule for ImplicitGain {
     fn get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
         gain(self.gain.get_6d(x,y,z,w,u,v), self.source.get_6d(x,y,z,w, u, v))    	
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        gain(self.gain.get_nd(p), self.source.get_nd(p))
+    }
 
     fn spacing(&self) -> f64 {
     	self.base.spacing
@@ -74,4 +80,8 @@ impl ImplicitModule for ImplicitGain {
     fn set_deriv_spacing(&mut self, s: f64) {
     	self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
 }
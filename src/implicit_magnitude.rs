@@ -70,6 +70,9 @@ impl ImplicitMagnitude {
 }
 
 impl ImplicitModule for ImplicitMagnitude {
+    fn get_1d(&mut self, x: f64) -> f64 {
+        self.x.get_1d(x).abs()
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         let xx = self.x.get_2d(x, y);
         let yy = self.y.get_2d(x, y);
@@ -97,6 +100,15 @@ impl ImplicitModule for ImplicitMagnitude {
         let vv = self.v.get_6d(x, y, z, w, u, v);
         (xx * xx + yy * yy + zz * zz + ww * ww + uu * uu + vv * vv).sqrt()
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        let xx = self.x.get_nd(p);
+        let yy = self.y.get_nd(p);
+        let zz = self.z.get_nd(p);
+        let ww = self.w.get_nd(p);
+        let uu = self.u.get_nd(p);
+        let vv = self.v.get_nd(p);
+        (xx * xx + yy * yy + zz * zz + ww * ww + uu * uu + vv * vv).sqrt()
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -104,4 +116,13 @@ impl ImplicitModule for ImplicitMagnitude {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.x.set_footprint(f);
+        self.y.set_footprint(f);
+        self.z.set_footprint(f);
+        self.w.set_footprint(f);
+        self.u.set_footprint(f);
+        self.v.set_footprint(f);
+    }
 }
@@ -43,6 +43,14 @@ impl ImplicitModifier {
 }
 
 impl ImplicitModule for ImplicitModifier {
+    fn get_1d(&mut self, x: f64) -> f64 {
+        if let Some(ref s) = self.source {
+            let val = clamp(s.borrow_mut().get_1d(x), 0.0, 1.0);
+            self.curve.linear_interp(val)
+        } else {
+            0.0
+        }
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         if let Some(ref s) = self.source {
             let mut v = s.borrow_mut().get_2d(x, y);
@@ -86,6 +94,14 @@ impl ImplicitModule for ImplicitModifier {
             0.0
         }
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        if let Some(ref s) = self.source {
+            let val = clamp(s.borrow_mut().get_nd(p), 0.0, 1.0);
+            self.curve.linear_interp(val)
+        } else {
+            0.0
+        }
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -93,4 +109,10 @@ impl ImplicitModule for ImplicitModifier {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        if let Some(ref s) = self.source {
+            s.borrow_mut().set_footprint(f);
+        }
+    }
 }
@@ -1,5 +1,6 @@
 use super::implicit_base::ImplicitModuleBase;
 use super::{ImplicitModule, ScalarParameter};
+use super::noise_gen::MAX_DIMENSIONS;
 
 use std::rc::Rc;
 use std::cell::RefCell;
@@ -43,6 +44,14 @@ impl ImplicitNormalizeCoords {
 }
 
 impl ImplicitModule for ImplicitNormalizeCoords {
+    fn get_1d(&mut self, x: f64) -> f64 {
+        if x == 0.0 {
+            return self.source.get_1d(x);
+        }
+
+        let r = self.length.get_1d(x);
+        self.source.get_1d(x / x.abs() * r)
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         if x == 0.0 && y == 0.0 {
             return self.source.get_2d(x, y);
@@ -84,6 +93,20 @@ impl ImplicitModule for ImplicitNormalizeCoords {
                            u / len * r,
                            v / len * r)
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        let n = p.len().min(MAX_DIMENSIONS);
+        let len = p[..n].iter().map(|c| c * c).sum::<f64>().sqrt();
+        if len == 0.0 {
+            return self.source.get_nd(p);
+        }
+
+        let r = self.length.get_nd(p);
+        let mut q = [0.0; MAX_DIMENSIONS];
+        for (qk, pk) in q.iter_mut().zip(p.iter()) {
+            *qk = pk / len * r;
+        }
+        self.source.get_nd(&q[..n])
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -91,4 +114,8 @@ impl ImplicitModule for ImplicitNormalizeCoords {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
 }
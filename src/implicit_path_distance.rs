@@ -0,0 +1,70 @@
+use super::implicit_base::ImplicitModuleBase;
+use super::ImplicitModule;
+use super::utility::clamp;
+
+/// Outputs the distance in the XY plane from the input point to the nearest of a set of polylines, such as rivers extracted with [`extract_rivers()`](hydrology/fn.extract_rivers.html), roads or borders. The remaining axes are ignored. Fed through a curve or a clamp, the distance makes masks and falloffs that carve valleys along the paths, or keep features away from them. With no paths, the distance is infinite.
+pub struct ImplicitPathDistance {
+    base: ImplicitModuleBase,
+    paths: Vec<Vec<[f64; 2]>>,
+}
+
+impl ImplicitPathDistance {
+    pub fn new(paths: Vec<Vec<[f64; 2]>>) -> ImplicitPathDistance {
+        ImplicitPathDistance {
+            base: Default::default(),
+            paths,
+        }
+    }
+
+    pub fn add_path(&mut self, path: Vec<[f64; 2]>) {
+        self.paths.push(path);
+    }
+
+    pub fn clear_paths(&mut self) {
+        self.paths.clear();
+    }
+
+    pub fn get_paths(&self) -> &[Vec<[f64; 2]>] {
+        &self.paths
+    }
+
+    fn distance(&self, x: f64, y: f64) -> f64 {
+        let mut best = f64::INFINITY;
+        for path in self.paths.iter() {
+            if path.len() == 1 {
+                best = best.min((x - path[0][0]).hypot(y - path[0][1]));
+            }
+            for s in path.windows(2) {
+                let (ax, ay) = (s[0][0], s[0][1]);
+                let (dx, dy) = (s[1][0] - ax, s[1][1] - ay);
+                let len2 = dx * dx + dy * dy;
+                let t = if len2 > 0.0 { clamp(((x - ax) * dx + (y - ay) * dy) / len2, 0.0, 1.0) } else { 0.0 };
+                best = best.min((x - ax - t * dx).hypot(y - ay - t * dy));
+            }
+        }
+        best
+    }
+}
+
+impl ImplicitModule for ImplicitPathDistance {
+    fn get_2d(&mut self, x: f64, y: f64) -> f64 {
+        self.distance(x, y)
+    }
+    fn get_3d(&mut self, x: f64, y: f64, _: f64) -> f64 {
+        self.distance(x, y)
+    }
+    fn get_4d(&mut self, x: f64, y: f64, _: f64, _: f64) -> f64 {
+        self.distance(x, y)
+    }
+    fn get_6d(&mut self, x: f64, y: f64, _: f64, _: f64, _: f64, _: f64) -> f64 {
+        self.distance(x, y)
+    }
+
+    fn spacing(&self) -> f64 {
+        self.base.spacing
+    }
+
+    fn set_deriv_spacing(&mut self, s: f64) {
+        self.base.spacing = s;
+    }
+}
@@ -35,6 +35,9 @@ impl ImplicitPow {
 }
 
 impl ImplicitModule for ImplicitPow {
+    fn get_1d(&mut self, x: f64) -> f64 {
+        self.source.get_1d(x).powf(self.power.get_1d(x))
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         self.source.get_2d(x, y).powf(self.power.get_2d(x, y))
     }
@@ -47,6 +50,9 @@ impl ImplicitModule for ImplicitPow {
     fn get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
         self.source.get_6d(x, y, z, w, u, v).powf(self.power.get_6d(x, y, z, w, u, v))
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        self.source.get_nd(p).powf(self.power.get_nd(p))
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -54,4 +60,8 @@ impl ImplicitModule for ImplicitPow {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
 }
@@ -65,4 +65,9 @@ impl ImplicitModule for ImplicitRgbaDotProduct {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source_1.set_footprint(f);
+        self.source_2.set_footprint(f);
+    }
 }
@@ -1,7 +1,8 @@
 /// The documentation is taken from original [C++ library by Joshua Tippetts](http://accidentalnoise.sourceforge.net/docs.html).
 
-use super::implicit_base::{ImplicitModuleBase, ScalarParameter};
+use super::implicit_base::{ImplicitModuleBase, ScalarParameter, get_nd_padded};
 use super::ImplicitModule;
+use super::noise_gen::MAX_DIMENSIONS;
 
 use std::rc::Rc;
 use std::cell::RefCell;
@@ -132,6 +133,14 @@ impl ImplicitRotateDomain {
         let ay = self.ay.get_6d(x, y, z, w, u, v);
         let az = self.az.get_6d(x, y, z, w, u, v);
 
+        self.calculate_rot_matrix(angledeg, ax, ay, az);
+    }
+    fn calculate_rot_matrix_nd(&mut self, p: &[f64]) {
+        let angledeg = self.angledeg.get_nd(p);
+        let ax = self.ax.get_nd(p);
+        let ay = self.ay.get_nd(p);
+        let az = self.az.get_nd(p);
+
         self.calculate_rot_matrix(angledeg, ax, ay, az);
     }
 }
@@ -167,6 +176,20 @@ impl ImplicitModule for ImplicitRotateDomain {
         let nz = (self.rotmatrix[0][2] * x) + (self.rotmatrix[1][2] * y) + (self.rotmatrix[2][2] * z);
         self.source.get_6d(nx, ny, nz, w, u, v)
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        if p.len() < 3 {
+            return get_nd_padded(self, p);
+        }
+        self.calculate_rot_matrix_nd(p);
+        let n = p.len().min(MAX_DIMENSIONS);
+        let mut q = [0.0; MAX_DIMENSIONS];
+        q[..n].copy_from_slice(&p[..n]);
+        let (x, y, z) = (p[0], p[1], p[2]);
+        q[0] = (self.rotmatrix[0][0] * x) + (self.rotmatrix[1][0] * y) + (self.rotmatrix[2][0] * z);
+        q[1] = (self.rotmatrix[0][1] * x) + (self.rotmatrix[1][1] * y) + (self.rotmatrix[2][1] * z);
+        q[2] = (self.rotmatrix[0][2] * x) + (self.rotmatrix[1][2] * y) + (self.rotmatrix[2][2] * z);
+        self.source.get_nd(&q[..n])
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -174,4 +197,8 @@ impl ImplicitModule for ImplicitRotateDomain {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
 }
@@ -35,6 +35,11 @@ impl ImplicitSawTooth {
 }
 
 impl ImplicitModule for ImplicitSawTooth {
+    fn get_1d(&mut self, x: f64) -> f64 {
+        let val = self.source.get_1d(x);
+        let p = self.period.get_1d(x);
+        2.0 * (val / p - (0.5 + val / p).floor())
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         let val = self.source.get_2d(x, y);
         let p = self.period.get_2d(x, y);
@@ -55,6 +60,11 @@ impl ImplicitModule for ImplicitSawTooth {
         let p = self.period.get_6d(x, y, z, w, u, v);
         2.0 * (val / p - (0.5 + val / p).floor())
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        let val = self.source.get_nd(p);
+        let p = self.period.get_nd(p);
+        2.0 * (val / p - (0.5 + val / p).floor())
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -62,4 +72,8 @@ impl ImplicitModule for ImplicitSawTooth {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
 }
@@ -2,11 +2,44 @@
 
 use super::implicit_base::{ImplicitModuleBase, ScalarParameter};
 use super::ImplicitModule;
+use super::noise_gen::MAX_DIMENSIONS;
 
 use std::rc::Rc;
 use std::cell::RefCell;
 
-/// ScaleDomain applies a domain scaling to the coordinates input to the function.
+/// ScaleDomain applies a domain scaling to the coordinates input to the function. In [`get_nd()`](trait.ImplicitModule.html#method.get_nd), the axes beyond the sixth are passed through unscaled.
+///
+/// ```
+/// use anl::{ImplicitScaleDomain, ImplicitModule};
+/// use std::rc::Rc;
+/// use std::cell::{Cell, RefCell};
+///
+/// // Records the footprint it receives.
+/// struct Probe(Rc<Cell<f64>>);
+/// impl ImplicitModule for Probe {
+///     fn get_2d(&mut self, _: f64, _: f64) -> f64 { 0.0 }
+///     fn get_3d(&mut self, _: f64, _: f64, _: f64) -> f64 { 0.0 }
+///     fn get_4d(&mut self, _: f64, _: f64, _: f64, _: f64) -> f64 { 0.0 }
+///     fn get_6d(&mut self, _: f64, _: f64, _: f64, _: f64, _: f64, _: f64) -> f64 { 0.0 }
+///     fn get_nd(&mut self, _: &[f64]) -> f64 { 0.0 }
+///     fn spacing(&self) -> f64 { 0.0 }
+///     fn set_deriv_spacing(&mut self, _: f64) {}
+///     fn set_footprint(&mut self, f: f64) { self.0.set(f) }
+/// }
+///
+/// let footprint = Rc::new(Cell::new(0.0));
+/// let mut scale = ImplicitScaleDomain::new(0.5, 0.5, 0.5, 0.5, 0.5, 0.25);
+/// scale.set_source_module(Rc::new(RefCell::new(Probe(footprint.clone()))));
+/// scale.set_footprint(0.1);
+///
+/// scale.get_nd(&[0.0; 6]);
+/// assert_eq!(footprint.get(), 0.05);
+/// // The seventh and eighth axes pass through unscaled, at a scale of 1.
+/// scale.get_nd(&[0.0; 7]);
+/// assert_eq!(footprint.get(), 0.1);
+/// scale.get_nd(&[0.0; 8]);
+/// assert_eq!(footprint.get(), 0.1);
+/// ```
 pub struct ImplicitScaleDomain {
     base: ImplicitModuleBase,
     source: ScalarParameter,
@@ -16,6 +49,8 @@ pub struct ImplicitScaleDomain {
     sw: ScalarParameter,
     su: ScalarParameter,
     sv: ScalarParameter,
+    footprint: Option<f64>,
+    forwarded_footprint: f64,
 }
 
 impl ImplicitScaleDomain {
@@ -29,6 +64,8 @@ impl ImplicitScaleDomain {
             sv: ScalarParameter::Value(v),
             base: Default::default(),
             source: ScalarParameter::Value(0.0),
+            footprint: None,
+            forwarded_footprint: -1.0,
         }
     }
 
@@ -47,6 +84,7 @@ impl ImplicitScaleDomain {
 
     pub fn set_source_module(&mut self, m: Rc<RefCell<ImplicitModule>>) {
         self.source = ScalarParameter::Source(m);
+        self.forwarded_footprint = -1.0;
     }
 
     pub fn set_source_value(&mut self, v: f64) {
@@ -89,26 +127,54 @@ impl ImplicitScaleDomain {
     pub fn set_v_scale(&mut self, v: Rc<RefCell<ImplicitModule>>) {
         self.sv = ScalarParameter::Source(v)
     }
+
+    // Passes the footprint on to the source, scaled by the largest constant scale factor of the axes in use; scales
+    // driven by functions are taken as 1, and so are the unscaled axes beyond the sixth. It is only passed on again when the scaled footprint changes.
+    fn update_footprint(&mut self, axes: usize) {
+        let f = match self.footprint {
+            Some(f) => f,
+            None => return,
+        };
+        let mut scale: f64 = if axes > 6 { 1.0 } else { 0.0 };
+        for s in [&self.sx, &self.sy, &self.sz, &self.sw, &self.su, &self.sv].iter().take(axes) {
+            scale = scale.max(match **s {
+                ScalarParameter::Value(v) => v.abs(),
+                ScalarParameter::Source(_) => 1.0,
+            });
+        }
+        if f * scale != self.forwarded_footprint {
+            self.forwarded_footprint = f * scale;
+            self.source.set_footprint(f * scale);
+        }
+    }
 }
 
 impl ImplicitModule for ImplicitScaleDomain {
     fn set_seed(&mut self, _: u32) {}
 
+    fn get_1d(&mut self, x: f64) -> f64 {
+        self.update_footprint(1);
+        self.source.get_1d(x * self.sx.get_1d(x))
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
+        self.update_footprint(2);
         self.source.get_2d(x * self.sx.get_2d(x, y), y * self.sy.get_2d(x, y))
     }
     fn get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
+        self.update_footprint(3);
         self.source.get_3d(x * self.sx.get_3d(x, y, z),
                            y * self.sy.get_3d(x, y, z),
                            z * self.sz.get_3d(x, y, z))
     }
     fn get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
+        self.update_footprint(4);
         self.source.get_4d(x * self.sx.get_4d(x, y, z, w),
                            y * self.sy.get_4d(x, y, z, w),
                            z * self.sz.get_4d(x, y, z, w),
                            w * self.sw.get_4d(x, y, z, w))
     }
     fn get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
+        self.update_footprint(6);
         self.source.get_6d(x * self.sx.get_6d(x, y, z, w, u, v),
                            y * self.sy.get_6d(x, y, z, w, u, v),
                            z * self.sz.get_6d(x, y, z, w, u, v),
@@ -116,6 +182,17 @@ impl ImplicitModule for ImplicitScaleDomain {
                            u * self.su.get_6d(x, y, z, w, u, v),
                            v * self.sv.get_6d(x, y, z, w, u, v))
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        self.update_footprint(p.len());
+        let n = p.len().min(MAX_DIMENSIONS);
+        let mut q = [0.0; MAX_DIMENSIONS];
+        q[..n].copy_from_slice(&p[..n]);
+        let mut scales = [&mut self.sx, &mut self.sy, &mut self.sz, &mut self.sw, &mut self.su, &mut self.sv];
+        for (qk, s) in q.iter_mut().zip(scales.iter_mut()).take(n) {
+            *qk *= s.get_nd(p);
+        }
+        self.source.get_nd(&q[..n])
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -124,4 +201,9 @@ impl ImplicitModule for ImplicitScaleDomain {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    // The footprint reaches the source on the next sample, once the axes in use are known.
+    fn set_footprint(&mut self, f: f64) {
+        self.footprint = Some(f);
+    }
 }
@@ -49,6 +49,9 @@ impl ImplicitScaleOffset {
 impl ImplicitModule for ImplicitScaleOffset {
     fn set_seed(&mut self, _: u32) {}
 
+    fn get_1d(&mut self, x: f64) -> f64 {
+        self.source.get_1d(x) * self.scale.get_1d(x) + self.offset.get_1d(x)
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         self.source.get_2d(x, y) * self.scale.get_2d(x, y) + self.offset.get_2d(x, y)
     }
@@ -61,6 +64,9 @@ impl ImplicitModule for ImplicitScaleOffset {
     fn get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
         self.source.get_6d(x, y, z, w, u, v) * self.scale.get_6d(x, y, z, w, u, v) + self.offset.get_6d(x, y, z, w, u, v)
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        self.source.get_nd(p) * self.scale.get_nd(p) + self.offset.get_nd(p)
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -69,4 +75,8 @@ impl ImplicitModule for ImplicitScaleOffset {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
 }
@@ -73,6 +73,32 @@ impl ImplicitSelect {
 impl ImplicitModule for ImplicitSelect {
     fn set_seed(&mut self, _: u32) {}
 
+    fn get_1d(&mut self, x: f64) -> f64 {
+        let control = self.control.get_1d(x);
+        let falloff = self.falloff.get_1d(x);
+        let threshold = self.threshold.get_1d(x);
+
+        if falloff > 0. {
+            if control < (threshold - falloff) {
+                self.low.get_1d(x)
+            } else if control > (threshold + falloff) {
+                self.high.get_1d(x)
+            } else {
+                let lower = threshold - falloff;
+                let upper = threshold + falloff;
+                let blend = quintic_blend((control - lower) / (upper - lower));
+                lerp(blend,
+                     self.low.get_1d(x),
+                     self.high.get_1d(x))
+            }
+        } else {
+            if control < threshold {
+                self.low.get_1d(x)
+            } else {
+                self.high.get_1d(x)
+            }
+        }
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         let control = self.control.get_2d(x, y);
         let falloff = self.falloff.get_2d(x, y);
@@ -173,6 +199,32 @@ impl ImplicitModule for ImplicitSelect {
             }
         }
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        let control = self.control.get_nd(p);
+        let falloff = self.falloff.get_nd(p);
+        let threshold = self.threshold.get_nd(p);
+
+        if falloff > 0. {
+            if control < (threshold - falloff) {
+                self.low.get_nd(p)
+            } else if control > (threshold + falloff) {
+                self.high.get_nd(p)
+            } else {
+                let lower = threshold - falloff;
+                let upper = threshold + falloff;
+                let blend = quintic_blend((control - lower) / (upper - lower));
+                lerp(blend,
+                     self.low.get_nd(p),
+                     self.high.get_nd(p))
+            }
+        } else {
+            if control < threshold {
+                self.low.get_nd(p)
+            } else {
+                self.high.get_nd(p)
+            }
+        }
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -181,4 +233,9 @@ impl ImplicitModule for ImplicitSelect {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.low.set_footprint(f);
+        self.high.set_footprint(f);
+    }
 }
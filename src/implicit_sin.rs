@@ -29,6 +29,9 @@ impl ImplicitSin {
 }
 
 impl ImplicitModule for ImplicitSin {
+    fn get_1d(&mut self, x: f64) -> f64 {
+        self.source.get_1d(x).sin()
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         self.source.get_2d(x, y).sin()
     }
@@ -41,6 +44,9 @@ impl ImplicitModule for ImplicitSin {
     fn get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
         self.source.get_6d(x, y, z, w, u, v).sin()
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        self.source.get_nd(p).sin()
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -48,4 +54,8 @@ impl ImplicitModule for ImplicitSin {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
 }
@@ -168,4 +168,14 @@ impl ImplicitModule for ImplicitSphere {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.cx.set_footprint(f);
+        self.cy.set_footprint(f);
+        self.cz.set_footprint(f);
+        self.cw.set_footprint(f);
+        self.cu.set_footprint(f);
+        self.cv.set_footprint(f);
+        self.radius.set_footprint(f);
+    }
 }
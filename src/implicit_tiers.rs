@@ -63,6 +63,10 @@ impl ImplicitTiers {
 }
 
 impl ImplicitModule for ImplicitTiers {
+    fn get_1d(&mut self, x: f64) -> f64 {
+        let val = self.source.get_1d(x);
+        self.get(val)
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         let val = self.source.get_2d(x, y);
         self.get(val)
@@ -79,6 +83,10 @@ impl ImplicitModule for ImplicitTiers {
         let val = self.source.get_6d(x, y, z, w, u, v);
         self.get(val)
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        let val = self.source.get_nd(p);
+        self.get(val)
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -86,4 +94,8 @@ impl ImplicitModule for ImplicitTiers {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
 }
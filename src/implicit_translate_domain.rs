@@ -2,11 +2,12 @@
 
 use super::implicit_base::{ImplicitModuleBase, ScalarParameter};
 use super::ImplicitModule;
+use super::noise_gen::MAX_DIMENSIONS;
 
 use std::rc::Rc;
 use std::cell::RefCell;
 
-/// TranslateDomain is used to translate the input coordinates of a function. Each axis is specifiable as a constant or noise source. This application of domain transformation is commonly called turbulence and is useful in generating many types of effects. 
+/// TranslateDomain is used to translate the input coordinates of a function. Each axis is specifiable as a constant or noise source. This application of domain transformation is commonly called turbulence and is useful in generating many types of effects. In [`get_nd()`](trait.ImplicitModule.html#method.get_nd), the axes beyond the sixth are passed through untranslated.
 pub struct ImplicitTranslateDomain {
     base: ImplicitModuleBase,
     source: ScalarParameter,
@@ -92,6 +93,9 @@ impl ImplicitTranslateDomain {
 impl ImplicitModule for ImplicitTranslateDomain {
     fn set_seed(&mut self, _: u32) {}
 
+    fn get_1d(&mut self, x: f64) -> f64 {
+        self.source.get_1d(x + self.ax.get_1d(x))
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         self.source.get_2d(x + self.ax.get_2d(x, y), y + self.ay.get_2d(x, y))
     }
@@ -114,6 +118,16 @@ impl ImplicitModule for ImplicitTranslateDomain {
                            u + self.au.get_6d(x, y, z, w, u, v),
                            v + self.av.get_6d(x, y, z, w, u, v))
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        let n = p.len().min(MAX_DIMENSIONS);
+        let mut q = [0.0; MAX_DIMENSIONS];
+        q[..n].copy_from_slice(&p[..n]);
+        let mut offsets = [&mut self.ax, &mut self.ay, &mut self.az, &mut self.aw, &mut self.au, &mut self.av];
+        for (qk, a) in q.iter_mut().zip(offsets.iter_mut()).take(n) {
+            *qk += a.get_nd(p);
+        }
+        self.source.get_nd(&q[..n])
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -122,4 +136,8 @@ impl ImplicitModule for ImplicitTranslateDomain {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
 }
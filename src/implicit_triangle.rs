@@ -44,6 +44,12 @@ impl ImplicitTriangle {
 }
 
 impl ImplicitModule for ImplicitTriangle {
+    fn get_1d(&mut self, x: f64) -> f64 {
+        let val = self.source.get_1d(x);
+        let period = self.period.get_1d(x);
+        let offset = self.offset.get_1d(x);
+		get(val, period, offset)
+    }
     fn get_2d(&mut self, x: f64, y: f64) -> f64 {
         let val = self.source.get_2d(x, y);
         let period = self.period.get_2d(x, y);
@@ -68,6 +74,12 @@ impl ImplicitModule for ImplicitTriangle {
         let offset = self.offset.get_6d(x, y, z, w, u, v);
 		get(val, period, offset)
     }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        let val = self.source.get_nd(p);
+        let period = self.period.get_nd(p);
+        let offset = self.offset.get_nd(p);
+		get(val, period, offset)
+    }
 
     fn spacing(&self) -> f64 {
         self.base.spacing
@@ -75,6 +87,10 @@ impl ImplicitModule for ImplicitTriangle {
     fn set_deriv_spacing(&mut self, s: f64) {
         self.base.spacing = s;
     }
+
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
 }
 
 #[inline(always)]
@@ -0,0 +1,171 @@
+use super::implicit_base::{ImplicitModuleBase, ScalarParameter};
+use super::ImplicitModule;
+use super::implicit_fractal::{ImplicitFractal, FractalType};
+use super::implicit_basis_function::{BasisType, InterpType};
+use super::noise_gen::MAX_DIMENSIONS;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+// Offsets of the sampling position of each distortion fractal, so that the axes are not displaced alike. The x, y and
+// z rows are the ones used by libnoise; the other rows and columns extend the table in the same spirit.
+const TURBULENCE_OFFSETS: [[f64; MAX_DIMENSIONS]; MAX_DIMENSIONS] = [
+    [12414.0 / 65536.0, 65124.0 / 65536.0, 31337.0 / 65536.0, 7205.0 / 65536.0, 48661.0 / 65536.0, 22950.0 / 65536.0, 39517.0 / 65536.0, 16083.0 / 65536.0],
+    [26519.0 / 65536.0, 18128.0 / 65536.0, 60493.0 / 65536.0, 39102.0 / 65536.0, 5873.0 / 65536.0, 57314.0 / 65536.0, 21846.0 / 65536.0, 44270.0 / 65536.0],
+    [53820.0 / 65536.0, 11213.0 / 65536.0, 44845.0 / 65536.0, 28466.0 / 65536.0, 62039.0 / 65536.0, 14521.0 / 65536.0, 3372.0 / 65536.0, 59118.0 / 65536.0],
+    [34108.0 / 65536.0, 50712.0 / 65536.0, 3941.0 / 65536.0, 61177.0 / 65536.0, 19830.0 / 65536.0, 42693.0 / 65536.0, 55761.0 / 65536.0, 27349.0 / 65536.0],
+    [9327.0 / 65536.0, 37480.0 / 65536.0, 24619.0 / 65536.0, 15788.0 / 65536.0, 55204.0 / 65536.0, 31846.0 / 65536.0, 46602.0 / 65536.0, 6735.0 / 65536.0],
+    [47053.0 / 65536.0, 2396.0 / 65536.0, 58274.0 / 65536.0, 45931.0 / 65536.0, 27385.0 / 65536.0, 8692.0 / 65536.0, 33158.0 / 65536.0, 51924.0 / 65536.0],
+    [18266.0 / 65536.0, 59731.0 / 65536.0, 13580.0 / 65536.0, 50274.0 / 65536.0, 36913.0 / 65536.0, 63406.0 / 65536.0, 10145.0 / 65536.0, 30872.0 / 65536.0],
+    [61839.0 / 65536.0, 29057.0 / 65536.0, 40768.0 / 65536.0, 1924.0 / 65536.0, 14467.0 / 65536.0, 36330.0 / 65536.0, 57781.0 / 65536.0, 23516.0 / 65536.0],
+];
+
+/// Turbulence randomly displaces the input coordinates of a function, with the semantics of the Turbulence module of libnoise. Each axis is displaced by its own internal FBM fractal of gradient noise: the `frequency` is the frequency of the fractals, the `power` scales the displacement, and the `roughness` is the number of octaves, so that higher values give a rougher, more jittery displacement. The internal fractals are seeded from the module's own seed (the fractal for axis k gets `seed + k`), so two turbulence modules with the same parameters and seed give the same result, and changing the seed changes every axis.
+///
+/// It replaces the hand-wired combination of a [`TranslateDomain`](struct.ImplicitTranslateDomain.html) with a fractal and a scale/offset module per axis. 4D and 6D evaluation displace the extra axes as well, each with a fractal of its own, and so does [`get_nd()`](trait.ImplicitModule.html#method.get_nd) up to [`MAX_DIMENSIONS`](noise_gen/constant.MAX_DIMENSIONS.html) axes.
+pub struct ImplicitTurbulence {
+    base: ImplicitModuleBase,
+    source: ScalarParameter,
+    distort: Vec<ImplicitFractal>,
+    frequency: f64,
+    power: f64,
+    roughness: u32,
+    seed: u32,
+}
+
+impl ImplicitTurbulence {
+    pub fn new() -> ImplicitTurbulence {
+        ImplicitTurbulence::with_parameters(1.0, 1.0, 3)
+    }
+
+    pub fn with_parameters(frequency: f64, power: f64, roughness: u32) -> ImplicitTurbulence {
+        let mut t = ImplicitTurbulence {
+            base: Default::default(),
+            source: ScalarParameter::Value(0.0),
+            distort: (0..MAX_DIMENSIONS).map(|_| ImplicitFractal::new(FractalType::FBM, BasisType::Gradient, InterpType::Quintic)).collect(),
+            frequency,
+            power,
+            roughness,
+            seed: 0,
+        };
+        t.set_frequency(frequency);
+        t.set_roughness(roughness);
+        t.set_seed(0);
+        t
+    }
+
+    pub fn set_source_module(&mut self, m: Rc<RefCell<ImplicitModule>>) {
+        self.source = ScalarParameter::Source(m);
+    }
+
+    pub fn set_source_value(&mut self, v: f64) {
+        self.source = ScalarParameter::Value(v);
+    }
+
+    pub fn set_frequency(&mut self, f: f64) {
+        self.frequency = f;
+        for d in self.distort.iter_mut() {
+            d.set_frequency(f);
+        }
+    }
+
+    pub fn set_power(&mut self, p: f64) {
+        self.power = p;
+    }
+
+    pub fn set_roughness(&mut self, r: u32) {
+        self.roughness = r;
+        for d in self.distort.iter_mut() {
+            d.set_num_octaves(r);
+        }
+    }
+
+    pub fn get_frequency(&self) -> f64 {
+        self.frequency
+    }
+
+    pub fn get_power(&self) -> f64 {
+        self.power
+    }
+
+    pub fn get_roughness(&self) -> u32 {
+        self.roughness
+    }
+
+    pub fn get_seed(&self) -> u32 {
+        self.seed
+    }
+
+    fn displace(&mut self, p: &[f64]) -> [f64; MAX_DIMENSIONS] {
+        let n = p.len();
+        let mut q = [0.0; MAX_DIMENSIONS];
+        let mut s = [0.0; MAX_DIMENSIONS];
+        for axis in 0..n {
+            for k in 0..n {
+                s[k] = p[k] + TURBULENCE_OFFSETS[axis][k];
+            }
+            let d = &mut self.distort[axis];
+            let offset = match n {
+                1 => d.get_1d(s[0]),
+                2 => d.get_2d(s[0], s[1]),
+                3 => d.get_3d(s[0], s[1], s[2]),
+                4 => d.get_4d(s[0], s[1], s[2], s[3]),
+                6 => d.get_6d(s[0], s[1], s[2], s[3], s[4], s[5]),
+                _ => d.get_nd(&s[..n]),
+            };
+            q[axis] = p[axis] + offset * self.power;
+        }
+        q
+    }
+}
+
+impl ImplicitModule for ImplicitTurbulence {
+    fn set_seed(&mut self, seed: u32) {
+        self.seed = seed;
+        for (k, d) in self.distort.iter_mut().enumerate() {
+            d.set_seed(seed.wrapping_add(k as u32));
+        }
+    }
+
+    fn get_1d(&mut self, x: f64) -> f64 {
+        let q = self.displace(&[x]);
+        self.source.get_1d(q[0])
+    }
+    fn get_2d(&mut self, x: f64, y: f64) -> f64 {
+        let q = self.displace(&[x, y]);
+        self.source.get_2d(q[0], q[1])
+    }
+    fn get_3d(&mut self, x: f64, y: f64, z: f64) -> f64 {
+        let q = self.displace(&[x, y, z]);
+        self.source.get_3d(q[0], q[1], q[2])
+    }
+    fn get_4d(&mut self, x: f64, y: f64, z: f64, w: f64) -> f64 {
+        let q = self.displace(&[x, y, z, w]);
+        self.source.get_4d(q[0], q[1], q[2], q[3])
+    }
+    fn get_6d(&mut self, x: f64, y: f64, z: f64, w: f64, u: f64, v: f64) -> f64 {
+        let q = self.displace(&[x, y, z, w, u, v]);
+        self.source.get_6d(q[0], q[1], q[2], q[3], q[4], q[5])
+    }
+    fn get_nd(&mut self, p: &[f64]) -> f64 {
+        let n = p.len().min(MAX_DIMENSIONS);
+        let q = self.displace(&p[..n]);
+        self.source.get_nd(&q[..n])
+    }
+
+    fn spacing(&self) -> f64 {
+        self.base.spacing
+    }
+
+    fn set_deriv_spacing(&mut self, s: f64) {
+        self.base.spacing = s;
+    }
+
+    // The distortion fractals are sampled in the same domain as the source, and apply their frequency themselves.
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+        for d in self.distort.iter_mut() {
+            d.set_footprint(f);
+        }
+    }
+}
@@ -11,6 +11,10 @@ pub mod utility;
 pub mod curve;
 pub mod hsv;
 pub mod mapping;
+pub mod mesh;
+pub mod erosion;
+pub mod hydrology;
+pub mod raster;
 pub mod vector_types;
 
 pub mod noise_gen;
@@ -19,6 +23,8 @@ pub mod random_gen;
 
 mod implicit_base;
 mod rgba_module_base;
+#[macro_use]
+mod buffer_sampler;
 
 pub use implicit_base::{ImplicitModule, ScalarParameter};
 pub use rgba_module_base::*;
@@ -56,6 +62,12 @@ mod implicit_sphere;
 mod implicit_tiers;
 mod implicit_triangle;
 mod implicit_bright_contrast;
+mod implicit_gabor;
+mod implicit_curl;
+mod implicit_domain_warp;
+mod implicit_turbulence;
+mod implicit_path_distance;
+mod implicit_buffer;
 
 mod rgba_blend;
 mod rgba_blend_ops;
@@ -69,6 +81,7 @@ mod rgba_normalize;
 mod rgba_rgba_to_hsv;
 mod rgba_rotate_color;
 mod rgba_select;
+mod rgba_buffer;
 
 pub use implicit_cache::*;
 pub use implicit_auto_correct::*;
@@ -103,6 +116,12 @@ pub use implicit_sphere::*;
 pub use implicit_tiers::*;
 pub use implicit_triangle::*;
 pub use implicit_bright_contrast::*;
+pub use implicit_gabor::*;
+pub use implicit_curl::*;
+pub use implicit_domain_warp::*;
+pub use implicit_turbulence::*;
+pub use implicit_path_distance::*;
+pub use implicit_buffer::*;
 
 pub use rgba_blend::*;
 pub use rgba_blend_ops::*;
@@ -115,4 +134,5 @@ pub use rgba_implicit_greyscale::*;
 pub use rgba_normalize::*;
 pub use rgba_rgba_to_hsv::*;
 pub use rgba_rotate_color::*;
-pub use rgba_select::*;
\ No newline at end of file
+pub use rgba_select::*;
+pub use rgba_buffer::*;
\ No newline at end of file
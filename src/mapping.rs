@@ -15,6 +15,14 @@ pub enum MappingMode {
     SeamlessXYZ,
 }
 
+/// The area of the domain that the mapping functions map a buffer onto. The map ranges give the extent along each axis, so that the first element of a buffer is sampled at `(x0, y0, z0)` and the elements span up to `(x1, y1, z1)`. The loop ranges are used by the seamless modes only: a seamless axis repeats every `loop1 - loop0` units, so a map range as long as the loop range gives a buffer that tiles once, and a map range twice as long gives a buffer with two repeats. Every range defaults to -1..1.
+///
+/// ```
+/// use anl::mapping::MappingRanges;
+///
+/// let ranges = MappingRanges::new().map_x(0.0, 4.0).map_y(0.0, 4.0).loop_x(0.0, 4.0).loop_y(0.0, 4.0);
+/// assert_eq!(ranges.get_map_x(), (0.0, 4.0));
+/// ```
 pub struct MappingRanges {
     mapx0: f64,
     mapy0: f64,
@@ -28,6 +36,7 @@ pub struct MappingRanges {
     loopx1: f64,
     loopy1: f64,
     loopz1: f64,
+    band_limited: bool,
 }
 
 impl MappingRanges {
@@ -45,11 +54,621 @@ impl MappingRanges {
             loopx1: 1.0,
             loopy1: 1.0,
             loopz1: 1.0,
+            band_limited: true,
         }
     }
+
+    pub fn map_x(mut self, x0: f64, x1: f64) -> MappingRanges {
+        self.mapx0 = x0;
+        self.mapx1 = x1;
+        self
+    }
+
+    pub fn map_y(mut self, y0: f64, y1: f64) -> MappingRanges {
+        self.mapy0 = y0;
+        self.mapy1 = y1;
+        self
+    }
+
+    pub fn map_z(mut self, z0: f64, z1: f64) -> MappingRanges {
+        self.mapz0 = z0;
+        self.mapz1 = z1;
+        self
+    }
+
+    pub fn loop_x(mut self, x0: f64, x1: f64) -> MappingRanges {
+        self.loopx0 = x0;
+        self.loopx1 = x1;
+        self
+    }
+
+    pub fn loop_y(mut self, y0: f64, y1: f64) -> MappingRanges {
+        self.loopy0 = y0;
+        self.loopy1 = y1;
+        self
+    }
+
+    pub fn loop_z(mut self, z0: f64, z1: f64) -> MappingRanges {
+        self.loopz0 = z0;
+        self.loopz1 = z1;
+        self
+    }
+
+    /// Sets whether the mapping functions band-limit the module to the size of a pixel while they sample it (see
+    /// [`map_2d()`](fn.map_2d.html)). It is on by default; turn it off to sample the module as it is, for instance to
+    /// keep every octave of a fractal in a small or coarse buffer.
+    pub fn band_limit(mut self, on: bool) -> MappingRanges {
+        self.band_limited = on;
+        self
+    }
+
+    pub fn get_map_x(&self) -> (f64, f64) {
+        (self.mapx0, self.mapx1)
+    }
+
+    pub fn get_map_y(&self) -> (f64, f64) {
+        (self.mapy0, self.mapy1)
+    }
+
+    pub fn get_map_z(&self) -> (f64, f64) {
+        (self.mapz0, self.mapz1)
+    }
+
+    pub fn get_loop_x(&self) -> (f64, f64) {
+        (self.loopx0, self.loopx1)
+    }
+
+    pub fn get_loop_y(&self) -> (f64, f64) {
+        (self.loopy0, self.loopy1)
+    }
+
+    pub fn get_loop_z(&self) -> (f64, f64) {
+        (self.loopz0, self.loopz1)
+    }
+
+    pub fn is_band_limited(&self) -> bool {
+        self.band_limited
+    }
+
+    // The size of a pixel in domain units, along the longest side. It is the same in the seamless modes, whose loops
+    // are circles with the loop range as circumference. The mapping functions band-limit the module to it while they
+    // run, and then set the footprint back to 0. It is 0 when band-limiting is turned off.
+    fn pixel_footprint(&self, w: usize, h: usize, d: usize) -> f64 {
+        let mut f: f64 = 0.0;
+        if !self.band_limited {
+            return f;
+        }
+        if w > 0 {
+            f = f.max(((self.mapx1 - self.mapx0) / w as f64).abs());
+        }
+        if h > 0 {
+            f = f.max(((self.mapy1 - self.mapy0) / h as f64).abs());
+        }
+        if d > 0 {
+            f = f.max(((self.mapz1 - self.mapz0) / d as f64).abs());
+        }
+        f
+    }
+}
+
+impl Default for MappingRanges {
+    fn default() -> Self {
+        MappingRanges::new()
+    }
+}
+
+/// The coordinates that a [`CoordinateMapper`](trait.CoordinateMapper.html) maps a buffer element to. The number of coordinates selects the get method of the module that is sampled there.
+pub enum MappedCoords {
+    D2([f64; 2]),
+    D3([f64; 3]),
+    D4([f64; 4]),
+    D6([f64; 6]),
+}
+
+impl MappedCoords {
+    pub fn get(&self, m: &mut ImplicitModule) -> f64 {
+        match *self {
+            MappedCoords::D2(c) => m.get_2d(c[0], c[1]),
+            MappedCoords::D3(c) => m.get_3d(c[0], c[1], c[2]),
+            MappedCoords::D4(c) => m.get_4d(c[0], c[1], c[2], c[3]),
+            MappedCoords::D6(c) => m.get_6d(c[0], c[1], c[2], c[3], c[4], c[5]),
+        }
+    }
+
+    pub fn get_rgba(&self, m: &mut RgbaModule) -> Rgba {
+        match *self {
+            MappedCoords::D2(c) => m.get_2d(c[0], c[1]),
+            MappedCoords::D3(c) => m.get_3d(c[0], c[1], c[2]),
+            MappedCoords::D4(c) => m.get_4d(c[0], c[1], c[2], c[3]),
+            MappedCoords::D6(c) => m.get_6d(c[0], c[1], c[2], c[3], c[4], c[5]),
+        }
+    }
+}
+
+/// A coordinate mapper decides where in the domain of a module each element of a buffer is sampled. The mapping functions pass it the position of an element within the buffer, normalized so that the buffer spans 0..1 along each axis (the last element is just short of 1), and the [`MappingRanges`](struct.MappingRanges.html) of the call. It returns the coordinates to sample, or `None` to leave the element at 0.
+///
+/// The seamless modes of [`MappingMode`](enum.MappingMode.html) are the built-in mappers, and custom ones can wrap them, for instance to flip, rotate or offset a tile:
+///
+/// ```
+/// use anl::mapping::{CoordinateMapper, MappedCoords, MappingMode, MappingRanges};
+///
+/// struct FlippedX(MappingMode);
+///
+/// impl CoordinateMapper for FlippedX {
+///     fn map_2d(&self, ranges: &MappingRanges, p: f64, q: f64, z: f64) -> Option<MappedCoords> {
+///         self.0.map_2d(ranges, 1.0 - p, q, z)
+///     }
+///     fn map_2d_no_z(&self, ranges: &MappingRanges, p: f64, q: f64) -> Option<MappedCoords> {
+///         self.0.map_2d_no_z(ranges, 1.0 - p, q)
+///     }
+///     fn map_3d(&self, ranges: &MappingRanges, p: f64, q: f64, r: f64) -> Option<MappedCoords> {
+///         self.0.map_3d(ranges, 1.0 - p, q, r)
+///     }
+/// }
+/// ```
+pub trait CoordinateMapper {
+    /// Maps an element of a 2D buffer, used by [`map_2d()`](fn.map_2d.html) and [`map_rgba_2d()`](fn.map_rgba_2d.html). `z` is the z coordinate passed to them, in domain units.
+    fn map_2d(&self, ranges: &MappingRanges, p: f64, q: f64, z: f64) -> Option<MappedCoords>;
+    /// Maps an element of a 2D buffer, used by [`map_2d_no_z()`](fn.map_2d_no_z.html) and [`map_rgba_2d_no_z()`](fn.map_rgba_2d_no_z.html).
+    fn map_2d_no_z(&self, ranges: &MappingRanges, p: f64, q: f64) -> Option<MappedCoords>;
+    /// Maps an element of a 3D buffer, used by [`map_3d()`](fn.map_3d.html) and [`map_rgba_3d()`](fn.map_rgba_3d.html).
+    fn map_3d(&self, ranges: &MappingRanges, p: f64, q: f64, r: f64) -> Option<MappedCoords>;
+}
+
+impl<T: CoordinateMapper + ?Sized> CoordinateMapper for &T {
+    fn map_2d(&self, ranges: &MappingRanges, p: f64, q: f64, z: f64) -> Option<MappedCoords> {
+        (**self).map_2d(ranges, p, q, z)
+    }
+    fn map_2d_no_z(&self, ranges: &MappingRanges, p: f64, q: f64) -> Option<MappedCoords> {
+        (**self).map_2d_no_z(ranges, p, q)
+    }
+    fn map_3d(&self, ranges: &MappingRanges, p: f64, q: f64, r: f64) -> Option<MappedCoords> {
+        (**self).map_3d(ranges, p, q, r)
+    }
+}
+
+impl CoordinateMapper for MappingMode {
+    fn map_2d(&self, ranges: &MappingRanges, mut p: f64, mut q: f64, z: f64) -> Option<MappedCoords> {
+        let r;
+        let nx;
+        let ny;
+        let nz;
+        let nw;
+        let nu;
+        let nv;
+        let coords;
+        let dx;
+        let dy;
+        let dz;
+
+        match *self {
+            MappingMode::SeamlessNone => {
+                nx = ranges.mapx0 + p * (ranges.mapx1 - ranges.mapx0);
+                ny = ranges.mapy0 + q * (ranges.mapy1 - ranges.mapy0);
+                nz = z;
+                coords = MappedCoords::D3([nx, ny, nz]);
+            }
+            MappingMode::SeamlessX => {
+                dx = ranges.loopx1 - ranges.loopx0;
+                dy = ranges.mapy1 - ranges.mapy0;
+                p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
+                nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
+                ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
+                nz = ranges.mapy0 + q * dy;
+                nw = z;
+                coords = MappedCoords::D4([nx, ny, nz, nw]);
+            }
+            MappingMode::SeamlessY => {
+                dx = ranges.mapx1 - ranges.mapx0;
+                dy = ranges.loopy1 - ranges.loopy0;
+                q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
+                nx = ranges.mapx0 + p * dx;
+                ny = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
+                nz = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
+                nw = z;
+                coords = MappedCoords::D4([nx, ny, nz, nw]);
+            }
+            MappingMode::SeamlessZ => {
+                dx = ranges.mapx1 - ranges.mapx0;
+                dy = ranges.mapy1 - ranges.mapy0;
+                dz = ranges.loopz1 - ranges.loopz0;
+                nx = ranges.mapx0 + p * dx;
+                ny = ranges.mapy0 + q * dy;
+                r = (z - ranges.mapz0) / (ranges.mapz1 - ranges.mapz0);
+                let zval = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
+                nz = ranges.loopz0 + (zval * PI2).cos() * dz / PI2;
+                nw = ranges.loopz0 + (zval * PI2).sin() * dz / PI2;
+                coords = MappedCoords::D4([nx, ny, nz, nw]);
+            }
+            MappingMode::SeamlessXY => {
+                dx = ranges.loopx1 - ranges.loopx0;
+                dy = ranges.loopy1 - ranges.loopy0;
+                p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
+                q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
+                nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
+                ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
+                nz = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
+                nw = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
+                nu = z;
+                coords = MappedCoords::D6([nx, ny, nz, nw, nu, 0.0]);
+            }
+            MappingMode::SeamlessXZ => {
+                dx = ranges.loopx1 - ranges.loopx0;
+                dy = ranges.mapy1 - ranges.mapy0;
+                dz = ranges.loopz1 - ranges.loopz0;
+                r = (z - ranges.mapz0) / (ranges.mapz1 - ranges.mapz0);
+                let zval = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
+                p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
+                nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
+                ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
+                nz = ranges.mapy0 + q * dy;
+                nw = ranges.loopz0 + (zval * PI2).cos() * dz / PI2;
+                nu = ranges.loopz0 + (zval * PI2).sin() * dz / PI2;
+                coords = MappedCoords::D6([nx, ny, nz, nw, nu, 0.0]);
+            }
+            MappingMode::SeamlessYZ => {
+                dx = ranges.mapx1 - ranges.mapx0;
+                dy = ranges.loopy1 - ranges.loopy0;
+                dz = ranges.loopz1 - ranges.loopz0;
+                r = (z - ranges.mapz0) / (ranges.mapz1 - ranges.mapz0);
+                let zval = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
+                q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
+                nx = ranges.mapx0 + p * dx;
+                ny = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
+                nz = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
+                nw = ranges.loopz0 + (zval * PI2).cos() * dz / PI2;
+                nu = ranges.loopz0 + (zval * PI2).sin() * dz / PI2;
+                coords = MappedCoords::D6([nx, ny, nz, nw, nu, 0.0]);
+            }
+            MappingMode::SeamlessXYZ => {
+                dx = ranges.loopx1 - ranges.loopx0;
+                dy = ranges.loopy1 - ranges.loopy0;
+                dz = ranges.loopz1 - ranges.loopz0;
+                p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
+                q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
+                r = (z - ranges.mapz0) / (ranges.mapz1 - ranges.mapz0);
+                let zval = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
+                nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
+                ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
+                nz = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
+                nw = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
+                nu = ranges.loopz0 + (zval * PI2).cos() * dz / PI2;
+                nv = ranges.loopz0 + (zval * PI2).sin() * dz / PI2;
+                coords = MappedCoords::D6([nx, ny, nz, nw, nu, nv]);
+            }
+        }
+        Some(coords)
+    }
+
+    fn map_2d_no_z(&self, ranges: &MappingRanges, mut p: f64, mut q: f64) -> Option<MappedCoords> {
+        let nx;
+        let ny;
+        let nz;
+        let nw;
+        let coords;
+        let dx;
+        let dy;
+
+        match *self {
+            MappingMode::SeamlessNone => {
+                nx = ranges.mapx0 + p * (ranges.mapx1 - ranges.mapx0);
+                ny = ranges.mapy0 + q * (ranges.mapy1 - ranges.mapy0);
+                coords = MappedCoords::D2([nx, ny]);
+            }
+            MappingMode::SeamlessX => {
+                dx = ranges.loopx1 - ranges.loopx0;
+                dy = ranges.mapy1 - ranges.mapy0;
+                p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
+                nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
+                ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
+                nz = ranges.mapy0 + q * dy;
+                coords = MappedCoords::D3([nx, ny, nz]);
+            }
+            MappingMode::SeamlessY => {
+                dx = ranges.mapx1 - ranges.mapx0;
+                dy = ranges.loopy1 - ranges.loopy0;
+                q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
+                nx = ranges.mapx0 + p * dx;
+                ny = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
+                nz = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
+                coords = MappedCoords::D3([nx, ny, nz]);
+            }
+            MappingMode::SeamlessXY => {
+                dx = ranges.loopx1 - ranges.loopx0;
+                dy = ranges.loopy1 - ranges.loopy0;
+                p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
+                q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
+                nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
+                ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
+                nz = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
+                nw = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
+                coords = MappedCoords::D4([nx, ny, nz, nw]);
+            }
+            _ => {
+                return None;
+            }
+        }
+        Some(coords)
+    }
+
+    fn map_3d(&self, ranges: &MappingRanges, mut p: f64, mut q: f64, mut r: f64) -> Option<MappedCoords> {
+    let nx;
+    let ny;
+    let nz;
+    let nw;
+    let nu;
+    let nv;
+    let coords;
+    let dx;
+    let dy;
+    let dz;
+
+    match *self {
+        MappingMode::SeamlessNone => {
+            dx = ranges.mapx1 - ranges.mapx0;
+            dy = ranges.mapy1 - ranges.mapy0;
+            dz = ranges.mapz1 - ranges.mapz0;
+            nx = ranges.mapx0 + p * dx;
+            ny = ranges.mapy0 + q * dy;
+            nz = ranges.mapz0 + r * dz;
+            coords = MappedCoords::D3([nx, ny, nz]);
+        }
+        MappingMode::SeamlessX => {
+            dx = ranges.loopx1 - ranges.loopx0;
+            dy = ranges.mapy1 - ranges.mapy0;
+            dz = ranges.mapz1 - ranges.mapz0;
+            p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
+            nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
+            ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
+            nz = ranges.mapy0 + q * dy;
+            nw = ranges.mapz0 + r * dz;
+            coords = MappedCoords::D4([nx, ny, nz, nw]);
+        }
+        MappingMode::SeamlessY => {
+            dx = ranges.mapx1 - ranges.mapx0;
+            dy = ranges.loopy1 - ranges.loopy0;
+            dz = ranges.mapz1 - ranges.mapz0;
+            q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
+            nx = ranges.mapx0 + p * dx;
+            ny = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
+            nz = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
+            nw = ranges.mapz0 + r * dz;
+            coords = MappedCoords::D4([nx, ny, nz, nw]);
+        }
+        MappingMode::SeamlessZ => {
+            dx = ranges.mapx1 - ranges.mapx0;
+            dy = ranges.mapy1 - ranges.mapy0;
+            dz = ranges.loopz1 - ranges.loopz0;
+            r = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
+            nx = ranges.mapx0 + p * dx;
+            ny = ranges.mapy0 + q * dy;
+            nz = ranges.loopz0 + (r * PI2).cos() * dz / PI2;
+            nw = ranges.loopz0 + (r * PI2).sin() * dz / PI2;
+            coords = MappedCoords::D4([nx, ny, nz, nw]);
+        }
+        MappingMode::SeamlessXY => {
+            dx = ranges.loopx1 - ranges.loopx0;
+            dy = ranges.loopy1 - ranges.loopy0;
+            dz = ranges.mapz1 - ranges.mapz0;
+            p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
+            q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
+            nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
+            ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
+            nz = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
+            nw = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
+            nu = ranges.mapz0 + r * dz;
+            coords = MappedCoords::D6([nx, ny, nz, nw, nu, 0.0]);
+        }
+        MappingMode::SeamlessXZ => {
+            dx = ranges.loopx1 - ranges.loopx0;
+            dy = ranges.mapy1 - ranges.mapy0;
+            dz = ranges.loopz1 - ranges.loopz0;
+            p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
+            r = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
+            nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
+            ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
+            nz = ranges.mapy0 + q * dy;
+            nw = ranges.loopz0 + (r * PI2).cos() * dz / PI2;
+            nu = ranges.loopz0 + (r * PI2).sin() * dz / PI2;
+            coords = MappedCoords::D6([nx, ny, nz, nw, nu, 0.0]);
+        }
+        MappingMode::SeamlessYZ => {
+            dx = ranges.mapx1 - ranges.mapx0;
+            dy = ranges.loopy1 - ranges.loopy0;
+            dz = ranges.loopz1 - ranges.loopz0;
+            q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
+            r = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
+            nx = ranges.mapx0 + p * dx;
+            ny = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
+            nz = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
+            nw = ranges.loopz0 + (r * PI2).cos() * dz / PI2;
+            nu = ranges.loopz0 + (r * PI2).sin() * dz / PI2;
+            coords = MappedCoords::D6([nx, ny, nz, nw, nu, 0.0]);
+        }
+        MappingMode::SeamlessXYZ => {
+            dx = ranges.loopx1 - ranges.loopx0;
+            dy = ranges.loopy1 - ranges.loopy0;
+            dz = ranges.loopz1 - ranges.loopz0;
+            p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
+            q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
+            r = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
+            nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
+            ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
+            nz = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
+            nw = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
+            nu = ranges.loopz0 + (r * PI2).cos() * dz / PI2;
+            nv = ranges.loopz0 + (r * PI2).sin() * dz / PI2;
+            coords = MappedCoords::D6([nx, ny, nz, nw, nu, nv]);
+        }
+    }
+        Some(coords)
+    }
+}
+
+pub enum SamplingPattern {
+    Regular,
+    Jittered,
+    RotatedGrid,
+}
+
+pub enum ReconstructionFilter {
+    Box,
+    Tent,
+    Gaussian,
+}
+
+/// Supersampling of the 2D mapping functions, passed to [`map_2d_supersampled()`](fn.map_2d_supersampled.html), [`map_2d_no_z_supersampled()`](fn.map_2d_no_z_supersampled.html) and their RGBA counterparts. [`map_2d()`](fn.map_2d.html) and the others take a single point sample per pixel, which aliases with white noise, cellular edges or steps. Each pixel is the weighted average of a number of samples placed around it, in every MappingMode, the seamless ones included, since the samples are taken before the mapping to the loops.
+///
+/// The samples are placed on a `k` by `k` grid, where `k` is the square root of the number of samples, rounded to the nearest integer. `Regular` puts them at the centers of the grid cells, `Jittered` at random positions within the cells, which trades the aliasing left over for noise, and `RotatedGrid` shears the grid so that no two samples share a row or a column, which handles near horizontal and vertical edges much better for the same cost. The jitter is deterministic, and depends on the pixel and the seed only.
+///
+/// The samples cover the support of the filter that weights them: half a pixel around the pixel for `Box`, which is a plain average, a pixel for `Tent`, and a pixel and a half for `Gaussian` (with a standard deviation of half a pixel). The wider filters overlap the neighbouring pixels, so they blur a little but suppress aliasing better.
+///
+/// ```
+/// use anl::{ImplicitBasisFunction, BasisType, InterpType, ImplicitModule};
+/// use anl::mapping::{map_2d_supersampled, MappingMode, MappingRanges, Supersampling, SamplingPattern, ReconstructionFilter};
+///
+/// let mut noise = ImplicitBasisFunction::with_types(BasisType::White, InterpType::None);
+/// noise.set_seed(1);
+/// let supersampling = Supersampling::new(SamplingPattern::RotatedGrid, 16, ReconstructionFilter::Tent);
+/// let mut image = vec![vec![0.0; 32]; 32];
+/// map_2d_supersampled(MappingMode::SeamlessNone, &mut image, &mut noise, &mut MappingRanges::new(), 0.0, &supersampling);
+/// assert!(image.iter().all(|column| column.iter().all(|v| v.abs() <= 1.0)));
+/// ```
+pub struct Supersampling {
+    pattern: SamplingPattern,
+    filter: ReconstructionFilter,
+    grid: usize,
+    seed: u32,
+}
+
+impl Supersampling {
+    pub fn new(pattern: SamplingPattern, samples: usize, filter: ReconstructionFilter) -> Supersampling {
+        let grid = (samples as f64).sqrt().round() as usize;
+        Supersampling {
+            pattern,
+            filter,
+            grid: if grid > 0 { grid } else { 1 },
+            seed: 0,
+        }
+    }
+
+    /// A single point sample per pixel, which is how the mapping functions sample by default.
+    pub fn none() -> Supersampling {
+        Supersampling::new(SamplingPattern::Regular, 1, ReconstructionFilter::Box)
+    }
+
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = seed;
+    }
+
+    pub fn get_num_samples(&self) -> usize {
+        self.grid * self.grid
+    }
+
+    fn radius(&self) -> f64 {
+        match self.filter {
+            ReconstructionFilter::Box => 0.5,
+            ReconstructionFilter::Tent => 1.0,
+            ReconstructionFilter::Gaussian => 1.5,
+        }
+    }
+
+    fn weight(&self, dx: f64, dy: f64) -> f64 {
+        match self.filter {
+            ReconstructionFilter::Box => 1.0,
+            ReconstructionFilter::Tent => (1.0 - dx.abs()) * (1.0 - dy.abs()),
+            ReconstructionFilter::Gaussian => (-2.0 * (dx * dx + dy * dy)).exp(),
+        }
+    }
+
+    // Fills samples with the offsets, in pixels, of the samples of pixel (x,y) and their weights, which add up to 1.
+    fn pixel_samples(&self, x: usize, y: usize, samples: &mut Vec<(f64, f64, f64)>) {
+        samples.clear();
+        let k = self.grid;
+        let cell = 1.0 / k as f64;
+        let diameter = 2.0 * self.radius();
+        let mut total = 0.0;
+        for s in 0..k * k {
+            let (i, j) = ((s % k) as f64, (s / k) as f64);
+            let (u, v) = match self.pattern {
+                SamplingPattern::Regular => ((i + 0.5) * cell, (j + 0.5) * cell),
+                SamplingPattern::Jittered => {
+                    ((i + jitter(x, y, 2 * s, self.seed)) * cell, (j + jitter(x, y, 2 * s + 1, self.seed)) * cell)
+                }
+                SamplingPattern::RotatedGrid => ((i + (j + 0.5) * cell) * cell, (j + (i + 0.5) * cell) * cell),
+            };
+            let (dx, dy) = ((u - 0.5) * diameter, (v - 0.5) * diameter);
+            let weight = self.weight(dx, dy);
+            total += weight;
+            samples.push((dx, dy, weight));
+        }
+        for sample in samples.iter_mut() {
+            sample.2 /= total;
+        }
+    }
+}
+
+// A hash of the pixel, the sample and the seed, mapped to [0,1).
+fn jitter(x: usize, y: usize, s: usize, seed: u32) -> f64 {
+    let mut h = (x as u32).wrapping_mul(0x8da6_b343) ^ (y as u32).wrapping_mul(0xd816_3841) ^ (s as u32).wrapping_mul(0xcb1a_b31f) ^ seed.wrapping_mul(0x1656_67b1);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2c1b_3c6d);
+    h ^= h >> 12;
+    h = h.wrapping_mul(0x297a_2d39);
+    h ^= h >> 15;
+    h as f64 / 4_294_967_296.0
 }
 
-pub fn map_2d<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(seamlessmode: MappingMode, mut a: Matrix, m: &mut ImplicitModule, ranges: &mut MappingRanges, z: f64) {
+/// Samples the 1D function into a time series, such as the per-frame offsets of a camera shake. The samples are spread evenly over the x map range: with the range set to `map_x(x0, x1)`, sample k of n is taken at `x0 + k / n * (x1 - x0)`. The other ranges are not used. The module is band-limited to the spacing of the samples, as in [`map_2d()`](fn.map_2d.html).
+///
+/// ```
+/// use anl::{ImplicitFractal, FractalType, BasisType, InterpType, ImplicitModule};
+/// use anl::mapping::{map_1d, MappingRanges};
+///
+/// let mut shake = ImplicitFractal::new(FractalType::FBM, BasisType::Gradient, InterpType::Quintic);
+/// shake.set_seed(1);
+/// let mut frames = vec![0.0; 60];
+/// map_1d(&mut frames[..], &mut shake, &mut MappingRanges::new().map_x(0.0, 2.0));
+/// assert!(frames.iter().all(|v| v.is_finite()));
+/// ```
+pub fn map_1d<Row: AsMut<[f64]>>(mut a: Row, m: &mut ImplicitModule, ranges: &mut MappingRanges) {
+    let n = a.as_mut().len();
+    m.set_footprint(ranges.pixel_footprint(n, 0, 0));
+    for (k, val) in a.as_mut().iter_mut().enumerate() {
+        let t = ranges.mapx0 + k as f64 / n as f64 * (ranges.mapx1 - ranges.mapx0);
+        *val = m.get_1d(t);
+    }
+    m.set_footprint(0.0);
+}
+
+/// Maps a 2D buffer onto the map ranges, sampling the module once per element at the coordinates the mapper gives it,
+/// with `z` as the third coordinate in the modes that use one.
+///
+/// While it runs, the module is band-limited to the size of a pixel with
+/// [`set_footprint()`](../trait.ImplicitModule.html#method.set_footprint): fractals fade out their octaves as they go
+/// from a quarter to half a cycle per pixel and skip the finer ones, so that minified renderings do not alias. On small
+/// or coarse buffers this can leave few octaves, or none, and the buffer comes out smooth or flat. Turn it off with
+/// [`MappingRanges::band_limit(false)`](struct.MappingRanges.html#method.band_limit) to sample the module as it is.
+///
+/// ```
+/// use anl::{ImplicitFractal, FractalType, BasisType, InterpType, ImplicitModule};
+/// use anl::mapping::{map_2d, MappingMode, MappingRanges};
+///
+/// let mut fbm = ImplicitFractal::new(FractalType::FBM, BasisType::Gradient, InterpType::Quintic);
+/// fbm.set_seed(1);
+/// let mut band_limited = vec![vec![0.0; 5]; 4];
+/// map_2d(MappingMode::SeamlessNone, &mut band_limited, &mut fbm, &mut MappingRanges::new(), 0.0);
+/// let mut point_sampled = vec![vec![0.0; 5]; 4];
+/// map_2d(MappingMode::SeamlessNone, &mut point_sampled, &mut fbm, &mut MappingRanges::new().band_limit(false), 0.0);
+/// assert!(band_limited.iter().all(|column| column.iter().all(|&v| v == 0.0)));
+/// assert!(point_sampled.iter().any(|column| column.iter().any(|&v| v != 0.0)));
+/// ```
+pub fn map_2d<Mapper: CoordinateMapper, Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(mapper: Mapper, a: Matrix, m: &mut ImplicitModule, ranges: &mut MappingRanges, z: f64) {
+    map_2d_supersampled(mapper, a, m, ranges, z, &Supersampling::none());
+}
+
+/// Maps a 2D buffer as [`map_2d()`](fn.map_2d.html) does, with each element averaged from the samples that `supersampling` places around it.
+pub fn map_2d_supersampled<Mapper: CoordinateMapper, Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(mapper: Mapper, mut a: Matrix, m: &mut ImplicitModule, ranges: &mut MappingRanges, z: f64, supersampling: &Supersampling) {
     let (w, h) = {
         let w = a.as_mut().len();
         if w > 0 {
@@ -60,124 +679,33 @@ pub fn map_2d<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(seamlessmode: MappingMode
         }
     };
 
+    m.set_footprint(ranges.pixel_footprint(w, h, 0));
+    let mut samples = Vec::new();
     for x in 0..w {
         for y in 0..h {
-            let mut p = x as f64 / w as f64;
-            let mut q = y as f64 / h as f64;
-            let r;
-            let nx;
-            let ny;
-            let nz;
-            let nw;
-            let nu;
-            let nv;
-            let val;
-            let dx;
-            let dy;
-            let dz;
-
-            match seamlessmode {
-                MappingMode::SeamlessNone => {
-                    nx = ranges.mapx0 + p * (ranges.mapx1 - ranges.mapx0);
-                    ny = ranges.mapy0 + q * (ranges.mapy1 - ranges.mapy0);
-                    nz = z;
-                    val = m.get_3d(nx, ny, nz);
-                }
-                MappingMode::SeamlessX => {
-                    dx = ranges.loopx1 - ranges.loopx0;
-                    dy = ranges.mapy1 - ranges.mapy0;
-                    p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                    nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                    ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                    nz = ranges.mapy0 + q * dy;
-                    nw = z;
-                    val = m.get_4d(nx, ny, nz, nw);
-                }
-                MappingMode::SeamlessY => {
-                    dx = ranges.mapx1 - ranges.mapx0;
-                    dy = ranges.loopy1 - ranges.loopy0;
-                    q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                    nx = ranges.mapx0 + p * dx;
-                    ny = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                    nz = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                    nw = z;
-                    val = m.get_4d(nx, ny, nz, nw);
-                }
-                MappingMode::SeamlessZ => {
-                    dx = ranges.mapx1 - ranges.mapx0;
-                    dy = ranges.mapy1 - ranges.mapy0;
-                    dz = ranges.loopz1 - ranges.loopz0;
-                    nx = ranges.mapx0 + p * dx;
-                    ny = ranges.mapy0 + p * dy;
-                    r = (z - ranges.mapz0) / (ranges.mapz1 - ranges.mapz0);
-                    let zval = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
-                    nz = ranges.loopz0 + (zval * PI2).cos() * dz / PI2;
-                    nw = ranges.loopz0 + (zval * PI2).sin() * dz / PI2;
-                    val = m.get_4d(nx, ny, nz, nw);
-                }
-                MappingMode::SeamlessXY => {
-                    dx = ranges.loopx1 - ranges.loopx0;
-                    dy = ranges.loopy1 - ranges.loopy0;
-                    p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                    q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                    nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                    ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                    nz = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                    nw = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                    nu = z;
-                    val = m.get_6d(nx, ny, nz, nw, nu, 0.0);
-                }
-                MappingMode::SeamlessXZ => {
-                    dx = ranges.loopx1 - ranges.loopx0;
-                    dy = ranges.mapy1 - ranges.mapy0;
-                    dz = ranges.loopz1 - ranges.loopz0;
-                    r = (z - ranges.mapz0) / (ranges.mapz1 - ranges.mapz0);
-                    let zval = r * (ranges.mapx1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
-                    p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                    nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                    ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                    nz = ranges.mapy0 + q * dy;
-                    nw = ranges.loopz0 + (zval * PI2).cos() * dz / PI2;
-                    nu = ranges.loopz0 + (zval * PI2).sin() * dz / PI2;
-                    val = m.get_6d(nx, ny, nz, nw, nu, 0.0);
-                }
-                MappingMode::SeamlessYZ => {
-                    dx = ranges.mapx1 - ranges.mapx0;
-                    dy = ranges.loopy1 - ranges.loopy0;
-                    dz = ranges.loopz1 - ranges.loopz0;
-                    r = (z - ranges.mapz0) / (ranges.mapz1 - ranges.mapz0);
-                    let zval = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
-                    q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                    nx = ranges.mapx0 + p * dx;
-                    ny = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                    nz = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                    nw = ranges.loopz0 + (zval * PI2).cos() * dz / PI2;
-                    nu = ranges.loopz0 + (zval * PI2).sin() * dz / PI2;
-                    val = m.get_6d(nx, ny, nz, nw, nu, 0.0);
-                }
-                MappingMode::SeamlessXYZ => {
-                    dx = ranges.loopx1 - ranges.loopx0;
-                    dy = ranges.loopy1 - ranges.loopy0;
-                    dz = ranges.loopz1 - ranges.loopz0;
-                    p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                    q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                    r = (z - ranges.mapz0) / (ranges.mapz1 - ranges.mapz0);
-                    let zval = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
-                    nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                    ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                    nz = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                    nw = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                    nu = ranges.loopz0 + (zval * PI2).cos() * dz / PI2;
-                    nv = ranges.loopz0 + (zval * PI2).sin() * dz / PI2;
-                    val = m.get_6d(nx, ny, nz, nw, nu, nv);
+            let mut val = 0.0;
+            supersampling.pixel_samples(x, y, &mut samples);
+            for &(dx, dy, weight) in samples.iter() {
+                let p = (x as f64 + dx) / w as f64;
+                let q = (y as f64 + dy) / h as f64;
+                if let Some(coords) = mapper.map_2d(ranges, p, q, z) {
+                    val += coords.get(m) * weight;
                 }
             }
             a.as_mut()[x].as_mut()[y] = val;
         }
     }
+    m.set_footprint(0.0);
 }
 
-pub fn map_2d_no_z<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(seamlessmode: MappingMode, mut a: Matrix, m: &mut ImplicitModule, ranges: &mut MappingRanges) {
+/// Maps a 2D buffer as [`map_2d()`](fn.map_2d.html) does, in the modes that only need two coordinates, and
+/// band-limits the module in the same way.
+pub fn map_2d_no_z<Mapper: CoordinateMapper, Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(mapper: Mapper, a: Matrix, m: &mut ImplicitModule, ranges: &mut MappingRanges) {
+    map_2d_no_z_supersampled(mapper, a, m, ranges, &Supersampling::none());
+}
+
+/// Maps a 2D buffer as [`map_2d_no_z()`](fn.map_2d_no_z.html) does, with each element averaged from the samples that `supersampling` places around it.
+pub fn map_2d_no_z_supersampled<Mapper: CoordinateMapper, Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(mapper: Mapper, mut a: Matrix, m: &mut ImplicitModule, ranges: &mut MappingRanges, supersampling: &Supersampling) {
     let (w, h) = {
         let w = a.as_mut().len();
         if w > 0 {
@@ -188,63 +716,29 @@ pub fn map_2d_no_z<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(seamlessmode: Mappin
         }
     };
 
+    m.set_footprint(ranges.pixel_footprint(w, h, 0));
+    let mut samples = Vec::new();
     for x in 0..w {
         for y in 0..h {
-            let mut p = x as f64 / w as f64;
-            let mut q = y as f64 / h as f64;
-            let nx;
-            let ny;
-            let nz;
-            let nw;
-            let val;
-            let dx;
-            let dy;
-
-            match seamlessmode {
-                MappingMode::SeamlessNone => {
-                    nx = ranges.mapx0 + p * (ranges.mapx1 - ranges.mapx0);
-                    ny = ranges.mapy0 + q * (ranges.mapy1 - ranges.mapy0);
-                    val = m.get_2d(nx, ny);
-                }
-                MappingMode::SeamlessX => {
-                    dx = ranges.loopx1 - ranges.loopx0;
-                    dy = ranges.mapy1 - ranges.mapy0;
-                    p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                    nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                    ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                    nz = ranges.mapy0 + q * dy;
-                    val = m.get_3d(nx, ny, nz);
-                }
-                MappingMode::SeamlessY => {
-                    dx = ranges.mapx1 - ranges.mapx0;
-                    dy = ranges.loopy1 - ranges.loopy0;
-                    q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                    nx = ranges.mapx0 + p * dx;
-                    ny = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                    nz = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                    val = m.get_3d(nx, ny, nz);
-                }
-                MappingMode::SeamlessXY => {
-                    dx = ranges.loopx1 - ranges.loopx0;
-                    dy = ranges.loopy1 - ranges.loopy0;
-                    p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                    q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                    nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                    ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                    nz = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                    nw = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                    val = m.get_4d(nx, ny, nz, nw);
-                }
-                _ => {
-                    val = 0.0;
+            let mut val = 0.0;
+            supersampling.pixel_samples(x, y, &mut samples);
+            for &(dx, dy, weight) in samples.iter() {
+                let p = (x as f64 + dx) / w as f64;
+                let q = (y as f64 + dy) / h as f64;
+                if let Some(coords) = mapper.map_2d_no_z(ranges, p, q) {
+                    val += coords.get(m) * weight;
                 }
             }
             a.as_mut()[x].as_mut()[y] = val;
         }
     }
+    m.set_footprint(0.0);
 }
 
-pub fn map_3d<Matrix: AsMut<[Row]>, Row: AsMut<[Col]>, Col: AsMut<[f64]>>(seamlessmode: MappingMode, mut a: Matrix, m: &mut ImplicitModule, ranges: &mut MappingRanges) {
+/// Maps a 3D buffer onto the map ranges, sampling the module once per element at the coordinates the mapper gives it.
+/// The module is band-limited to the size of a voxel while it runs, as in [`map_2d()`](fn.map_2d.html), unless that
+/// is turned off with [`MappingRanges::band_limit(false)`](struct.MappingRanges.html#method.band_limit).
+pub fn map_3d<Mapper: CoordinateMapper, Matrix: AsMut<[Row]>, Row: AsMut<[Col]>, Col: AsMut<[f64]>>(mapper: Mapper, mut a: Matrix, m: &mut ImplicitModule, ranges: &mut MappingRanges) {
     let (w, h, d) = {
         let w = a.as_mut().len();
         if w > 0 {
@@ -260,130 +754,31 @@ pub fn map_3d<Matrix: AsMut<[Row]>, Row: AsMut<[Col]>, Col: AsMut<[f64]>>(seamle
         }
     };
 
+    m.set_footprint(ranges.pixel_footprint(w, h, d));
     for x in 0..w {
         for y in 0..h {
             for z in 0..d {
-                let mut p = x as f64 / w as f64;
-                let mut q = y as f64 / h as f64;
-                let mut r = z as f64 / d as f64;
-                let nx;
-                let ny;
-                let nz;
-                let nw;
-                let nu;
-                let nv;
-                let val;
-                let dx;
-                let dy;
-                let dz;
-
-                match seamlessmode {
-                    MappingMode::SeamlessNone => {
-                        dx = ranges.mapx1 - ranges.mapx0;
-                        dy = ranges.mapy1 - ranges.mapy0;
-                        dz = ranges.mapz1 - ranges.mapz0;
-                        nx = ranges.mapx0 + p * dx;
-                        ny = ranges.mapy0 + q * dy;
-                        nz = ranges.mapz0 + r * dz;
-                        val = m.get_3d(nx, ny, nz);
-                    }
-                    MappingMode::SeamlessX => {
-                        dx = ranges.loopx1 - ranges.loopx0;
-                        dy = ranges.mapy1 - ranges.mapy0;
-                        dz = ranges.mapz1 - ranges.mapz0;
-                        p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                        nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                        ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                        nz = ranges.mapy0 + q * dy;
-                        nw = ranges.mapz0 + r * dz;
-                        val = m.get_4d(nx, ny, nz, nw);
-                    }
-                    MappingMode::SeamlessY => {
-                        dx = ranges.mapx1 - ranges.mapx0;
-                        dy = ranges.loopy1 - ranges.loopy0;
-                        dz = ranges.mapz1 - ranges.mapz0;
-                        q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                        nx = ranges.mapx0 + p * dx;
-                        ny = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                        nz = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                        nw = ranges.mapz0 + r * dz;
-                        val = m.get_4d(nx, ny, nz, nw);
-                    }
-                    MappingMode::SeamlessZ => {
-                        dx = ranges.mapx1 - ranges.mapx0;
-                        dy = ranges.mapy1 - ranges.mapy0;
-                        dz = ranges.loopz1 - ranges.loopz0;
-                        r = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
-                        nx = ranges.mapx0 + p * dx;
-                        ny = ranges.mapy0 + q * dy;
-                        nz = ranges.loopz0 + (r * PI2).cos() * dz / PI2;
-                        nw = ranges.loopz0 + (r * PI2).sin() * dz / PI2;
-                        val = m.get_4d(nx, ny, nz, nw);
-                    }
-                    MappingMode::SeamlessXY => {
-                        dx = ranges.loopx1 - ranges.loopx0;
-                        dy = ranges.loopy1 - ranges.loopy0;
-                        dz = ranges.mapz1 - ranges.mapz0;
-                        p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                        q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                        nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                        ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                        nz = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                        nw = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                        nu = ranges.mapz0 + r * dz;
-                        val = m.get_6d(nx, ny, nz, nw, nu, 0.0);
-                    }
-                    MappingMode::SeamlessXZ => {
-                        dx = ranges.loopx1 - ranges.loopx0;
-                        dy = ranges.mapy1 - ranges.mapy0;
-                        dz = ranges.loopz1 - ranges.loopz0;
-                        p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                        r = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
-                        nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                        ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                        nz = ranges.mapy0 + q * dy;
-                        nw = ranges.loopz0 + (r * PI2).cos() * dz / PI2;
-                        nu = ranges.loopz0 + (r * PI2).sin() * dz / PI2;
-                        val = m.get_6d(nx, ny, nz, nw, nu, 0.0);
-                    }
-                    MappingMode::SeamlessYZ => {
-                        dx = ranges.mapx1 - ranges.mapx0;
-                        dy = ranges.loopy1 - ranges.loopy0;
-                        dz = ranges.loopz1 - ranges.loopz0;
-                        q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                        r = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
-                        nx = ranges.mapx0 + p * dx;
-                        ny = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                        nz = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                        nw = ranges.loopz0 + (r * PI2).cos() * dz / PI2;
-                        nu = ranges.loopz0 + (r * PI2).sin() * dz / PI2;
-                        val = m.get_6d(nx, ny, nz, nw, nu, 0.0);
-                    }
-                    MappingMode::SeamlessXYZ => {
-                        dx = ranges.loopx1 - ranges.loopx0;
-                        dy = ranges.loopy1 - ranges.loopy0;
-                        dz = ranges.loopz1 - ranges.loopz0;
-                        p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                        q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                        r = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
-                        nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                        ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                        nz = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                        nw = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                        nu = ranges.loopz0 + (r * PI2).cos() * dz / PI2;
-                        nv = ranges.loopz0 + (r * PI2).sin() * dz / PI2;
-                        val = m.get_6d(nx, ny, nz, nw, nu, nv);
-                    }
+                let p = x as f64 / w as f64;
+                let q = y as f64 / h as f64;
+                let r = z as f64 / d as f64;
+                if let Some(coords) = mapper.map_3d(ranges, p, q, r) {
+                    a.as_mut()[x].as_mut()[y].as_mut()[z] = coords.get(m);
                 }
-                a.as_mut()[x].as_mut()[y].as_mut()[z] = val;
             }
         }
     }
+    m.set_footprint(0.0);
 }
 
 
 
-pub fn map_rgba_2d<Matrix: AsMut<[Row]>, Row: AsMut<[Rgba]>>(seamlessmode: MappingMode, mut a: Matrix, m: &mut RgbaModule, ranges: &mut MappingRanges, z: f64) {
+/// The RGBA version of [`map_2d()`](fn.map_2d.html), band-limited in the same way.
+pub fn map_rgba_2d<Mapper: CoordinateMapper, Matrix: AsMut<[Row]>, Row: AsMut<[Rgba]>>(mapper: Mapper, a: Matrix, m: &mut RgbaModule, ranges: &mut MappingRanges, z: f64) {
+    map_rgba_2d_supersampled(mapper, a, m, ranges, z, &Supersampling::none());
+}
+
+/// The RGBA version of [`map_2d_supersampled()`](fn.map_2d_supersampled.html).
+pub fn map_rgba_2d_supersampled<Mapper: CoordinateMapper, Matrix: AsMut<[Row]>, Row: AsMut<[Rgba]>>(mapper: Mapper, mut a: Matrix, m: &mut RgbaModule, ranges: &mut MappingRanges, z: f64, supersampling: &Supersampling) {
     let (w, h) = {
         let w = a.as_mut().len();
         if w > 0 {
@@ -394,124 +789,32 @@ pub fn map_rgba_2d<Matrix: AsMut<[Row]>, Row: AsMut<[Rgba]>>(seamlessmode: Mappi
         }
     };
 
+    m.set_footprint(ranges.pixel_footprint(w, h, 0));
+    let mut samples = Vec::new();
     for x in 0..w {
         for y in 0..h {
-            let mut p = x as f64 / w as f64;
-            let mut q = y as f64 / h as f64;
-            let r;
-            let nx;
-            let ny;
-            let nz;
-            let nw;
-            let nu;
-            let nv;
-            let val;
-            let dx;
-            let dy;
-            let dz;
-
-            match seamlessmode {
-                MappingMode::SeamlessNone => {
-                    nx = ranges.mapx0 + p * (ranges.mapx1 - ranges.mapx0);
-                    ny = ranges.mapy0 + q * (ranges.mapy1 - ranges.mapy0);
-                    nz = z;
-                    val = m.get_3d(nx, ny, nz);
-                }
-                MappingMode::SeamlessX => {
-                    dx = ranges.loopx1 - ranges.loopx0;
-                    dy = ranges.mapy1 - ranges.mapy0;
-                    p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                    nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                    ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                    nz = ranges.mapy0 + q * dy;
-                    nw = z;
-                    val = m.get_4d(nx, ny, nz, nw);
-                }
-                MappingMode::SeamlessY => {
-                    dx = ranges.mapx1 - ranges.mapx0;
-                    dy = ranges.loopy1 - ranges.loopy0;
-                    q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                    nx = ranges.mapx0 + p * dx;
-                    ny = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                    nz = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                    nw = z;
-                    val = m.get_4d(nx, ny, nz, nw);
-                }
-                MappingMode::SeamlessZ => {
-                    dx = ranges.mapx1 - ranges.mapx0;
-                    dy = ranges.mapy1 - ranges.mapy0;
-                    dz = ranges.loopz1 - ranges.loopz0;
-                    nx = ranges.mapx0 + p * dx;
-                    ny = ranges.mapy0 + p * dy;
-                    r = (z - ranges.mapz0) / (ranges.mapz1 - ranges.mapz0);
-                    let zval = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
-                    nz = ranges.loopz0 + (zval * PI2).cos() * dz / PI2;
-                    nw = ranges.loopz0 + (zval * PI2).sin() * dz / PI2;
-                    val = m.get_4d(nx, ny, nz, nw);
-                }
-                MappingMode::SeamlessXY => {
-                    dx = ranges.loopx1 - ranges.loopx0;
-                    dy = ranges.loopy1 - ranges.loopy0;
-                    p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                    q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                    nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                    ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                    nz = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                    nw = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                    nu = z;
-                    val = m.get_6d(nx, ny, nz, nw, nu, 0.0);
-                }
-                MappingMode::SeamlessXZ => {
-                    dx = ranges.loopx1 - ranges.loopx0;
-                    dy = ranges.mapy1 - ranges.mapy0;
-                    dz = ranges.loopz1 - ranges.loopz0;
-                    r = (z - ranges.mapz0) / (ranges.mapz1 - ranges.mapz0);
-                    let zval = r * (ranges.mapx1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
-                    p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                    nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                    ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                    nz = ranges.mapy0 + q * dy;
-                    nw = ranges.loopz0 + (zval * PI2).cos() * dz / PI2;
-                    nu = ranges.loopz0 + (zval * PI2).sin() * dz / PI2;
-                    val = m.get_6d(nx, ny, nz, nw, nu, 0.0);
-                }
-                MappingMode::SeamlessYZ => {
-                    dx = ranges.mapx1 - ranges.mapx0;
-                    dy = ranges.loopy1 - ranges.loopy0;
-                    dz = ranges.loopz1 - ranges.loopz0;
-                    r = (z - ranges.mapz0) / (ranges.mapz1 - ranges.mapz0);
-                    let zval = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
-                    q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                    nx = ranges.mapx0 + p * dx;
-                    ny = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                    nz = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                    nw = ranges.loopz0 + (zval * PI2).cos() * dz / PI2;
-                    nu = ranges.loopz0 + (zval * PI2).sin() * dz / PI2;
-                    val = m.get_6d(nx, ny, nz, nw, nu, 0.0);
-                }
-                MappingMode::SeamlessXYZ => {
-                    dx = ranges.loopx1 - ranges.loopx0;
-                    dy = ranges.loopy1 - ranges.loopy0;
-                    dz = ranges.loopz1 - ranges.loopz0;
-                    p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                    q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                    r = (z - ranges.mapz0) / (ranges.mapz1 - ranges.mapz0);
-                    let zval = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
-                    nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                    ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                    nz = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                    nw = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                    nu = ranges.loopz0 + (zval * PI2).cos() * dz / PI2;
-                    nv = ranges.loopz0 + (zval * PI2).sin() * dz / PI2;
-                    val = m.get_6d(nx, ny, nz, nw, nu, nv);
+            let mut val = Rgba::with_value(0.0);
+            supersampling.pixel_samples(x, y, &mut samples);
+            for &(dx, dy, weight) in samples.iter() {
+                let p = (x as f64 + dx) / w as f64;
+                let q = (y as f64 + dy) / h as f64;
+                if let Some(coords) = mapper.map_2d(ranges, p, q, z) {
+                    val += coords.get_rgba(m) * weight as f32;
                 }
             }
             a.as_mut()[x].as_mut()[y] = val;
         }
     }
+    m.set_footprint(0.0);
 }
 
-pub fn map_rgba_2d_no_z<Matrix: AsMut<[Row]>, Row: AsMut<[Rgba]>>(seamlessmode: MappingMode, mut a: Matrix, m: &mut RgbaModule, ranges: &mut MappingRanges) {
+/// The RGBA version of [`map_2d_no_z()`](fn.map_2d_no_z.html), band-limited in the same way.
+pub fn map_rgba_2d_no_z<Mapper: CoordinateMapper, Matrix: AsMut<[Row]>, Row: AsMut<[Rgba]>>(mapper: Mapper, a: Matrix, m: &mut RgbaModule, ranges: &mut MappingRanges) {
+    map_rgba_2d_no_z_supersampled(mapper, a, m, ranges, &Supersampling::none());
+}
+
+/// The RGBA version of [`map_2d_no_z_supersampled()`](fn.map_2d_no_z_supersampled.html).
+pub fn map_rgba_2d_no_z_supersampled<Mapper: CoordinateMapper, Matrix: AsMut<[Row]>, Row: AsMut<[Rgba]>>(mapper: Mapper, mut a: Matrix, m: &mut RgbaModule, ranges: &mut MappingRanges, supersampling: &Supersampling) {
     let (w, h) = {
         let w = a.as_mut().len();
         if w > 0 {
@@ -522,63 +825,27 @@ pub fn map_rgba_2d_no_z<Matrix: AsMut<[Row]>, Row: AsMut<[Rgba]>>(seamlessmode:
         }
     };
 
+    m.set_footprint(ranges.pixel_footprint(w, h, 0));
+    let mut samples = Vec::new();
     for x in 0..w {
         for y in 0..h {
-            let mut p = x as f64 / w as f64;
-            let mut q = y as f64 / h as f64;
-            let nx;
-            let ny;
-            let nz;
-            let nw;
-            let val;
-            let dx;
-            let dy;
-
-            match seamlessmode {
-                MappingMode::SeamlessNone => {
-                    nx = ranges.mapx0 + p * (ranges.mapx1 - ranges.mapx0);
-                    ny = ranges.mapy0 + q * (ranges.mapy1 - ranges.mapy0);
-                    val = m.get_2d(nx, ny);
-                }
-                MappingMode::SeamlessX => {
-                    dx = ranges.loopx1 - ranges.loopx0;
-                    dy = ranges.mapy1 - ranges.mapy0;
-                    p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                    nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                    ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                    nz = ranges.mapy0 + q * dy;
-                    val = m.get_3d(nx, ny, nz);
-                }
-                MappingMode::SeamlessY => {
-                    dx = ranges.mapx1 - ranges.mapx0;
-                    dy = ranges.loopy1 - ranges.loopy0;
-                    q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                    nx = ranges.mapx0 + p * dx;
-                    ny = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                    nz = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                    val = m.get_3d(nx, ny, nz);
-                }
-                MappingMode::SeamlessXY => {
-                    dx = ranges.loopx1 - ranges.loopx0;
-                    dy = ranges.loopy1 - ranges.loopy0;
-                    p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                    q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                    nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                    ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                    nz = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                    nw = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                    val = m.get_4d(nx, ny, nz, nw);
-                }
-                _ => {
-                    val = Rgba::with_value(0.0);
+            let mut val = Rgba::with_value(0.0);
+            supersampling.pixel_samples(x, y, &mut samples);
+            for &(dx, dy, weight) in samples.iter() {
+                let p = (x as f64 + dx) / w as f64;
+                let q = (y as f64 + dy) / h as f64;
+                if let Some(coords) = mapper.map_2d_no_z(ranges, p, q) {
+                    val += coords.get_rgba(m) * weight as f32;
                 }
             }
             a.as_mut()[x].as_mut()[y] = val;
         }
     }
+    m.set_footprint(0.0);
 }
 
-pub fn map_rgba_3d<Matrix: AsMut<[Row]>, Row: AsMut<[Col]>, Col: AsMut<[Rgba]>>(seamlessmode: MappingMode, mut a: Matrix, m: &mut RgbaModule, ranges: &mut MappingRanges) {
+/// The RGBA version of [`map_3d()`](fn.map_3d.html), band-limited in the same way.
+pub fn map_rgba_3d<Mapper: CoordinateMapper, Matrix: AsMut<[Row]>, Row: AsMut<[Col]>, Col: AsMut<[Rgba]>>(mapper: Mapper, mut a: Matrix, m: &mut RgbaModule, ranges: &mut MappingRanges) {
     let (w, h, d) = {
         let w = a.as_mut().len();
         if w > 0 {
@@ -594,123 +861,180 @@ pub fn map_rgba_3d<Matrix: AsMut<[Row]>, Row: AsMut<[Col]>, Col: AsMut<[Rgba]>>(
         }
     };
 
+    m.set_footprint(ranges.pixel_footprint(w, h, d));
     for x in 0..w {
         for y in 0..h {
             for z in 0..d {
-                let mut p = x as f64 / w as f64;
-                let mut q = y as f64 / h as f64;
-                let mut r = z as f64 / d as f64;
-                let nx;
-                let ny;
-                let nz;
-                let nw;
-                let nu;
-                let nv;
-                let val;
-                let dx;
-                let dy;
-                let dz;
-
-                match seamlessmode {
-                    MappingMode::SeamlessNone => {
-                        dx = ranges.mapx1 - ranges.mapx0;
-                        dy = ranges.mapy1 - ranges.mapy0;
-                        dz = ranges.mapz1 - ranges.mapz0;
-                        nx = ranges.mapx0 + p * dx;
-                        ny = ranges.mapy0 + q * dy;
-                        nz = ranges.mapz0 + r * dz;
-                        val = m.get_3d(nx, ny, nz);
-                    }
-                    MappingMode::SeamlessX => {
-                        dx = ranges.loopx1 - ranges.loopx0;
-                        dy = ranges.mapy1 - ranges.mapy0;
-                        dz = ranges.mapz1 - ranges.mapz0;
-                        p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                        nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                        ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                        nz = ranges.mapy0 + q * dy;
-                        nw = ranges.mapz0 + r * dz;
-                        val = m.get_4d(nx, ny, nz, nw);
-                    }
-                    MappingMode::SeamlessY => {
-                        dx = ranges.mapx1 - ranges.mapx0;
-                        dy = ranges.loopy1 - ranges.loopy0;
-                        dz = ranges.mapz1 - ranges.mapz0;
-                        q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                        nx = ranges.mapx0 + p * dx;
-                        ny = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                        nz = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                        nw = ranges.mapz0 + r * dz;
-                        val = m.get_4d(nx, ny, nz, nw);
-                    }
-                    MappingMode::SeamlessZ => {
-                        dx = ranges.mapx1 - ranges.mapx0;
-                        dy = ranges.mapy1 - ranges.mapy0;
-                        dz = ranges.loopz1 - ranges.loopz0;
-                        r = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
-                        nx = ranges.mapx0 + p * dx;
-                        ny = ranges.mapy0 + q * dy;
-                        nz = ranges.loopz0 + (r * PI2).cos() * dz / PI2;
-                        nw = ranges.loopz0 + (r * PI2).sin() * dz / PI2;
-                        val = m.get_4d(nx, ny, nz, nw);
-                    }
-                    MappingMode::SeamlessXY => {
-                        dx = ranges.loopx1 - ranges.loopx0;
-                        dy = ranges.loopy1 - ranges.loopy0;
-                        dz = ranges.mapz1 - ranges.mapz0;
-                        p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                        q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                        nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                        ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                        nz = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                        nw = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                        nu = ranges.mapz0 + r * dz;
-                        val = m.get_6d(nx, ny, nz, nw, nu, 0.0);
-                    }
-                    MappingMode::SeamlessXZ => {
-                        dx = ranges.loopx1 - ranges.loopx0;
-                        dy = ranges.mapy1 - ranges.mapy0;
-                        dz = ranges.loopz1 - ranges.loopz0;
-                        p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                        r = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
-                        nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                        ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                        nz = ranges.mapy0 + q * dy;
-                        nw = ranges.loopz0 + (r * PI2).cos() * dz / PI2;
-                        nu = ranges.loopz0 + (r * PI2).sin() * dz / PI2;
-                        val = m.get_6d(nx, ny, nz, nw, nu, 0.0);
-                    }
-                    MappingMode::SeamlessYZ => {
-                        dx = ranges.mapx1 - ranges.mapx0;
-                        dy = ranges.loopy1 - ranges.loopy0;
-                        dz = ranges.loopz1 - ranges.loopz0;
-                        q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                        r = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
-                        nx = ranges.mapx0 + p * dx;
-                        ny = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                        nz = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                        nw = ranges.loopz0 + (r * PI2).cos() * dz / PI2;
-                        nu = ranges.loopz0 + (r * PI2).sin() * dz / PI2;
-                        val = m.get_6d(nx, ny, nz, nw, nu, 0.0);
-                    }
-                    MappingMode::SeamlessXYZ => {
-                        dx = ranges.loopx1 - ranges.loopx0;
-                        dy = ranges.loopy1 - ranges.loopy0;
-                        dz = ranges.loopz1 - ranges.loopz0;
-                        p = p * (ranges.mapx1 - ranges.mapx0) / (ranges.loopx1 - ranges.loopx0);
-                        q = q * (ranges.mapy1 - ranges.mapy0) / (ranges.loopy1 - ranges.loopy0);
-                        r = r * (ranges.mapz1 - ranges.mapz0) / (ranges.loopz1 - ranges.loopz0);
-                        nx = ranges.loopx0 + (p * PI2).cos() * dx / PI2;
-                        ny = ranges.loopx0 + (p * PI2).sin() * dx / PI2;
-                        nz = ranges.loopy0 + (q * PI2).cos() * dy / PI2;
-                        nw = ranges.loopy0 + (q * PI2).sin() * dy / PI2;
-                        nu = ranges.loopz0 + (r * PI2).cos() * dz / PI2;
-                        nv = ranges.loopz0 + (r * PI2).sin() * dz / PI2;
-                        val = m.get_6d(nx, ny, nz, nw, nu, nv);
-                    }
+                let p = x as f64 / w as f64;
+                let q = y as f64 / h as f64;
+                let r = z as f64 / d as f64;
+                if let Some(coords) = mapper.map_3d(ranges, p, q, r) {
+                    a.as_mut()[x].as_mut()[y].as_mut()[z] = coords.get_rgba(m);
                 }
-                a.as_mut()[x].as_mut()[y].as_mut()[z] = val;
             }
         }
     }
+    m.set_footprint(0.0);
+}
+
+pub enum CubeFace {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+
+// The faces in the order map_cube() fills them.
+const CUBE_FACES: [CubeFace; 6] = [
+    CubeFace::PositiveX,
+    CubeFace::NegativeX,
+    CubeFace::PositiveY,
+    CubeFace::NegativeY,
+    CubeFace::PositiveZ,
+    CubeFace::NegativeZ,
+];
+
+// The direction through the center of element (x,y) of a w by h face, with the orientation of OpenGL cube maps.
+fn cube_face_direction(face: &CubeFace, x: usize, y: usize, w: usize, h: usize) -> [f64; 3] {
+    let s = 2.0 * (x as f64 + 0.5) / w as f64 - 1.0;
+    let t = 2.0 * (y as f64 + 0.5) / h as f64 - 1.0;
+    let d = match *face {
+        CubeFace::PositiveX => [1.0, -t, -s],
+        CubeFace::NegativeX => [-1.0, -t, s],
+        CubeFace::PositiveY => [s, 1.0, t],
+        CubeFace::NegativeY => [s, -1.0, -t],
+        CubeFace::PositiveZ => [s, -t, 1.0],
+        CubeFace::NegativeZ => [-s, -t, -1.0],
+    };
+    let len = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+    [d[0] / len, d[1] / len, d[2] / len]
+}
+
+// The direction of element (x,y) of a w by h equirectangular map. The longitude goes around once along x, starting at
+// +X and passing through +Z, so that the map wraps; the latitude goes from the north pole, +Y, to the south pole
+// along y, through the centers of the rows.
+fn equirectangular_direction(x: usize, y: usize, w: usize, h: usize) -> [f64; 3] {
+    let lon = x as f64 / w as f64 * PI2;
+    let lat = PI / 2.0 - (y as f64 + 0.5) / h as f64 * PI;
+    [lat.cos() * lon.cos(), lat.sin(), lat.cos() * lon.sin()]
+}
+
+fn map_sphere<T, Matrix: AsMut<[Row]>, Row: AsMut<[T]>, D: Fn(usize, usize, usize, usize) -> [f64; 3], S: FnMut(f64, f64, f64) -> T>(mut a: Matrix, direction: D, radius: f64, mut sample: S) {
+    let w = a.as_mut().len();
+    for x in 0..w {
+        let row = a.as_mut()[x].as_mut();
+        let h = row.len();
+        for (y, e) in row.iter_mut().enumerate() {
+            let d = direction(x, y, w, h);
+            *e = sample(d[0] * radius, d[1] * radius, d[2] * radius);
+        }
+    }
+}
+
+// The size of an element in domain units, at the equator of an equirectangular map.
+fn equirectangular_footprint(w: usize, h: usize, radius: f64) -> f64 {
+    if w == 0 || h == 0 {
+        return 0.0;
+    }
+    (PI2 * radius / w as f64).max(PI * radius / h as f64).abs()
+}
+
+// The size of an element in domain units, at the center of a cube face.
+fn cube_face_footprint(w: usize, h: usize, radius: f64) -> f64 {
+    if w == 0 || h == 0 {
+        return 0.0;
+    }
+    (2.0 * radius / w as f64).max(2.0 * radius / h as f64).abs()
+}
+
+fn buffer_size<T, Matrix: AsMut<[Row]>, Row: AsMut<[T]>>(a: &mut Matrix) -> (usize, usize) {
+    let w = a.as_mut().len();
+    if w > 0 { (w, a.as_mut()[0].as_mut().len()) } else { (0, 0) }
+}
+
+/// Maps a 2D buffer to the sphere of the given radius around the origin, as an equirectangular (latitude/longitude) map, sampling the module in 3D. The first index of the buffer is the longitude, which goes around the sphere once, so the map wraps horizontally; the second is the latitude, from the north pole (+Y) to the south pole. Sampling on the sphere rather than on a looped plane gives a planet texture without seams or stretching in the domain, though, as with any such map, texels crowd together towards the poles.
+pub fn map_equirectangular<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(mut a: Matrix, m: &mut ImplicitModule, radius: f64) {
+    let (w, h) = buffer_size(&mut a);
+    m.set_footprint(equirectangular_footprint(w, h, radius));
+    map_sphere(a, equirectangular_direction, radius, |x, y, z| m.get_3d(x, y, z));
+    m.set_footprint(0.0);
+}
+
+/// Maps a 2D buffer to one face of a cube map: each element is sampled in 3D at the point of the sphere of the given radius in the direction of the element's center, with the face orientation of OpenGL cube maps (the first index of the buffer is the horizontal one). As the six faces sample the same sphere, they join without seams.
+pub fn map_cube_face<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(face: CubeFace, mut a: Matrix, m: &mut ImplicitModule, radius: f64) {
+    let (w, h) = buffer_size(&mut a);
+    m.set_footprint(cube_face_footprint(w, h, radius));
+    map_sphere(a, |x, y, w, h| cube_face_direction(&face, x, y, w, h), radius, |x, y, z| m.get_3d(x, y, z));
+    m.set_footprint(0.0);
+}
+
+/// Maps the six faces of a cube map, in the order +X, -X, +Y, -Y, +Z, -Z. See [`map_cube_face()`](fn.map_cube_face.html).
+pub fn map_cube<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(faces: &mut [Matrix; 6], m: &mut ImplicitModule, radius: f64) {
+    for (face, a) in CUBE_FACES.iter().zip(faces.iter_mut()) {
+        let (w, h) = buffer_size(a);
+        m.set_footprint(cube_face_footprint(w, h, radius));
+        map_sphere(a.as_mut(), |x, y, w, h| cube_face_direction(face, x, y, w, h), radius, |x, y, z| m.get_3d(x, y, z));
+    }
+    m.set_footprint(0.0);
+}
+
+/// The RGBA version of [`map_equirectangular()`](fn.map_equirectangular.html).
+pub fn map_rgba_equirectangular<Matrix: AsMut<[Row]>, Row: AsMut<[Rgba]>>(mut a: Matrix, m: &mut RgbaModule, radius: f64) {
+    let (w, h) = buffer_size(&mut a);
+    m.set_footprint(equirectangular_footprint(w, h, radius));
+    map_sphere(a, equirectangular_direction, radius, |x, y, z| m.get_3d(x, y, z));
+    m.set_footprint(0.0);
+}
+
+/// The RGBA version of [`map_cube_face()`](fn.map_cube_face.html).
+pub fn map_rgba_cube_face<Matrix: AsMut<[Row]>, Row: AsMut<[Rgba]>>(face: CubeFace, mut a: Matrix, m: &mut RgbaModule, radius: f64) {
+    let (w, h) = buffer_size(&mut a);
+    m.set_footprint(cube_face_footprint(w, h, radius));
+    map_sphere(a, |x, y, w, h| cube_face_direction(&face, x, y, w, h), radius, |x, y, z| m.get_3d(x, y, z));
+    m.set_footprint(0.0);
+}
+
+/// The RGBA version of [`map_cube()`](fn.map_cube.html).
+pub fn map_rgba_cube<Matrix: AsMut<[Row]>, Row: AsMut<[Rgba]>>(faces: &mut [Matrix; 6], m: &mut RgbaModule, radius: f64) {
+    for (face, a) in CUBE_FACES.iter().zip(faces.iter_mut()) {
+        let (w, h) = buffer_size(a);
+        m.set_footprint(cube_face_footprint(w, h, radius));
+        map_sphere(a.as_mut(), |x, y, w, h| cube_face_direction(face, x, y, w, h), radius, |x, y, z| m.get_3d(x, y, z));
+    }
+    m.set_footprint(0.0);
+}
+
+// The mode that loops z as well as the axes that the given mode loops.
+fn with_looped_z(mode: &MappingMode) -> MappingMode {
+    match *mode {
+        MappingMode::SeamlessNone | MappingMode::SeamlessZ => MappingMode::SeamlessZ,
+        MappingMode::SeamlessX | MappingMode::SeamlessXZ => MappingMode::SeamlessXZ,
+        MappingMode::SeamlessY | MappingMode::SeamlessYZ => MappingMode::SeamlessYZ,
+        MappingMode::SeamlessXY | MappingMode::SeamlessXYZ => MappingMode::SeamlessXYZ,
+    }
+}
+
+// The z coordinate of frame k of n, spread over the z map range so that frame n would be frame 0 again.
+fn frame_z(ranges: &MappingRanges, k: usize, n: usize) -> f64 {
+    ranges.mapz0 + k as f64 / n as f64 * (ranges.mapz1 - ranges.mapz0)
+}
+
+/// Renders the frames of a looping animation, one 2D buffer per frame. Time is the z axis: the frames are spread evenly over the z map range, which is looped onto a circle like the seamless modes loop the other axes, so the animation loops when the z map range is a whole number of z loop ranges (the loop range sets how fast the function changes over time). The X and Y seamlessness of the mode is kept, so `SeamlessXY` gives tiles that loop in time, evaluated in 6D, and `SeamlessNone` gives a plain animation, evaluated in 4D; the z part of the mode does not matter, as time always loops.
+pub fn map_animation<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(seamlessmode: MappingMode, frames: &mut [Matrix], m: &mut ImplicitModule, ranges: &mut MappingRanges) {
+    let n = frames.len();
+    for (k, a) in frames.iter_mut().enumerate() {
+        let z = frame_z(ranges, k, n);
+        map_2d(with_looped_z(&seamlessmode), a.as_mut(), m, ranges, z);
+    }
+}
+
+/// The RGBA version of [`map_animation()`](fn.map_animation.html).
+pub fn map_rgba_animation<Matrix: AsMut<[Row]>, Row: AsMut<[Rgba]>>(seamlessmode: MappingMode, frames: &mut [Matrix], m: &mut RgbaModule, ranges: &mut MappingRanges) {
+    let n = frames.len();
+    for (k, a) in frames.iter_mut().enumerate() {
+        let z = frame_z(ranges, k, n);
+        map_rgba_2d(with_looped_z(&seamlessmode), a.as_mut(), m, ranges, z);
+    }
 }
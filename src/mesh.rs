@@ -0,0 +1,524 @@
+use super::ImplicitModule;
+use super::mapping::MappingRanges;
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// An indexed triangle mesh. Triangles are wound counterclockwise when seen from the front, which is the side their
+/// normals point to. The normals and texture coordinates are per vertex, and each is either empty or as many as the
+/// positions.
+#[derive(Clone, Debug, Default)]
+pub struct Mesh {
+    pub positions: Vec<[f64; 3]>,
+    pub normals: Vec<[f64; 3]>,
+    pub uvs: Vec<[f64; 2]>,
+    pub triangles: Vec<[u32; 3]>,
+}
+
+impl Mesh {
+    pub fn new() -> Mesh {
+        Default::default()
+    }
+
+    /// Writes the mesh as a Wavefront OBJ file, with the normals and texture coordinates if it has them.
+    pub fn write_obj<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        for p in self.positions.iter() {
+            writeln!(out, "v {} {} {}", p[0], p[1], p[2])?;
+        }
+        for uv in self.uvs.iter() {
+            writeln!(out, "vt {} {}", uv[0], uv[1])?;
+        }
+        for n in self.normals.iter() {
+            writeln!(out, "vn {} {} {}", n[0], n[1], n[2])?;
+        }
+        let (has_normals, has_uvs) = (!self.normals.is_empty(), !self.uvs.is_empty());
+        for t in self.triangles.iter() {
+            write!(out, "f")?;
+            for &v in t.iter() {
+                // OBJ indices start at 1.
+                let v = v + 1;
+                match (has_uvs, has_normals) {
+                    (true, true) => write!(out, " {}/{}/{}", v, v, v)?,
+                    (true, false) => write!(out, " {}/{}", v, v)?,
+                    (false, true) => write!(out, " {}//{}", v, v)?,
+                    (false, false) => write!(out, " {}", v)?,
+                }
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the mesh as an ASCII PLY file, with the normals and texture coordinates if it has them.
+    pub fn write_ply<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let (has_normals, has_uvs) = (!self.normals.is_empty(), !self.uvs.is_empty());
+        writeln!(out, "ply")?;
+        writeln!(out, "format ascii 1.0")?;
+        writeln!(out, "element vertex {}", self.positions.len())?;
+        writeln!(out, "property double x")?;
+        writeln!(out, "property double y")?;
+        writeln!(out, "property double z")?;
+        if has_normals {
+            writeln!(out, "property double nx")?;
+            writeln!(out, "property double ny")?;
+            writeln!(out, "property double nz")?;
+        }
+        if has_uvs {
+            writeln!(out, "property double s")?;
+            writeln!(out, "property double t")?;
+        }
+        writeln!(out, "element face {}", self.triangles.len())?;
+        writeln!(out, "property list uchar uint vertex_indices")?;
+        writeln!(out, "end_header")?;
+        for (k, p) in self.positions.iter().enumerate() {
+            write!(out, "{} {} {}", p[0], p[1], p[2])?;
+            if has_normals {
+                let n = self.normals[k];
+                write!(out, " {} {} {}", n[0], n[1], n[2])?;
+            }
+            if has_uvs {
+                let uv = self.uvs[k];
+                write!(out, " {} {}", uv[0], uv[1])?;
+            }
+            writeln!(out)?;
+        }
+        for t in self.triangles.iter() {
+            writeln!(out, "3 {} {} {}", t[0], t[1], t[2])?;
+        }
+        Ok(())
+    }
+
+    /// Writes the mesh as a binary glTF 2.0 file (.glb), a single mesh in a single scene, with the normals and texture coordinates if it has them. glTF stores single-precision floats, so the positions are rounded to them.
+    pub fn write_gltf<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let mut bin = Vec::new();
+        let mut views = Vec::new();
+        let mut accessors = Vec::new();
+        let mut attributes = Vec::new();
+
+        let (mut lo, mut hi) = ([0.0f32; 3], [0.0f32; 3]);
+        for (k, p) in self.positions.iter().enumerate() {
+            for a in 0..3 {
+                let v = p[a] as f32;
+                if k == 0 || v < lo[a] {
+                    lo[a] = v;
+                }
+                if k == 0 || v > hi[a] {
+                    hi[a] = v;
+                }
+            }
+        }
+        let bounds = format!(",\"min\":[{},{},{}],\"max\":[{},{},{}]", lo[0], lo[1], lo[2], hi[0], hi[1], hi[2]);
+        let positions = self.positions.iter().flat_map(|p| p.iter().cloned()).collect::<Vec<_>>();
+        attributes.push(format!("\"POSITION\":{}", accessors.len()));
+        gltf_floats(&mut bin, &mut views, &mut accessors, &positions, "VEC3", &bounds);
+        if !self.normals.is_empty() {
+            let normals = self.normals.iter().flat_map(|n| n.iter().cloned()).collect::<Vec<_>>();
+            attributes.push(format!("\"NORMAL\":{}", accessors.len()));
+            gltf_floats(&mut bin, &mut views, &mut accessors, &normals, "VEC3", "");
+        }
+        if !self.uvs.is_empty() {
+            let uvs = self.uvs.iter().flat_map(|uv| uv.iter().cloned()).collect::<Vec<_>>();
+            attributes.push(format!("\"TEXCOORD_0\":{}", accessors.len()));
+            gltf_floats(&mut bin, &mut views, &mut accessors, &uvs, "VEC2", "");
+        }
+
+        views.push(format!("{{\"buffer\":0,\"byteOffset\":{},\"byteLength\":{},\"target\":34963}}", bin.len(), self.triangles.len() * 12));
+        for t in self.triangles.iter() {
+            for &v in t.iter() {
+                bin.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+        // 5125 is UNSIGNED_INT.
+        accessors.push(format!("{{\"bufferView\":{},\"componentType\":5125,\"count\":{},\"type\":\"SCALAR\"}}", views.len() - 1, self.triangles.len() * 3));
+
+        let mut json = format!(
+            "{{\"asset\":{{\"version\":\"2.0\",\"generator\":\"anl\"}},\"scene\":0,\"scenes\":[{{\"nodes\":[0]}}],\"nodes\":[{{\"mesh\":0}}],\
+             \"meshes\":[{{\"primitives\":[{{\"attributes\":{{{}}},\"indices\":{}}}]}}],\"buffers\":[{{\"byteLength\":{}}}],\
+             \"bufferViews\":[{}],\"accessors\":[{}]}}",
+            attributes.join(","), accessors.len() - 1, bin.len(), views.join(","), accessors.join(",")).into_bytes();
+        // Both chunks must be 4-byte aligned: the JSON is padded with spaces, and the binary data with zeros.
+        while json.len() % 4 != 0 {
+            json.push(b' ');
+        }
+        while bin.len() % 4 != 0 {
+            bin.push(0);
+        }
+
+        out.write_all(b"glTF")?;
+        out.write_all(&2u32.to_le_bytes())?;
+        out.write_all(&(12 + 8 + json.len() as u32 + 8 + bin.len() as u32).to_le_bytes())?;
+        out.write_all(&(json.len() as u32).to_le_bytes())?;
+        out.write_all(b"JSON")?;
+        out.write_all(&json)?;
+        out.write_all(&(bin.len() as u32).to_le_bytes())?;
+        out.write_all(b"BIN\0")?;
+        out.write_all(&bin)
+    }
+}
+
+// Appends the values to the glTF buffer as single-precision floats, with a buffer view and an accessor for them.
+// Attribute data is always a multiple of 4 bytes long, so every view starts aligned.
+fn gltf_floats(bin: &mut Vec<u8>, views: &mut Vec<String>, accessors: &mut Vec<String>, values: &[f64], kind: &str, bounds: &str) {
+    let components = if kind == "VEC2" { 2 } else { 3 };
+    views.push(format!("{{\"buffer\":0,\"byteOffset\":{},\"byteLength\":{},\"target\":34962}}", bin.len(), values.len() * 4));
+    for &v in values.iter() {
+        bin.extend_from_slice(&(v as f32).to_bits().to_le_bytes());
+    }
+    // 5126 is FLOAT.
+    accessors.push(format!("{{\"bufferView\":{},\"componentType\":5126,\"count\":{},\"type\":\"{}\"{}}}", views.len() - 1, values.len() / components, kind, bounds));
+}
+
+// Corner k of a cell is at (k & 1, (k >> 1) & 1, (k >> 2) & 1). The edges are listed by axis, and each runs from its
+// lower corner to its upper corner.
+const CELL_EDGES: [(usize, usize); 12] = [
+    (0, 1), (2, 3), (4, 5), (6, 7),
+    (0, 2), (1, 3), (4, 6), (5, 7),
+    (0, 4), (1, 5), (2, 6), (3, 7),
+];
+
+// The corners of each face of a cell, counterclockwise when seen from outside the cell.
+const CELL_FACES: [[usize; 4]; 6] = [
+    [0, 4, 6, 2],
+    [1, 3, 7, 5],
+    [0, 1, 5, 4],
+    [2, 6, 7, 3],
+    [0, 2, 3, 1],
+    [4, 5, 7, 6],
+];
+
+lazy_static! {
+    static ref TRIANGLE_TABLE: Vec<Vec<[usize; 3]>> = (0..256).map(cell_triangles).collect();
+}
+
+fn cell_edge(a: usize, b: usize) -> usize {
+    CELL_EDGES.iter().position(|&(p, q)| (p, q) == (a, b) || (q, p) == (a, b)).unwrap()
+}
+
+// The triangles of a cell whose solid corners are the bits of `case`, as triples of cell edges. They are built by
+// walking the faces: on every face, the isoline runs from each edge where the boundary leaves the solid to the next
+// edge where it enters it again, cutting off the empty corners in between. This separates the empty corners of an
+// ambiguous face, the same way from either side, so neighbouring cells always agree and the surface has no holes.
+// Each crossed edge is left by the isoline of one of its faces and entered by that of the other, so the lines join
+// into loops, which are then split into fans.
+fn cell_triangles(case: usize) -> Vec<[usize; 3]> {
+    let solid = |k: usize| case & (1 << k) != 0;
+    let mut next = [None; 12];
+    for face in CELL_FACES.iter() {
+        let mut exits = Vec::new();
+        let mut entries = Vec::new();
+        for k in 0..4 {
+            let (a, b) = (face[k], face[(k + 1) % 4]);
+            if solid(a) && !solid(b) {
+                exits.push((k, cell_edge(a, b)));
+            } else if !solid(a) && solid(b) {
+                entries.push((k, cell_edge(a, b)));
+            }
+        }
+        for &(k, exit) in exits.iter() {
+            let entry = entries.iter().min_by_key(|&&(j, _)| (j + 4 - k) % 4).unwrap().1;
+            next[exit] = Some(entry);
+        }
+    }
+
+    let mut triangles = Vec::new();
+    let mut visited = [false; 12];
+    for start in 0..12 {
+        if next[start].is_none() || visited[start] {
+            continue;
+        }
+        let mut ring = Vec::new();
+        let mut e = start;
+        while !visited[e] {
+            visited[e] = true;
+            ring.push(e);
+            e = next[e].unwrap();
+        }
+        // A fan whose triangle lies in a face of the cell would be matched by the same triangle, reversed, in the
+        // neighbouring cell, so the fan starts at a corner that has none. Every loop has one. The loops run clockwise
+        // when seen from the empty side, so the fans are reversed to face it.
+        let n = ring.len();
+        let fan = |s: usize| -> Vec<[usize; 3]> {
+            (1..n - 1).map(|k| [ring[s], ring[(s + k + 1) % n], ring[(s + k) % n]]).collect()
+        };
+        let s = (0..n).find(|&s| !fan(s).iter().any(in_one_face)).unwrap();
+        triangles.extend(fan(s));
+    }
+    triangles
+}
+
+fn in_one_face(t: &[usize; 3]) -> bool {
+    CELL_FACES.iter().any(|face| {
+        t.iter().all(|&e| (0..4).any(|k| cell_edge(face[k], face[(k + 1) % 4]) == e))
+    })
+}
+
+// The unit normal of the surface at (x,y,z), pointing towards lower values. The finite differences of get_d*_3() are
+// already in that direction.
+fn surface_normal(m: &mut ImplicitModule, x: f64, y: f64, z: f64) -> [f64; 3] {
+    let g = match m.get_analytic_gradient_3(x, y, z) {
+        Some(g) => [-g[0], -g[1], -g[2]],
+        None => [m.get_dx_3(x, y, z), m.get_dy_3(x, y, z), m.get_dz_3(x, y, z)],
+    };
+    let len = (g[0] * g[0] + g[1] * g[1] + g[2] * g[2]).sqrt();
+    if len > 0.0 {
+        [g[0] / len, g[1] / len, g[2] / len]
+    } else {
+        [0.0; 3]
+    }
+}
+
+/// Extracts the isosurface of a 3D function as a triangle mesh, by marching cubes. The function is sampled on a grid spanning the box from `min` to `max`, with `resolution` cells along each axis, and the surface is placed where it crosses the `iso` level. Points above the level are taken to be solid, as with the output of an [`ImplicitSelect`](../struct.ImplicitSelect.html) choosing between 0 for air and 1 for rock, and the triangles face away from them, towards lower values. The vertices are shared between neighbouring triangles, and their normals are taken from the derivatives of the function: analytic where it provides them, finite differences otherwise.
+///
+/// The surface is left open where it leaves the box. Like the mapping functions, this band-limits the function to the size of a cell while it runs.
+///
+/// ```
+/// use anl::ImplicitSphere;
+/// use anl::mesh::marching_cubes;
+///
+/// // The sphere function falls from 1 at the center to 0 at a radius of 1, so level 0.5 is a sphere of radius 0.5.
+/// let mut sphere = ImplicitSphere::new_3d(0.0, 0.0, 0.0);
+/// let mesh = marching_cubes(&mut sphere, [-1.0; 3], [1.0; 3], [16; 3], 0.5);
+/// assert!(mesh.positions.iter().all(|p| ((p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt() - 0.5).abs() < 0.05));
+///
+/// let mut obj = Vec::new();
+/// mesh.write_obj(&mut obj).unwrap();
+/// ```
+pub fn marching_cubes(m: &mut ImplicitModule, min: [f64; 3], max: [f64; 3], resolution: [usize; 3], iso: f64) -> Mesh {
+    let mut mesh = Mesh::new();
+    let [nx, ny, nz] = resolution;
+    if nx == 0 || ny == 0 || nz == 0 {
+        return mesh;
+    }
+    let step = [(max[0] - min[0]) / nx as f64, (max[1] - min[1]) / ny as f64, (max[2] - min[2]) / nz as f64];
+    let position = |i: usize, j: usize, k: usize| [min[0] + i as f64 * step[0], min[1] + j as f64 * step[1], min[2] + k as f64 * step[2]];
+
+    m.set_footprint(step[0].abs().max(step[1].abs()).max(step[2].abs()));
+    let (sx, sy) = (nx + 1, ny + 1);
+    let index = |i: usize, j: usize, k: usize| i + sx * (j + sy * k);
+    let mut values = vec![0.0; sx * sy * (nz + 1)];
+    for k in 0..nz + 1 {
+        for j in 0..ny + 1 {
+            for i in 0..nx + 1 {
+                let p = position(i, j, k);
+                values[index(i, j, k)] = m.get_3d(p[0], p[1], p[2]);
+            }
+        }
+    }
+
+    // Vertices are keyed by the grid edge they lie on: the index of its lower grid point, and its axis.
+    let mut vertices: HashMap<(usize, usize), u32> = HashMap::new();
+    for k in 0..nz {
+        for j in 0..ny {
+            for i in 0..nx {
+                let corner = |c: usize| (i + (c & 1), j + ((c >> 1) & 1), k + ((c >> 2) & 1));
+                let mut case = 0;
+                for c in 0..8 {
+                    let (ci, cj, ck) = corner(c);
+                    if values[index(ci, cj, ck)] > iso {
+                        case |= 1 << c;
+                    }
+                }
+
+                for t in TRIANGLE_TABLE[case].iter() {
+                    let mut triangle = [0; 3];
+                    for (v, &e) in triangle.iter_mut().zip(t.iter()) {
+                        let (a, b) = CELL_EDGES[e];
+                        let (ai, aj, ak) = corner(a);
+                        let key = (index(ai, aj, ak), e / 4);
+                        *v = match vertices.get(&key) {
+                            Some(&v) => v,
+                            None => {
+                                let (bi, bj, bk) = corner(b);
+                                let (va, vb) = (values[index(ai, aj, ak)], values[index(bi, bj, bk)]);
+                                let s = (iso - va) / (vb - va);
+                                let (pa, pb) = (position(ai, aj, ak), position(bi, bj, bk));
+                                let p = [pa[0] + s * (pb[0] - pa[0]), pa[1] + s * (pb[1] - pa[1]), pa[2] + s * (pb[2] - pa[2])];
+                                mesh.normals.push(surface_normal(m, p[0], p[1], p[2]));
+                                mesh.positions.push(p);
+                                let v = mesh.positions.len() as u32 - 1;
+                                vertices.insert(key, v);
+                                v
+                            }
+                        };
+                    }
+                    mesh.triangles.push(triangle);
+                }
+            }
+        }
+    }
+    m.set_footprint(0.0);
+    mesh
+}
+
+/// How [`heightmap_mesh()`](fn.heightmap_mesh.html) and [`heightmap_mesh_from_module()`](fn.heightmap_mesh_from_module.html) lay out the grid. The mesh is Y-up, as in glTF: the first index of the heightmap runs along x, the second along z, and the heights, multiplied by the vertical scale, give y. By default the grid spans one unit per sample from the origin, the vertical scale is 1, and there are no skirts.
+///
+/// ```
+/// use anl::mesh::HeightmapOptions;
+///
+/// let options = HeightmapOptions::new().extent(0.0, 100.0, 0.0, 100.0).vertical_scale(20.0).skirt(5.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct HeightmapOptions {
+    extent: Option<[f64; 4]>,
+    scale: f64,
+    skirt: f64,
+}
+
+impl HeightmapOptions {
+    pub fn new() -> HeightmapOptions {
+        HeightmapOptions {
+            extent: None,
+            scale: 1.0,
+            skirt: 0.0,
+        }
+    }
+
+    /// Spreads the grid over x0..x1 and z0..z1, the first and last samples landing on the edges.
+    pub fn extent(mut self, x0: f64, x1: f64, z0: f64, z1: f64) -> HeightmapOptions {
+        self.extent = Some([x0, x1, z0, z1]);
+        self
+    }
+
+    pub fn vertical_scale(mut self, s: f64) -> HeightmapOptions {
+        self.scale = s;
+        self
+    }
+
+    /// Hangs a skirt of the given depth from the edges of the grid: a vertical strip that hides the cracks between neighbouring tiles at different levels of detail. The skirt vertices share the normals and texture coordinates of the edge above them, so that it is shaded like the edge. 0 leaves it out.
+    pub fn skirt(mut self, depth: f64) -> HeightmapOptions {
+        self.skirt = depth;
+        self
+    }
+}
+
+impl Default for HeightmapOptions {
+    fn default() -> Self {
+        HeightmapOptions::new()
+    }
+}
+
+// Builds the grid mesh of a w by h heightmap, stored by columns like the mapping buffers, from its heights and its
+// normals in heightmap units: the normals are scaled to the extent and the vertical scale here.
+fn grid_mesh(w: usize, h: usize, heights: &[f64], normals: &[[f64; 3]], options: &HeightmapOptions) -> Mesh {
+    let mut mesh = Mesh::new();
+    if w < 2 || h < 2 {
+        return mesh;
+    }
+    let [x0, x1, z0, z1] = options.extent.unwrap_or([0.0, (w - 1) as f64, 0.0, (h - 1) as f64]);
+    let (dx, dz) = ((x1 - x0) / (w - 1) as f64, (z1 - z0) / (h - 1) as f64);
+    let s = options.scale;
+
+    for i in 0..w {
+        for j in 0..h {
+            let k = i * h + j;
+            mesh.positions.push([x0 + i as f64 * dx, heights[k] * s, z0 + j as f64 * dz]);
+            // A normal (a, b, 1) of z = f(x, y) becomes (a * s / dx, 1, b * s / dz) when x, y and z are scaled by
+            // dx, dz and s, and y is up.
+            let n = normals[k];
+            let (nx, ny, nz) = (n[0] / n[2] * s / dx, 1.0, n[1] / n[2] * s / dz);
+            let len = (nx * nx + ny * ny + nz * nz).sqrt();
+            mesh.normals.push([nx / len, ny / len, nz / len]);
+            mesh.uvs.push([i as f64 / (w - 1) as f64, j as f64 / (h - 1) as f64]);
+        }
+    }
+
+    // Seen from above, the quad (a, b, c, d) below runs clockwise, so its triangles are (a, c, b) and (b, c, d). A
+    // mirrored extent turns the grid over, and the winding with it.
+    let mirrored = dx * dz < 0.0;
+    let push = |mesh: &mut Mesh, a: usize, b: usize, c: usize| {
+        let t = if mirrored { [a as u32, c as u32, b as u32] } else { [a as u32, b as u32, c as u32] };
+        mesh.triangles.push(t);
+    };
+    for i in 0..w - 1 {
+        for j in 0..h - 1 {
+            let (a, b, c, d) = (i * h + j, (i + 1) * h + j, i * h + j + 1, (i + 1) * h + j + 1);
+            push(&mut mesh, a, c, b);
+            push(&mut mesh, b, c, d);
+        }
+    }
+
+    if options.skirt != 0.0 {
+        // The edge of the grid, counterclockwise when seen from above, so that the outside is to the right.
+        let mut edge = Vec::new();
+        edge.extend(0..h);
+        edge.extend((1..w).map(|i| i * h + h - 1));
+        edge.extend((0..h - 1).rev().map(|j| (w - 1) * h + j));
+        edge.extend((1..w - 1).rev().map(|i| i * h));
+        let base = mesh.positions.len();
+        for &v in edge.iter() {
+            let p = mesh.positions[v];
+            mesh.positions.push([p[0], p[1] - options.skirt, p[2]]);
+            let (n, uv) = (mesh.normals[v], mesh.uvs[v]);
+            mesh.normals.push(n);
+            mesh.uvs.push(uv);
+        }
+        for k in 0..edge.len() {
+            let l = (k + 1) % edge.len();
+            let (p, q, pl, ql) = (edge[k], edge[l], base + k, base + l);
+            push(&mut mesh, p, pl, q);
+            push(&mut mesh, q, pl, ql);
+        }
+    }
+    mesh
+}
+
+/// Turns a heightmap, such as a buffer filled by [`map_2d()`](../mapping/fn.map_2d.html), into a grid mesh with one vertex per sample, laid out as set by the options. The normals are taken from the central differences of the heights, and the texture coordinates span 0..1 over the grid. Every row of the heightmap must have the same length; a ragged heightmap panics.
+///
+/// ```
+/// use anl::{ImplicitFractal, FractalType, BasisType, InterpType, ImplicitModule};
+/// use anl::mapping::{map_2d, MappingMode, MappingRanges};
+/// use anl::mesh::{heightmap_mesh, HeightmapOptions};
+///
+/// let mut terrain = ImplicitFractal::new(FractalType::FBM, BasisType::Gradient, InterpType::Quintic);
+/// terrain.set_seed(1);
+/// let mut heights = vec![vec![0.0; 64]; 64];
+/// map_2d(MappingMode::SeamlessNone, &mut heights, &mut terrain, &mut MappingRanges::new(), 0.0);
+///
+/// let mesh = heightmap_mesh(&heights, &HeightmapOptions::new().vertical_scale(10.0).skirt(2.0));
+/// let mut glb = Vec::new();
+/// mesh.write_gltf(&mut glb).unwrap();
+/// ```
+pub fn heightmap_mesh<Matrix: AsRef<[Row]>, Row: AsRef<[f64]>>(a: Matrix, options: &HeightmapOptions) -> Mesh {
+    let a = a.as_ref();
+    let w = a.len();
+    let h = if w > 0 { a[0].as_ref().len() } else { 0 };
+    assert!(a.iter().all(|row| row.as_ref().len() == h), "heightmap_mesh: every row of the heightmap must have {} samples, as the first one does", h);
+    let heights = a.iter().flat_map(|row| row.as_ref().iter().cloned()).collect::<Vec<_>>();
+    let height = |i: usize, j: usize| heights[i * h + j];
+
+    let mut normals = Vec::with_capacity(w * h);
+    for i in 0..w {
+        for j in 0..h {
+            // Central differences, one-sided at the edges, in the (f(x - 1) - f(x + 1)) / 2 form of a normal.
+            let (il, ih) = (i.saturating_sub(1), (i + 1).min(w - 1));
+            let (jl, jh) = (j.saturating_sub(1), (j + 1).min(h - 1));
+            let nx = (height(il, j) - height(ih, j)) / (ih - il).max(1) as f64;
+            let ny = (height(i, jl) - height(i, jh)) / (jh - jl).max(1) as f64;
+            normals.push([nx, ny, 1.0]);
+        }
+    }
+    grid_mesh(w, h, &heights, &normals, options)
+}
+
+/// Samples the function on a `w` by `h` grid over the x and y map ranges and turns it into a grid mesh, laid out as set by the options. Unlike the mapping functions, the samples span the ranges end to end, so that meshes of neighbouring ranges share their edges. The normals are taken from the derivatives of the function ([`get_dx_2()`](../trait.ImplicitModule.html#method.get_dx_2) and [`get_dy_2()`](../trait.ImplicitModule.html#method.get_dy_2)), rather than from the grid, so they keep the detail between the samples.
+pub fn heightmap_mesh_from_module(m: &mut ImplicitModule, ranges: &MappingRanges, w: usize, h: usize, options: &HeightmapOptions) -> Mesh {
+    if w < 2 || h < 2 {
+        return Mesh::new();
+    }
+    let ((mx0, mx1), (my0, my1)) = (ranges.get_map_x(), ranges.get_map_y());
+    let (dx, dy) = ((mx1 - mx0) / (w - 1) as f64, (my1 - my0) / (h - 1) as f64);
+
+    m.set_footprint(dx.abs().max(dy.abs()));
+    let mut heights = Vec::with_capacity(w * h);
+    let mut normals = Vec::with_capacity(w * h);
+    for i in 0..w {
+        for j in 0..h {
+            let (x, y) = (mx0 + i as f64 * dx, my0 + j as f64 * dy);
+            heights.push(m.get_2d(x, y));
+            // get_dx_2() and get_dy_2() give twice the downhill slope per domain unit; the grid is in samples.
+            normals.push([m.get_dx_2(x, y) * dx / 2.0, m.get_dy_2(x, y) * dy / 2.0, 1.0]);
+        }
+    }
+    m.set_footprint(0.0);
+    grid_mesh(w, h, &heights, &normals, options)
+}
@@ -1,6 +1,8 @@
 use super::utility::{lerp, fast_floor, array_dot, array_dot_3, array_dot_4};
+use super::random_gen::{PRNG, LCG, CMWC4096, get_01, get_target};
 
 use std::cmp::Ordering;
+use std::collections::BinaryHeap;
 
 // Lookup tables for 2D and 3D gradient and simplex noise.
 // Generated with boost::random, using a lagged Fibonacci generator and a uniform_on_sphere distribution.
@@ -1306,12 +1308,20 @@ const FNV_32_PRIME: u32 = 0x01000193;
 const FNV_32_INIT: u32  = 2166136261;
 const FNV_MASK_8: u32   = (1<<8) - 1;
 
+/// The largest number of coordinates the N-dimensional lattice functions (`*_noise_nd_with()`) take into account.
+/// Further coordinates are ignored. An N-dimensional cell has 2^N corners, so the cost doubles with every dimension.
+pub const MAX_DIMENSIONS: usize = 8;
+
 pub type InterpFunc = fn(f64) -> f64;
 
+pub type NoiseFunc1 = fn(f64, u32, InterpFunc) -> f64;
 pub type NoiseFunc2 = fn(f64, f64, u32, InterpFunc) -> f64;
 pub type NoiseFunc3 = fn(f64, f64, f64, u32, InterpFunc) -> f64;
 pub type NoiseFunc4 = fn(f64, f64, f64, f64, u32, InterpFunc) -> f64;
 pub type NoiseFunc6 = fn(f64, f64, f64, f64, f64, f64, u32, InterpFunc) -> f64;
+pub type NoiseGradientFunc2 = fn(f64, f64, [u32; 2], u32, InterpFunc, InterpFunc) -> [f64; 2];
+pub type NoiseGradientFunc3 = fn(f64, f64, f64, [u32; 3], u32, InterpFunc, InterpFunc) -> [f64; 3];
+pub type NoiseFuncN = fn(&[f64], u32, InterpFunc) -> f64;
 
 #[inline(always)]
 pub fn no_interp(_: f64) -> f64 {
@@ -1392,39 +1402,300 @@ fn compute_hash_f64_f6(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32
     xor_fold_hash(fnv32_a_buf(&d)) as u32
 }
 
+// Lattice hash backends. The classic ANL hash XOR-folds FNV-1A down to 8 bits and indexes the lookup tables above;
+// the PCG backend keeps the full 32 bits, so value noise is continuous-valued and gradients are computed rather than
+// looked up.
+
+/// Hash backend used by the lattice noise functions. Implementors map integer lattice coordinates (or raw floating
+/// point coordinates, for white noise) to a hash, and turn that hash into a value, a white noise sample or a gradient.
+pub trait LatticeHash {
+	fn hash_2(x: i32, y: i32, seed: u32) -> u32;
+	fn hash_3(x: i32, y: i32, z: i32, seed: u32) -> u32;
+	fn hash_4(x: i32, y: i32, z: i32, w: i32, seed: u32) -> u32;
+	fn hash_6(x: i32, y: i32, z: i32, w: i32, u: i32, v: i32, seed: u32) -> u32;
+
+	fn hash_f64_2(x: f64, y: f64, seed: u32) -> u32;
+	fn hash_f64_3(x: f64, y: f64, z: f64, seed: u32) -> u32;
+	fn hash_f64_4(x: f64, y: f64, z: f64, w: f64, seed: u32) -> u32;
+	fn hash_f64_6(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32) -> u32;
+
+	/// Maps a hash to a lattice value in the range [-1,1].
+	fn value(hash: u32) -> f64;
+	/// Maps a hash to a white noise sample in the range [-1,1].
+	fn white(hash: u32) -> f64;
+
+	/// Maps a hash to a 1D gradient: a slope in the range [-1,1].
+	fn gradient_1(hash: u32) -> f64 {
+		Self::value(hash)
+	}
+	fn gradient_2(hash: u32) -> [f64; 2];
+	fn gradient_3(hash: u32) -> [f64; 3];
+	fn gradient_4(hash: u32) -> [f64; 4];
+	fn gradient_6(hash: u32) -> [f64; 6];
+
+	/// Hashes any number of lattice coordinates. For 2, 3, 4 and 6 coordinates it matches the fixed-dimension hashes.
+	fn hash_n(c: &[i32], seed: u32) -> u32;
+	/// Hashes any number of raw coordinates, for white noise. For 2, 3, 4 and 6 coordinates it matches the
+	/// fixed-dimension hashes.
+	fn hash_f64_n(p: &[f64], seed: u32) -> u32;
+
+	/// Fills `g` with the gradient of the given hash, in as many dimensions as `g` has. The dimensions with a
+	/// fixed-dimension gradient use it; the others get a unit vector from the hashed components.
+	fn gradient_n(hash: u32, g: &mut [f64]) {
+		match g.len() {
+			1 => g[0] = Self::gradient_1(hash),
+			2 => g.copy_from_slice(&Self::gradient_2(hash)),
+			3 => g.copy_from_slice(&Self::gradient_3(hash)),
+			4 => g.copy_from_slice(&Self::gradient_4(hash)),
+			6 => g.copy_from_slice(&Self::gradient_6(hash)),
+			_ => {
+				pcg_gradient(hash, g);
+			}
+		}
+	}
+}
+
+/// The original ANL hash: FNV-1A, XOR-folded to 8 bits. Value noise has 256 distinct levels and gradients come from the
+/// 256-entry lookup tables. Use it to reproduce the output of earlier versions.
+pub struct FnvHash;
+
+impl LatticeHash for FnvHash {
+	#[inline(always)]
+	fn hash_2(x: i32, y: i32, seed: u32) -> u32 {
+		hash_coords_2(x, y, seed)
+	}
+	#[inline(always)]
+	fn hash_3(x: i32, y: i32, z: i32, seed: u32) -> u32 {
+		hash_coords_3(x, y, z, seed)
+	}
+	#[inline(always)]
+	fn hash_4(x: i32, y: i32, z: i32, w: i32, seed: u32) -> u32 {
+		hash_coords_4(x, y, z, w, seed)
+	}
+	#[inline(always)]
+	fn hash_6(x: i32, y: i32, z: i32, w: i32, u: i32, v: i32, seed: u32) -> u32 {
+		hash_coords_6(x, y, z, w, u, v, seed)
+	}
+
+	#[inline(always)]
+	fn hash_f64_2(x: f64, y: f64, seed: u32) -> u32 {
+		compute_hash_f64_f2(x, y, seed)
+	}
+	#[inline(always)]
+	fn hash_f64_3(x: f64, y: f64, z: f64, seed: u32) -> u32 {
+		compute_hash_f64_f3(x, y, z, seed)
+	}
+	#[inline(always)]
+	fn hash_f64_4(x: f64, y: f64, z: f64, w: f64, seed: u32) -> u32 {
+		compute_hash_f64_f4(x, y, z, w, seed)
+	}
+	#[inline(always)]
+	fn hash_f64_6(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32) -> u32 {
+		compute_hash_f64_f6(x, y, z, w, u, v, seed)
+	}
+
+	#[inline(always)]
+	fn value(hash: u32) -> f64 {
+		hash as f64 / 255.0 * 2.0 - 1.0
+	}
+	#[inline(always)]
+	fn white(hash: u32) -> f64 {
+		WHITENOISE_LUT[hash as usize]
+	}
+
+	#[inline(always)]
+	fn gradient_2(hash: u32) -> [f64; 2] {
+		GRADIENT_2D_LUT[hash as usize]
+	}
+	#[inline(always)]
+	fn gradient_3(hash: u32) -> [f64; 3] {
+		GRADIENT_3D_LUT[hash as usize]
+	}
+	#[inline(always)]
+	fn gradient_4(hash: u32) -> [f64; 4] {
+		GRADIENT_4D_LUT[hash as usize]
+	}
+	#[inline(always)]
+	fn gradient_6(hash: u32) -> [f64; 6] {
+		GRADIENT_6D_LUT[hash as usize]
+	}
+
+	fn hash_n(c: &[i32], seed: u32) -> u32 {
+		let n = c.len().min(MAX_DIMENSIONS);
+		let mut d = [0u32; MAX_DIMENSIONS + 1];
+		for k in 0..n {
+			d[k] = c[k] as u32;
+		}
+		d[n] = seed;
+		xor_fold_hash(fnv32_a_buf(&d[..n + 1])) as u32
+	}
+	fn hash_f64_n(p: &[f64], seed: u32) -> u32 {
+		let n = p.len().min(MAX_DIMENSIONS);
+		let mut d = [0u32; MAX_DIMENSIONS + 1];
+		for k in 0..n {
+			d[k] = p[k] as u32;
+		}
+		d[n] = seed;
+		xor_fold_hash(fnv32_a_buf(&d[..n + 1])) as u32
+	}
+}
+
+/// A PCG-style integer hash (Jarzynski & Olano, "Hash Functions for GPU Rendering") chained over the coordinates.
+/// The full 32-bit result is kept: value noise is continuous-valued, gradients are uniformly distributed unit vectors
+/// and white noise hashes the full floating point coordinates.
+pub struct PcgHash;
+
+#[inline(always)]
+fn pcg_hash(v: u32) -> u32 {
+	let state = v.wrapping_mul(747796405).wrapping_add(2891336453);
+	let word = ((state >> ((state >> 28) + 4)) ^ state).wrapping_mul(277803737);
+	(word >> 22) ^ word
+}
+
+#[inline(always)]
+fn pcg_hash_buf(buf: &[u32]) -> u32 {
+	let mut h = 0;
+	for v in buf {
+		h = pcg_hash(h ^ *v);
+	}
+	h
+}
+
+#[inline(always)]
+fn f64_bits(v: f64) -> u32 {
+	let b = v.to_bits();
+	(b ^ (b >> 32)) as u32
+}
+
+#[inline(always)]
+fn hash_to_signed(hash: u32) -> f64 {
+	hash as f64 / 4294967295.0 * 2.0 - 1.0
+}
+
+// A unit vector uniformly distributed over the sphere: the normalized direction of a vector of independent Gaussian
+// components, drawn in pairs from the chained hash with the Box-Muller transform.
+fn pcg_gradient<A: AsMut<[f64]>>(hash: u32, mut g: A) -> A {
+	let mut len = 0.0;
+	let mut h = hash;
+	for pair in g.as_mut().chunks_mut(2) {
+		h = pcg_hash(h);
+		let r = (-2.0 * ((h as f64 + 1.0) / 4294967296.0).ln()).sqrt();
+		h = pcg_hash(h);
+		let (s, c) = (h as f64 / 4294967296.0 * ::std::f64::consts::PI * 2.0).sin_cos();
+		pair[0] = r * c;
+		len += pair[0] * pair[0];
+		if pair.len() > 1 {
+			pair[1] = r * s;
+			len += pair[1] * pair[1];
+		}
+	}
+	if len > 0.0 {
+		let len = len.sqrt();
+		for c in g.as_mut().iter_mut() {
+			*c /= len;
+		}
+	}
+	g
+}
+
+impl LatticeHash for PcgHash {
+	#[inline(always)]
+	fn hash_2(x: i32, y: i32, seed: u32) -> u32 {
+		pcg_hash_buf(&[seed, x as u32, y as u32])
+	}
+	#[inline(always)]
+	fn hash_3(x: i32, y: i32, z: i32, seed: u32) -> u32 {
+		pcg_hash_buf(&[seed, x as u32, y as u32, z as u32])
+	}
+	#[inline(always)]
+	fn hash_4(x: i32, y: i32, z: i32, w: i32, seed: u32) -> u32 {
+		pcg_hash_buf(&[seed, x as u32, y as u32, z as u32, w as u32])
+	}
+	#[inline(always)]
+	fn hash_6(x: i32, y: i32, z: i32, w: i32, u: i32, v: i32, seed: u32) -> u32 {
+		pcg_hash_buf(&[seed, x as u32, y as u32, z as u32, w as u32, u as u32, v as u32])
+	}
+
+	#[inline(always)]
+	fn hash_f64_2(x: f64, y: f64, seed: u32) -> u32 {
+		pcg_hash_buf(&[seed, f64_bits(x), f64_bits(y)])
+	}
+	#[inline(always)]
+	fn hash_f64_3(x: f64, y: f64, z: f64, seed: u32) -> u32 {
+		pcg_hash_buf(&[seed, f64_bits(x), f64_bits(y), f64_bits(z)])
+	}
+	#[inline(always)]
+	fn hash_f64_4(x: f64, y: f64, z: f64, w: f64, seed: u32) -> u32 {
+		pcg_hash_buf(&[seed, f64_bits(x), f64_bits(y), f64_bits(z), f64_bits(w)])
+	}
+	#[inline(always)]
+	fn hash_f64_6(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32) -> u32 {
+		pcg_hash_buf(&[seed, f64_bits(x), f64_bits(y), f64_bits(z), f64_bits(w), f64_bits(u), f64_bits(v)])
+	}
+
+	#[inline(always)]
+	fn value(hash: u32) -> f64 {
+		hash_to_signed(hash)
+	}
+	#[inline(always)]
+	fn white(hash: u32) -> f64 {
+		hash_to_signed(hash)
+	}
+
+	#[inline(always)]
+	fn gradient_2(hash: u32) -> [f64; 2] {
+		let angle = hash as f64 / 4294967296.0 * ::std::f64::consts::PI * 2.0;
+		[angle.cos(), angle.sin()]
+	}
+	#[inline(always)]
+	fn gradient_3(hash: u32) -> [f64; 3] {
+		// Uniform on the sphere: z uniform in [-1,1], azimuth uniform in [0,2pi)
+		let z = (hash >> 16) as f64 / 65535.0 * 2.0 - 1.0;
+		let angle = (hash & 0xffff) as f64 / 65536.0 * ::std::f64::consts::PI * 2.0;
+		let r = (1.0 - z * z).sqrt();
+		[r * angle.cos(), r * angle.sin(), z]
+	}
+	#[inline(always)]
+	fn gradient_4(hash: u32) -> [f64; 4] {
+		pcg_gradient(hash, [0.0; 4])
+	}
+	#[inline(always)]
+	fn gradient_6(hash: u32) -> [f64; 6] {
+		pcg_gradient(hash, [0.0; 6])
+	}
+
+	fn hash_n(c: &[i32], seed: u32) -> u32 {
+		c.iter().take(MAX_DIMENSIONS).fold(pcg_hash(seed), |h, v| pcg_hash(h ^ *v as u32))
+	}
+	fn hash_f64_n(p: &[f64], seed: u32) -> u32 {
+		p.iter().take(MAX_DIMENSIONS).fold(pcg_hash(seed), |h, v| pcg_hash(h ^ f64_bits(*v)))
+	}
+}
+
 // Worker noise functions
 type WorkerNoise2 = fn(f64, f64, i32, i32, u32) -> f64;
 type WorkerNoise3 = fn(f64, f64, f64, i32, i32, i32, u32) -> f64;
 type WorkerNoise4 = fn(f64, f64, f64, f64, i32, i32, i32, i32, u32) -> f64;
 type WorkerNoise6 = fn(f64, f64, f64, f64, f64, f64, i32, i32, i32, i32, i32, i32, u32) -> f64;
 
-fn value_noise_2(_: f64, _: f64, ix: i32, iy: i32, seed: u32) -> f64 {
-	let n = hash_coords_2(ix,iy,seed);
-    let noise = n as f64 / 255.0;
-    noise*2.0 - 1.0
+fn value_noise_2<H: LatticeHash>(_: f64, _: f64, ix: i32, iy: i32, seed: u32) -> f64 {
+	H::value(H::hash_2(ix, iy, seed))
 }
 
-fn value_noise_3(_: f64, _: f64, _: f64, ix: i32, iy: i32, iz: i32, seed: u32) -> f64 {
-	let n = hash_coords_3(ix,iy,iz,seed);
-    let noise = n as f64 / 255.0;
-    noise*2.0 - 1.0
+fn value_noise_3<H: LatticeHash>(_: f64, _: f64, _: f64, ix: i32, iy: i32, iz: i32, seed: u32) -> f64 {
+	H::value(H::hash_3(ix, iy, iz, seed))
 }
 
-fn value_noise_4(_: f64, _: f64, _: f64, _: f64, ix: i32, iy: i32, iz: i32, iw: i32, seed: u32) -> f64 {
-	let n = hash_coords_4(ix,iy,iz,iw,seed);
-    let noise = n as f64 / 255.0;
-    noise*2.0 - 1.0
+fn value_noise_4<H: LatticeHash>(_: f64, _: f64, _: f64, _: f64, ix: i32, iy: i32, iz: i32, iw: i32, seed: u32) -> f64 {
+	H::value(H::hash_4(ix, iy, iz, iw, seed))
 }
 
-fn value_noise_6(_: f64, _: f64, _: f64, _: f64, _: f64, _: f64, ix: i32, iy: i32, iz: i32, iw: i32, iu: i32, iv: i32, seed: u32) -> f64 {
-	let n = hash_coords_6(ix,iy,iz,iw,iu,iv,seed);
-    let noise = n as f64 / 255.0;
-    noise*2.0 - 1.0
+fn value_noise_6<H: LatticeHash>(_: f64, _: f64, _: f64, _: f64, _: f64, _: f64, ix: i32, iy: i32, iz: i32, iw: i32, iu: i32, iv: i32, seed: u32) -> f64 {
+	H::value(H::hash_6(ix, iy, iz, iw, iu, iv, seed))
 }
 
-fn grad_noise_2(x: f64, y: f64, ix: i32, iy: i32, seed: u32) -> f64 {
-	let hash = hash_coords_2(ix, iy, seed);
-	let vec = &GRADIENT_2D_LUT[hash as usize];
+fn grad_noise_2<H: LatticeHash>(x: f64, y: f64, ix: i32, iy: i32, seed: u32) -> f64 {
+	let vec = H::gradient_2(H::hash_2(ix, iy, seed));
 
 	let dx = x - ix as f64;
 	let dy = y - iy as f64;
@@ -1432,9 +1703,8 @@ fn grad_noise_2(x: f64, y: f64, ix: i32, iy: i32, seed: u32) -> f64 {
 	dx*vec[0] + dy*vec[1]
 }
 
-fn grad_noise_3(x: f64, y: f64, z: f64, ix: i32, iy: i32, iz: i32, seed: u32) -> f64 {
-	let hash = hash_coords_3(ix, iy, iz, seed);
-	let vec = &GRADIENT_3D_LUT[hash as usize];
+fn grad_noise_3<H: LatticeHash>(x: f64, y: f64, z: f64, ix: i32, iy: i32, iz: i32, seed: u32) -> f64 {
+	let vec = H::gradient_3(H::hash_3(ix, iy, iz, seed));
 
 	let dx = x - ix as f64;
 	let dy = y - iy as f64;
@@ -1443,9 +1713,8 @@ fn grad_noise_3(x: f64, y: f64, z: f64, ix: i32, iy: i32, iz: i32, seed: u32) ->
 	dx*vec[0] + dy*vec[1] + dz*vec[2]
 }
 
-fn grad_noise_4(x: f64, y: f64, z: f64, w: f64, ix: i32, iy: i32, iz: i32, iw: i32, seed: u32) -> f64 {
-	let hash = hash_coords_4(ix, iy, iz, iw, seed);
-	let vec = &GRADIENT_4D_LUT[hash as usize];
+fn grad_noise_4<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, ix: i32, iy: i32, iz: i32, iw: i32, seed: u32) -> f64 {
+	let vec = H::gradient_4(H::hash_4(ix, iy, iz, iw, seed));
 
 	let dx = x - ix as f64;
 	let dy = y - iy as f64;
@@ -1455,9 +1724,8 @@ fn grad_noise_4(x: f64, y: f64, z: f64, w: f64, ix: i32, iy: i32, iz: i32, iw: i
 	dx*vec[0] + dy*vec[1] + dz*vec[2] + dw*vec[3]
 }
 
-fn grad_noise_6(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, ix: i32, iy: i32, iz: i32, iw: i32, iu: i32, iv: i32, seed: u32) -> f64 {
-	let hash = hash_coords_6(ix, iy, iz, iw, iu, iv, seed);
-	let vec = &GRADIENT_6D_LUT[hash as usize];
+fn grad_noise_6<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, ix: i32, iy: i32, iz: i32, iw: i32, iu: i32, iv: i32, seed: u32) -> f64 {
+	let vec = H::gradient_6(H::hash_6(ix, iy, iz, iw, iu, iv, seed));
 
 	let dx = x - ix as f64;
 	let dy = y - iy as f64;
@@ -1576,7 +1844,7 @@ fn interp_XYZWUV6(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, xs: f64, ys: f
 
 // The usable noise functions
 
-pub fn value_noise_2d(x: f64, y: f64, seed: u32, interp: InterpFunc) -> f64 {
+pub fn value_noise_2d_with<H: LatticeHash>(x: f64, y: f64, seed: u32, interp: InterpFunc) -> f64 {
 	let x0 = fast_floor(x);
 	let y0 = fast_floor(y);
 
@@ -1586,10 +1854,10 @@ pub fn value_noise_2d(x: f64, y: f64, seed: u32, interp: InterpFunc) -> f64 {
 	let xs = interp((x - x0 as f64));
 	let ys = interp((y - y0 as f64));
 
-	interp_XY2(x, y, xs, ys, x0, x1, y0, y1, seed, value_noise_2)
+	interp_XY2(x, y, xs, ys, x0, x1, y0, y1, seed, value_noise_2::<H>)
 }
 
-pub fn value_noise_3d(x: f64, y: f64, z: f64, seed: u32, interp: InterpFunc) -> f64 {
+pub fn value_noise_3d_with<H: LatticeHash>(x: f64, y: f64, z: f64, seed: u32, interp: InterpFunc) -> f64 {
 	let x0 = fast_floor(x);
 	let y0 = fast_floor(y);
 	let z0 = fast_floor(z);
@@ -1601,10 +1869,10 @@ pub fn value_noise_3d(x: f64, y: f64, z: f64, seed: u32, interp: InterpFunc) ->
 	let ys = interp((y - y0 as f64));
 	let zs = interp((z - z0 as f64));
 
-	return interp_XYZ3(x, y, z, xs, ys, zs, x0, x1, y0, y1, z0, z1, seed, value_noise_3)
+	return interp_XYZ3(x, y, z, xs, ys, zs, x0, x1, y0, y1, z0, z1, seed, value_noise_3::<H>)
 }
 
-pub fn value_noise_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, interp: InterpFunc) -> f64 {
+pub fn value_noise_4d_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, seed: u32, interp: InterpFunc) -> f64 {
 	let x0 = fast_floor(x);
 	let y0 = fast_floor(y);
 	let z0 = fast_floor(z);
@@ -1620,10 +1888,10 @@ pub fn value_noise_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, interp: InterpF
 	let zs = interp((z - z0 as f64));
 	let ws = interp((w - w0 as f64));
 
-	interp_XYZW4(x, y, z, w, xs, ys, zs, ws, x0, x1, y0, y1, z0, z1, w0, w1, seed, value_noise_4)
+	interp_XYZW4(x, y, z, w, xs, ys, zs, ws, x0, x1, y0, y1, z0, z1, w0, w1, seed, value_noise_4::<H>)
 }
 
-pub fn value_noise_6d(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32, interp: InterpFunc) -> f64 {
+pub fn value_noise_6d_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32, interp: InterpFunc) -> f64 {
 	let x0 = fast_floor(x);
 	let y0 = fast_floor(y);
 	let z0 = fast_floor(z);
@@ -1645,10 +1913,10 @@ pub fn value_noise_6d(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32,
 	let us = interp((u - u0 as f64));
 	let vs = interp((v - v0 as f64));
 
-	interp_XYZWUV6(x, y, z, w, u, v, xs, ys, zs, ws, us, vs, x0, x1, y0, y1, z0, z1, w0, w1, u0, u1, v0, v1, seed, value_noise_6)
+	interp_XYZWUV6(x, y, z, w, u, v, xs, ys, zs, ws, us, vs, x0, x1, y0, y1, z0, z1, w0, w1, u0, u1, v0, v1, seed, value_noise_6::<H>)
 }
 
-pub fn gradient_noise_2d(x: f64, y: f64, seed: u32, interp: InterpFunc) -> f64 {
+pub fn gradient_noise_2d_with<H: LatticeHash>(x: f64, y: f64, seed: u32, interp: InterpFunc) -> f64 {
 	let x0 = fast_floor(x);
 	let y0 = fast_floor(y);
 
@@ -1658,10 +1926,10 @@ pub fn gradient_noise_2d(x: f64, y: f64, seed: u32, interp: InterpFunc) -> f64 {
 	let xs = interp((x - x0 as f64));
 	let ys = interp((y - y0 as f64));
 
-	interp_XY2(x, y, xs, ys, x0, x1, y0, y1, seed, grad_noise_2)
+	interp_XY2(x, y, xs, ys, x0, x1, y0, y1, seed, grad_noise_2::<H>)
 }
 
-pub fn gradient_noise_3d(x: f64, y: f64, z: f64, seed: u32, interp: InterpFunc) -> f64 {
+pub fn gradient_noise_3d_with<H: LatticeHash>(x: f64, y: f64, z: f64, seed: u32, interp: InterpFunc) -> f64 {
 	let x0 = fast_floor(x);
 	let y0 = fast_floor(y);
 	let z0 = fast_floor(z);
@@ -1674,10 +1942,10 @@ pub fn gradient_noise_3d(x: f64, y: f64, z: f64, seed: u32, interp: InterpFunc)
 	let ys = interp((y - y0 as f64));
 	let zs = interp((z - z0 as f64));
 
-	interp_XYZ3(x, y, z, xs, ys, zs, x0, x1, y0, y1, z0, z1, seed, grad_noise_3)
+	interp_XYZ3(x, y, z, xs, ys, zs, x0, x1, y0, y1, z0, z1, seed, grad_noise_3::<H>)
 }
 
-pub fn gradient_noise_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, interp: InterpFunc) -> f64 {
+pub fn gradient_noise_4d_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, seed: u32, interp: InterpFunc) -> f64 {
 	let x0 = fast_floor(x);
 	let y0 = fast_floor(y);
 	let z0 = fast_floor(z);
@@ -1693,10 +1961,10 @@ pub fn gradient_noise_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, interp: Inte
 	let zs = interp((z - z0 as f64));
 	let ws = interp((w - w0 as f64));
 
-	interp_XYZW4(x, y, z, w, xs, ys, zs, ws, x0, x1, y0, y1, z0, z1, w0, w1, seed, grad_noise_4)
+	interp_XYZW4(x, y, z, w, xs, ys, zs, ws, x0, x1, y0, y1, z0, z1, w0, w1, seed, grad_noise_4::<H>)
 }
 
-pub fn gradient_noise_6d(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32, interp: InterpFunc) -> f64 {
+pub fn gradient_noise_6d_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32, interp: InterpFunc) -> f64 {
 	let x0 = fast_floor(x);
 	let y0 = fast_floor(y);
 	let z0 = fast_floor(z);
@@ -1718,43 +1986,39 @@ pub fn gradient_noise_6d(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u
 	let us = interp((u - u0 as f64));
 	let vs = interp((v - v0 as f64));
 
-	interp_XYZWUV6(x, y, z, w, u, v, xs, ys, zs, ws, us, vs, x0, x1, y0, y1, z0, z1, w0, w1, u0, u1, v0, v1, seed, grad_noise_6)
+	interp_XYZWUV6(x, y, z, w, u, v, xs, ys, zs, ws, us, vs, x0, x1, y0, y1, z0, z1, w0, w1, u0, u1, v0, v1, seed, grad_noise_6::<H>)
 }
 
-pub fn gradval_noise_2d(x: f64, y: f64, seed: u32, interp: InterpFunc) -> f64 {
-	return value_noise_2d(x, y, seed, interp) + gradient_noise_2d(x, y, seed, interp)
+pub fn gradval_noise_2d_with<H: LatticeHash>(x: f64, y: f64, seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_2d_with::<H>(x, y, seed, interp) + gradient_noise_2d_with::<H>(x, y, seed, interp)
 }
 
-pub fn gradval_noise_3d(x: f64, y: f64, z: f64, seed: u32, interp: InterpFunc) -> f64 {
-	return value_noise_3d(x, y, z, seed, interp) + gradient_noise_3d(x, y, z, seed, interp)
+pub fn gradval_noise_3d_with<H: LatticeHash>(x: f64, y: f64, z: f64, seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_3d_with::<H>(x, y, z, seed, interp) + gradient_noise_3d_with::<H>(x, y, z, seed, interp)
 }
 
-pub fn gradval_noise_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, interp: InterpFunc) -> f64 {
-	return value_noise_4d(x, y, z, w, seed, interp) + gradient_noise_4d(x, y, z, w, seed, interp)
+pub fn gradval_noise_4d_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_4d_with::<H>(x, y, z, w, seed, interp) + gradient_noise_4d_with::<H>(x, y, z, w, seed, interp)
 }
 
-pub fn gradval_noise_6d(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32, interp: InterpFunc) -> f64 {
-	return value_noise_6d(x, y, z, w, u, v, seed, interp) + gradient_noise_6d(x, y, z, w, u, v, seed, interp)
+pub fn gradval_noise_6d_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_6d_with::<H>(x, y, z, w, u, v, seed, interp) + gradient_noise_6d_with::<H>(x, y, z, w, u, v, seed, interp)
 }
 
-pub fn white_noise_2d(x: f64, y: f64, seed: u32, _: InterpFunc) -> f64 {
-	let hash = compute_hash_f64_f2(x, y, seed);
-	WHITENOISE_LUT[hash as usize]
+pub fn white_noise_2d_with<H: LatticeHash>(x: f64, y: f64, seed: u32, _: InterpFunc) -> f64 {
+	H::white(H::hash_f64_2(x, y, seed))
 }
 
-pub fn white_noise_3d(x: f64, y: f64, z: f64, seed: u32, _: InterpFunc) -> f64 {
-	let hash = compute_hash_f64_f3(x, y, z, seed);
-	WHITENOISE_LUT[hash as usize]
+pub fn white_noise_3d_with<H: LatticeHash>(x: f64, y: f64, z: f64, seed: u32, _: InterpFunc) -> f64 {
+	H::white(H::hash_f64_3(x, y, z, seed))
 }
 
-pub fn white_noise_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, _: InterpFunc) -> f64 {
-	let hash = compute_hash_f64_f4(x, y, z, w, seed);
-	WHITENOISE_LUT[hash as usize]
+pub fn white_noise_4d_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, seed: u32, _: InterpFunc) -> f64 {
+	H::white(H::hash_f64_4(x, y, z, w, seed))
 }
 
-pub fn white_noise_6d(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32, _: InterpFunc) -> f64 {
-	let hash = compute_hash_f64_f6(x, y, z, w, u, v, seed);
-	WHITENOISE_LUT[hash as usize]
+pub fn white_noise_6d_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32, _: InterpFunc) -> f64 {
+	H::white(H::hash_f64_6(x, y, z, w, u, v, seed))
 }
 
 fn add_dist(f: &mut [f64], disp: &mut [f64], testdist: f64, testdisp: f64) {
@@ -1791,14 +2055,14 @@ pub fn cellular_function_2d(x: f64, y: f64, seed: u32, f: &mut [f64], disp: &mut
 
 	for ycur in yint - 3..yint+4 {
 		for xcur in xint - 3..xint+4 {
-			let xpos = xcur as f64 + value_noise_2(x, y, xcur, ycur, seed);
-			let ypos = ycur as f64 + value_noise_2(x, y, xcur, ycur, seed+1);
+			let xpos = xcur as f64 + value_noise_2::<FnvHash>(x, y, xcur, ycur, seed);
+			let ypos = ycur as f64 + value_noise_2::<FnvHash>(x, y, xcur, ycur, seed+1);
 			let xdist = xpos - x;
 			let ydist = ypos - y;
 			let dist = xdist*xdist + ydist*ydist;
 			let xval = fast_floor(xpos);
 			let yval = fast_floor(ypos);
-			let dsp = value_noise_2(x, y, xval, yval, seed+3);
+			let dsp = value_noise_2::<FnvHash>(x, y, xval, yval, seed+3);
 			add_dist(f, disp, dist, dsp);
 		}
 	}
@@ -1817,9 +2081,9 @@ pub fn cellular_function_3d(x: f64, y: f64, z: f64, seed: u32, f: &mut [f64], di
 	for zcur in zint-2..zint+3 {
 		for ycur in yint-2..yint+3 {
 			for xcur in xint-2..xint+3 {
-				let xpos = xcur as f64 + value_noise_3(x, y, z, xcur, ycur, zcur, seed);
-				let ypos = ycur as f64 + value_noise_3(x, y, z, xcur, ycur, zcur, seed+1);
-				let zpos = zcur as f64 + value_noise_3(x, y, z, xcur, ycur, zcur, seed+2);
+				let xpos = xcur as f64 + value_noise_3::<FnvHash>(x, y, z, xcur, ycur, zcur, seed);
+				let ypos = ycur as f64 + value_noise_3::<FnvHash>(x, y, z, xcur, ycur, zcur, seed+1);
+				let zpos = zcur as f64 + value_noise_3::<FnvHash>(x, y, z, xcur, ycur, zcur, seed+2);
 				let xdist = xpos - x;
 				let ydist = ypos - y;
 				let zdist = zpos - z;
@@ -1827,7 +2091,7 @@ pub fn cellular_function_3d(x: f64, y: f64, z: f64, seed: u32, f: &mut [f64], di
 				let xval = fast_floor(xpos);
 				let yval = fast_floor(ypos);
 				let zval = fast_floor(zpos);
-				let dsp = value_noise_3(x, y, z, xval, yval, zval, seed+3);
+				let dsp = value_noise_3::<FnvHash>(x, y, z, xval, yval, zval, seed+3);
 				add_dist(f, disp, dist, dsp);
 			}
 		}
@@ -1849,10 +2113,10 @@ pub fn cellular_function_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, f: &mut [
 		for zcur in zint-2..zint+3 {
 			for ycur in yint-2..yint+3 {
 				for xcur in xint-2..xint+3 {
-					let xpos = xcur as f64 + value_noise_4(x, y, z, w, xcur, ycur, zcur, wcur, seed);
-					let ypos = ycur as f64 + value_noise_4(x, y, z, w, xcur, ycur, zcur, wcur, seed+1);
-					let zpos = zcur as f64 + value_noise_4(x, y, z, w, xcur, ycur, zcur, wcur, seed+2);
-					let wpos = wcur as f64 + value_noise_4(x, y, z, w, xcur, ycur, zcur, wcur, seed+3);
+					let xpos = xcur as f64 + value_noise_4::<FnvHash>(x, y, z, w, xcur, ycur, zcur, wcur, seed);
+					let ypos = ycur as f64 + value_noise_4::<FnvHash>(x, y, z, w, xcur, ycur, zcur, wcur, seed+1);
+					let zpos = zcur as f64 + value_noise_4::<FnvHash>(x, y, z, w, xcur, ycur, zcur, wcur, seed+2);
+					let wpos = wcur as f64 + value_noise_4::<FnvHash>(x, y, z, w, xcur, ycur, zcur, wcur, seed+3);
 					let xdist = xpos - x;
 					let ydist = ypos - y;
 					let zdist = zpos - z;
@@ -1862,7 +2126,7 @@ pub fn cellular_function_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, f: &mut [
 					let yval = fast_floor(ypos);
 					let zval = fast_floor(zpos);
 					let wval = fast_floor(wpos);
-					let dsp = value_noise_4(x, y, z, w, xval, yval, zval, wval, seed+3);
+					let dsp = value_noise_4::<FnvHash>(x, y, z, w, xval, yval, zval, wval, seed+3);
 					add_dist(f, disp, dist, dsp);
 				}
 			}
@@ -1890,12 +2154,12 @@ pub fn cellular_function_6d(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed
 				for zcur in zint-2..zint+3 {
 					for ycur in yint-2..yint+3 {
 						for xcur in xint-2..xint+3 {
-							let xpos = xcur as f64 + value_noise_6(x, y, z, w, u, v, xcur, ycur, zcur, wcur, ucur, vcur, seed);
-							let ypos = ycur as f64 + value_noise_6(x, y, z, w, u, v, xcur, ycur, zcur, wcur, ucur, vcur, seed+1);
-							let zpos = zcur as f64 + value_noise_6(x, y, z, w, u, v, xcur, ycur, zcur, wcur, ucur, vcur, seed+2);
-							let wpos = wcur as f64 + value_noise_6(x, y, z, w, u, v, xcur, ycur, zcur, wcur, ucur, vcur, seed+3);
-							let upos = ucur as f64 + value_noise_6(x, y, z, w, u, v, xcur, ycur, zcur, wcur, ucur, vcur, seed+4);
-							let vpos = vcur as f64 + value_noise_6(x, y, z, w, u, v, xcur, ycur, zcur, wcur, ucur, vcur, seed+5);
+							let xpos = xcur as f64 + value_noise_6::<FnvHash>(x, y, z, w, u, v, xcur, ycur, zcur, wcur, ucur, vcur, seed);
+							let ypos = ycur as f64 + value_noise_6::<FnvHash>(x, y, z, w, u, v, xcur, ycur, zcur, wcur, ucur, vcur, seed+1);
+							let zpos = zcur as f64 + value_noise_6::<FnvHash>(x, y, z, w, u, v, xcur, ycur, zcur, wcur, ucur, vcur, seed+2);
+							let wpos = wcur as f64 + value_noise_6::<FnvHash>(x, y, z, w, u, v, xcur, ycur, zcur, wcur, ucur, vcur, seed+3);
+							let upos = ucur as f64 + value_noise_6::<FnvHash>(x, y, z, w, u, v, xcur, ycur, zcur, wcur, ucur, vcur, seed+4);
+							let vpos = vcur as f64 + value_noise_6::<FnvHash>(x, y, z, w, u, v, xcur, ycur, zcur, wcur, ucur, vcur, seed+5);
 							let xdist = xpos - x;
 							let ydist = ypos - y;
 							let zdist = zpos - z;
@@ -1909,7 +2173,7 @@ pub fn cellular_function_6d(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed
 							let wval = fast_floor(wpos);
 							let uval = fast_floor(upos);
 							let vval = fast_floor(vpos);
-							let dsp = value_noise_6(x, y, z, w, u, v, xval, yval, zval, wval, uval, vval, seed+6);
+							let dsp = value_noise_6::<FnvHash>(x, y, z, w, u, v, xval, yval, zval, wval, uval, vval, seed+6);
 							add_dist(f, disp, dist, dsp);
 						}
 					}
@@ -1925,7 +2189,7 @@ const F3: f64 = 1.0 / 3.0;
 const G3: f64 = 1.0 / 6.0;
 
 #[allow(non_snake_case)]
-pub fn simplex_noise_2d(x: f64, y: f64, seed: u32, _: InterpFunc) -> f64 {
+pub fn simplex_noise_2d_with<H: LatticeHash>(x: f64, y: f64, seed: u32, _: InterpFunc) -> f64 {
 	let s = (x + y) * F2;
 	let i = fast_floor(x + s);
 	let j = fast_floor(y + s);
@@ -1952,14 +2216,14 @@ pub fn simplex_noise_2d(x: f64, y: f64, seed: u32, _: InterpFunc) -> f64 {
 	let y2 = y0 - 1.0 + 2.0*G2;
 
 	// Hash the triangle coordinates to index the gradient table
-	let h0 = hash_coords_2(i, j, seed);
-	let h1 = hash_coords_2(i+i1, j+j1, seed);
-	let h2 = hash_coords_2(i+1, j+1, seed);
+	let h0 = H::hash_2(i, j, seed);
+	let h1 = H::hash_2(i+i1, j+j1, seed);
+	let h2 = H::hash_2(i+1, j+1, seed);
 
-	// Now, index the tables
-	let g0 = &GRADIENT_2D_LUT[h0 as usize];
-	let g1 = &GRADIENT_2D_LUT[h1 as usize];
-	let g2 = &GRADIENT_2D_LUT[h2 as usize];
+	// Now, look up the gradients
+	let g0 = &H::gradient_2(h0);
+	let g1 = &H::gradient_2(h1);
+	let g2 = &H::gradient_2(h2);
 
 	let n0: f64;
 	let n1: f64;
@@ -1994,7 +2258,7 @@ pub fn simplex_noise_2d(x: f64, y: f64, seed: u32, _: InterpFunc) -> f64 {
 }
 
 #[allow(non_snake_case)]
-pub fn simplex_noise_3d(x: f64, y: f64, z: f64, seed: u32, _: InterpFunc) -> f64 {
+pub fn simplex_noise_3d_with<H: LatticeHash>(x: f64, y: f64, z: f64, seed: u32, _: InterpFunc) -> f64 {
 	let n0: f64;
 	let n1: f64;
 	let n2: f64;
@@ -2079,15 +2343,15 @@ pub fn simplex_noise_3d(x: f64, y: f64, z: f64, seed: u32, _: InterpFunc) -> f64
 	let y3 = y0 - 1.0 + 3.0*G3;
 	let z3 = z0 - 1.0 + 3.0*G3;
 
-	let h0 = hash_coords_3(i, j, k, seed);
-	let h1 = hash_coords_3(i+i1, j+j1, k+k1, seed);
-	let h2 = hash_coords_3(i+i2, j+j2, k+k2, seed);
-	let h3 = hash_coords_3(i+1, j+1, k+1, seed);
+	let h0 = H::hash_3(i, j, k, seed);
+	let h1 = H::hash_3(i+i1, j+j1, k+k1, seed);
+	let h2 = H::hash_3(i+i2, j+j2, k+k2, seed);
+	let h3 = H::hash_3(i+1, j+1, k+1, seed);
 
-	let g0 = GRADIENT_3D_LUT[h0 as usize];
-	let g1 = GRADIENT_3D_LUT[h1 as usize];
-	let g2 = GRADIENT_3D_LUT[h2 as usize];
-	let g3 = GRADIENT_3D_LUT[h3 as usize];
+	let g0 = H::gradient_3(h0);
+	let g1 = H::gradient_3(h1);
+	let g2 = H::gradient_3(h2);
+	let g3 = H::gradient_3(h3);
 
 	let mut t0 = 0.6 - x0*x0 - y0*y0 - z0*z0;
 	if t0 < 0.0 {
@@ -2125,7 +2389,7 @@ pub fn simplex_noise_3d(x: f64, y: f64, z: f64, seed: u32, _: InterpFunc) -> f64
 }
 
 #[allow(non_snake_case)]
-pub fn simplex_noise_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, _: InterpFunc) -> f64 {
+pub fn simplex_noise_4d_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, seed: u32, _: InterpFunc) -> f64 {
 	let simplex: [[i32; 4];64] = [
 		[0, 1, 2, 3], [0, 1, 3, 2], [0, 0, 0, 0], [0, 2, 3, 1], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [1, 2, 3, 0],
 		[0, 2, 1, 3], [0, 0, 0, 0], [0, 3, 1, 2], [0, 3, 2, 1], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [1, 3, 2, 0],
@@ -2214,17 +2478,17 @@ pub fn simplex_noise_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, _: InterpFunc
 	let z4 = z0 - 1.0 + 4.0*G4;
 	let w4 = w0 - 1.0 + 4.0*G4;
 	// Work out the hashed gradient indices of the five simplex corners
-	let h0 = hash_coords_4(i, j, k, l, seed);
-	let h1 = hash_coords_4(i+i1, j+j1, k+k1, l+l1, seed);
-	let h2 = hash_coords_4(i+i2, j+j2, k+k2, l+l2, seed);
-	let h3 = hash_coords_4(i+i3, j+j3, k+k3, l+l3, seed);
-	let h4 = hash_coords_4(i+1, j+1, k+1, l+1, seed);
-
-	let g0 = &GRADIENT_4D_LUT[h0 as usize];
-	let g1 = &GRADIENT_4D_LUT[h1 as usize];
-	let g2 = &GRADIENT_4D_LUT[h2 as usize];
-	let g3 = &GRADIENT_4D_LUT[h3 as usize];
-	let g4 = &GRADIENT_4D_LUT[h4 as usize];
+	let h0 = H::hash_4(i, j, k, l, seed);
+	let h1 = H::hash_4(i+i1, j+j1, k+k1, l+l1, seed);
+	let h2 = H::hash_4(i+i2, j+j2, k+k2, l+l2, seed);
+	let h3 = H::hash_4(i+i3, j+j3, k+k3, l+l3, seed);
+	let h4 = H::hash_4(i+1, j+1, k+1, l+1, seed);
+
+	let g0 = &H::gradient_4(h0);
+	let g1 = &H::gradient_4(h1);
+	let g2 = &H::gradient_4(h2);
+	let g3 = &H::gradient_4(h3);
+	let g4 = &H::gradient_4(h4);
 
 	// Calculate the contribution from the five corners
 	let mut t0 = 0.6 - x0*x0 - y0*y0 - z0*z0 - w0*w0;
@@ -2443,7 +2707,7 @@ pub fn new_simplex_noise_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, _: Interp
 }
 
 #[allow(non_snake_case)]
-pub fn simplex_noise_6d(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32, _: InterpFunc) -> f64 {
+pub fn simplex_noise_6d_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32, _: InterpFunc) -> f64 {
 	// Skew
 	//self.f = ((self.d + 1) ** .5 - 1) / self.d
 
@@ -2504,8 +2768,8 @@ pub fn simplex_noise_6d(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u3
 		}
 
 		if t > 0.0 {
-			let h = hash_coords_6(intLoc[0], intLoc[1], intLoc[2], intLoc[3], intLoc[4], intLoc[5], seed);
-			let vec = &GRADIENT_6D_LUT[h as usize];
+			let h = H::hash_6(intLoc[0], intLoc[1], intLoc[2], intLoc[3], intLoc[4], intLoc[5], seed);
+			let vec = &H::gradient_6(h);
 			let mut gr = 0.0;
 			for d in 0..6 {
 				gr += vec[d] * u[d];
@@ -2518,3 +2782,1787 @@ pub fn simplex_noise_6d(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u3
 	n *= valueScaler;
 	n
 }
+
+// The classic ANL noise functions, hashed with FnvHash.
+
+pub fn value_noise_1d(x: f64, seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_1d_with::<FnvHash>(x, seed, interp)
+}
+
+pub fn value_noise_2d(x: f64, y: f64, seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_2d_with::<FnvHash>(x, y, seed, interp)
+}
+
+pub fn value_noise_3d(x: f64, y: f64, z: f64, seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_3d_with::<FnvHash>(x, y, z, seed, interp)
+}
+
+pub fn value_noise_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_4d_with::<FnvHash>(x, y, z, w, seed, interp)
+}
+
+pub fn value_noise_6d(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_6d_with::<FnvHash>(x, y, z, w, u, v, seed, interp)
+}
+
+pub fn gradient_noise_1d(x: f64, seed: u32, interp: InterpFunc) -> f64 {
+	gradient_noise_1d_with::<FnvHash>(x, seed, interp)
+}
+
+pub fn gradient_noise_2d(x: f64, y: f64, seed: u32, interp: InterpFunc) -> f64 {
+	gradient_noise_2d_with::<FnvHash>(x, y, seed, interp)
+}
+
+pub fn gradient_noise_3d(x: f64, y: f64, z: f64, seed: u32, interp: InterpFunc) -> f64 {
+	gradient_noise_3d_with::<FnvHash>(x, y, z, seed, interp)
+}
+
+pub fn gradient_noise_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, interp: InterpFunc) -> f64 {
+	gradient_noise_4d_with::<FnvHash>(x, y, z, w, seed, interp)
+}
+
+pub fn gradient_noise_6d(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32, interp: InterpFunc) -> f64 {
+	gradient_noise_6d_with::<FnvHash>(x, y, z, w, u, v, seed, interp)
+}
+
+pub fn gradval_noise_1d(x: f64, seed: u32, interp: InterpFunc) -> f64 {
+	gradval_noise_1d_with::<FnvHash>(x, seed, interp)
+}
+
+pub fn gradval_noise_2d(x: f64, y: f64, seed: u32, interp: InterpFunc) -> f64 {
+	gradval_noise_2d_with::<FnvHash>(x, y, seed, interp)
+}
+
+pub fn gradval_noise_3d(x: f64, y: f64, z: f64, seed: u32, interp: InterpFunc) -> f64 {
+	gradval_noise_3d_with::<FnvHash>(x, y, z, seed, interp)
+}
+
+pub fn gradval_noise_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, interp: InterpFunc) -> f64 {
+	gradval_noise_4d_with::<FnvHash>(x, y, z, w, seed, interp)
+}
+
+pub fn gradval_noise_6d(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32, interp: InterpFunc) -> f64 {
+	gradval_noise_6d_with::<FnvHash>(x, y, z, w, u, v, seed, interp)
+}
+
+pub fn white_noise_1d(x: f64, seed: u32, interp: InterpFunc) -> f64 {
+	white_noise_1d_with::<FnvHash>(x, seed, interp)
+}
+
+pub fn white_noise_2d(x: f64, y: f64, seed: u32, interp: InterpFunc) -> f64 {
+	white_noise_2d_with::<FnvHash>(x, y, seed, interp)
+}
+
+pub fn white_noise_3d(x: f64, y: f64, z: f64, seed: u32, interp: InterpFunc) -> f64 {
+	white_noise_3d_with::<FnvHash>(x, y, z, seed, interp)
+}
+
+pub fn white_noise_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, interp: InterpFunc) -> f64 {
+	white_noise_4d_with::<FnvHash>(x, y, z, w, seed, interp)
+}
+
+pub fn white_noise_6d(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32, interp: InterpFunc) -> f64 {
+	white_noise_6d_with::<FnvHash>(x, y, z, w, u, v, seed, interp)
+}
+
+pub fn simplex_noise_1d(x: f64, seed: u32, interp: InterpFunc) -> f64 {
+	simplex_noise_1d_with::<FnvHash>(x, seed, interp)
+}
+
+pub fn simplex_noise_2d(x: f64, y: f64, seed: u32, interp: InterpFunc) -> f64 {
+	simplex_noise_2d_with::<FnvHash>(x, y, seed, interp)
+}
+
+pub fn simplex_noise_3d(x: f64, y: f64, z: f64, seed: u32, interp: InterpFunc) -> f64 {
+	simplex_noise_3d_with::<FnvHash>(x, y, z, seed, interp)
+}
+
+pub fn simplex_noise_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, interp: InterpFunc) -> f64 {
+	simplex_noise_4d_with::<FnvHash>(x, y, z, w, seed, interp)
+}
+
+pub fn simplex_noise_6d(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32, interp: InterpFunc) -> f64 {
+	simplex_noise_6d_with::<FnvHash>(x, y, z, w, u, v, seed, interp)
+}
+
+pub fn value_noise_nd(p: &[f64], seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_nd_with::<FnvHash>(p, seed, interp)
+}
+
+/// Gradient noise at a point with any number of coordinates, up to [`MAX_DIMENSIONS`](constant.MAX_DIMENSIONS.html).
+/// For 2, 3, 4 and 6 coordinates it gives the same values as the fixed-dimension functions.
+///
+/// ```
+/// use anl::noise_gen::*;
+///
+/// let (x, y, z, w, u, v) = (0.3, -1.7, 2.25, 0.8, -0.4, 5.1);
+/// let q = quintic_interp;
+/// assert_eq!(gradient_noise_nd(&[x, y], 7, q), gradient_noise_2d(x, y, 7, q));
+/// assert_eq!(gradient_noise_nd(&[x, y, z], 7, q), gradient_noise_3d(x, y, z, 7, q));
+/// assert_eq!(gradient_noise_nd(&[x, y, z, w], 7, q), gradient_noise_4d(x, y, z, w, 7, q));
+/// assert_eq!(gradient_noise_nd(&[x, y, z, w, u, v], 7, q), gradient_noise_6d(x, y, z, w, u, v, 7, q));
+///
+/// // So get_nd() on a basis function matches the fixed-dimension methods too.
+/// use anl::{ImplicitBasisFunction, ImplicitModule, BasisType, InterpType};
+/// let mut basis = ImplicitBasisFunction::with_types(BasisType::Gradient, InterpType::Quintic);
+/// basis.set_seed(7);
+/// assert_eq!(basis.get_nd(&[x, y]), basis.get_2d(x, y));
+/// assert_eq!(basis.get_nd(&[x, y, z]), basis.get_3d(x, y, z));
+/// assert_eq!(basis.get_nd(&[x, y, z, w]), basis.get_4d(x, y, z, w));
+/// assert_eq!(basis.get_nd(&[x, y, z, w, u, v]), basis.get_6d(x, y, z, w, u, v));
+/// ```
+pub fn gradient_noise_nd(p: &[f64], seed: u32, interp: InterpFunc) -> f64 {
+	gradient_noise_nd_with::<FnvHash>(p, seed, interp)
+}
+
+pub fn gradval_noise_nd(p: &[f64], seed: u32, interp: InterpFunc) -> f64 {
+	gradval_noise_nd_with::<FnvHash>(p, seed, interp)
+}
+
+pub fn white_noise_nd(p: &[f64], seed: u32, interp: InterpFunc) -> f64 {
+	white_noise_nd_with::<FnvHash>(p, seed, interp)
+}
+
+// OpenSimplex2 noise, after the reference implementation by K.jpg (public domain).
+// OpenSimplex2 ("fast") evaluates the closest points of overlapping simplex lattices with a small kernel radius;
+// OpenSimplex2S ("smooth") uses a larger kernel radius and sums more lattice points, which is slower but smoother.
+// Lattice points are hashed with prime multiplication rather than with the lattice hash backends above.
+
+const OS2_PRIME_X: i64 = 0x5205402B9270C86F;
+const OS2_PRIME_Y: i64 = 0x598CD327003817B5;
+const OS2_PRIME_Z: i64 = 0x5BCC226E9FA0BACB;
+const OS2_PRIME_W: i64 = 0x56CC5227E58F554B;
+const OS2_HASH_MULTIPLIER: i64 = 0x53A3F72DEEC546F5;
+const OS2_SEED_FLIP_3D: i64 = -0x52D547B2E96ED629;
+const OS2_SEED_OFFSET_4D: i64 = 0xE83DC3E0DA7164D;
+
+const OS2_SKEW_2D: f64 = 0.366025403784439;
+const OS2_UNSKEW_2D: f64 = -0.21132486540518713;
+const OS2_ROTATE_3D: f64 = 2.0 / 3.0;
+const OS2_SKEW_4D: f64 = -0.138196601125011;
+const OS2_UNSKEW_4D: f64 = 0.309016994374947;
+const OS2_LATTICE_STEP_4D: f64 = 0.2;
+
+const OS2_RSQUARED_2D: f64 = 0.5;
+const OS2_RSQUARED_3D: f64 = 0.6;
+const OS2_RSQUARED_4D: f64 = 0.6;
+const OS2S_RSQUARED_2D: f64 = 2.0 / 3.0;
+const OS2S_RSQUARED_3D: f64 = 0.75;
+const OS2S_RSQUARED_4D: f64 = 0.8;
+
+const OS2_NORMALIZER_2D: f64 = 0.01001634121365712;
+const OS2_NORMALIZER_3D: f64 = 0.07969837668935331;
+const OS2_NORMALIZER_4D: f64 = 0.0220065933241897;
+const OS2S_NORMALIZER_2D: f64 = 0.05481866495625118;
+const OS2S_NORMALIZER_3D: f64 = 0.2781926117527186;
+const OS2S_NORMALIZER_4D: f64 = 0.11127401889945551;
+
+lazy_static! {
+	// 24 directions evenly spaced around the circle, repeated to fill the table.
+	static ref OS2_GRADIENTS_2D: [[f64; 2]; 128] = {
+		let mut g = [[0.0; 2]; 128];
+		for (i, v) in g.iter_mut().enumerate() {
+			let angle = ((i % 24) as f64 * 15.0 + 7.5).to_radians();
+			*v = [angle.cos(), angle.sin()];
+		}
+		g
+	};
+
+	// Permutations of (a, a, 1) and (b, c, 0) with all sign combinations, all of equal length.
+	static ref OS2_GRADIENTS_3D: [[f64; 3]; 256] = {
+		let a: f64 = 2.22474487139;
+		let b: f64 = 3.0862664687972017;
+		let c: f64 = 1.1721513422464978;
+		let mut base = Vec::with_capacity(48);
+		for axis in 0..3 {
+			for sign in 0..8 {
+				let sx = if sign & 1 == 0 { 1.0 } else { -1.0 };
+				let sy = if sign & 2 == 0 { 1.0 } else { -1.0 };
+				let sz = if sign & 4 == 0 { 1.0 } else { -1.0 };
+				let mut p = [a * sx, a * sy, 1.0 * sz];
+				p.swap(2, axis);
+				base.push(p);
+				let mut q = [b * sx, c * sy, 0.0];
+				if sign & 4 != 0 {
+					q.swap(0, 1);
+				}
+				q.swap(2, axis);
+				base.push(q);
+			}
+		}
+		let mut g = [[0.0; 3]; 256];
+		for (i, v) in g.iter_mut().enumerate() {
+			*v = base[i % base.len()];
+		}
+		g
+	};
+
+	// The 160 gradients of the reference implementation. Lifted onto the hyperplane x + y + z + w + v = 0 of 5D space
+	// they are the distinct permutations of two vectors and their negatives, which is how they are generated here from
+	// one reference gradient of each kind. All are unit length.
+	static ref OS2_GRADIENTS_4D: [[f64; 4]; 512] = {
+		let rows = [
+			[-0.6740059517812944, -0.3239847771997537, -0.3239847771997537, 0.5794684678643381],
+			[-0.8828161875373585, 0.08164729285680945, 0.08164729285680945, 0.4553054119602712],
+		];
+		let lift_scale = -(1.0 + 4.0 * OS2_SKEW_4D);
+		let mut base: Vec<[f64; 4]> = Vec::with_capacity(160);
+		for r in rows.iter() {
+			let s = (r[0] + r[1]) + (r[2] + r[3]);
+			let lifted = [r[0] + OS2_SKEW_4D * s, r[1] + OS2_SKEW_4D * s, r[2] + OS2_SKEW_4D * s, r[3] + OS2_SKEW_4D * s, lift_scale * s];
+			for p in 0..3125 {
+				let perm = [p % 5, p / 5 % 5, p / 25 % 5, p / 125 % 5, p / 625];
+				if (0..5).any(|i| (0..i).any(|j| perm[i] == perm[j])) {
+					continue;
+				}
+				for &sign in [1.0, -1.0].iter() {
+					let l: Vec<f64> = perm.iter().map(|&i| sign * lifted[i]).collect();
+					let g = [
+						l[0] - OS2_UNSKEW_4D * l[4],
+						l[1] - OS2_UNSKEW_4D * l[4],
+						l[2] - OS2_UNSKEW_4D * l[4],
+						l[3] - OS2_UNSKEW_4D * l[4],
+					];
+					if !base.iter().any(|b| (0..4).all(|i| (b[i] - g[i]).abs() < 1.0e-9)) {
+						base.push(g);
+					}
+				}
+			}
+		}
+		let mut g = [[0.0; 4]; 512];
+		for (i, v) in g.iter_mut().enumerate() {
+			*v = base[i % base.len()];
+		}
+		g
+	};
+}
+
+#[inline(always)]
+fn os2_floor(x: f64) -> i64 {
+	let xi = x as i64;
+	if x < xi as f64 { xi - 1 } else { xi }
+}
+
+#[inline(always)]
+fn os2_round(x: f64) -> i64 {
+	if x < 0.0 { (x - 0.5) as i64 } else { (x + 0.5) as i64 }
+}
+
+#[inline(always)]
+fn os2_grad_2(seed: i64, xsvp: i64, ysvp: i64, dx: f64, dy: f64) -> f64 {
+	let mut hash = (seed ^ xsvp ^ ysvp).wrapping_mul(OS2_HASH_MULTIPLIER);
+	hash ^= hash >> (64 - 7 + 1);
+	let g = &OS2_GRADIENTS_2D[((hash >> 1) & 127) as usize];
+	g[0] * dx + g[1] * dy
+}
+
+#[inline(always)]
+fn os2_grad_3(seed: i64, xrvp: i64, yrvp: i64, zrvp: i64, dx: f64, dy: f64, dz: f64) -> f64 {
+	let mut hash = ((seed ^ xrvp) ^ (yrvp ^ zrvp)).wrapping_mul(OS2_HASH_MULTIPLIER);
+	hash ^= hash >> (64 - 8 + 2);
+	let g = &OS2_GRADIENTS_3D[((hash >> 2) & 255) as usize];
+	g[0] * dx + g[1] * dy + g[2] * dz
+}
+
+#[inline(always)]
+fn os2_grad_4(seed: i64, xsvp: i64, ysvp: i64, zsvp: i64, wsvp: i64, dx: f64, dy: f64, dz: f64, dw: f64) -> f64 {
+	let mut hash = (seed ^ (xsvp ^ ysvp) ^ (zsvp ^ wsvp)).wrapping_mul(OS2_HASH_MULTIPLIER);
+	hash ^= hash >> (64 - 9 + 2);
+	let g = &OS2_GRADIENTS_4D[((hash >> 2) & 511) as usize];
+	g[0] * dx + g[1] * dy + g[2] * dz + g[3] * dw
+}
+
+#[inline(always)]
+fn os2_falloff(a: f64) -> f64 {
+	(a * a) * (a * a)
+}
+
+pub fn opensimplex2_noise_2d(x: f64, y: f64, seed: u32, _: InterpFunc) -> f64 {
+	let seed = seed as i64;
+
+	// Skew onto the A2 lattice
+	let s = OS2_SKEW_2D * (x + y);
+	let xs = x + s;
+	let ys = y + s;
+
+	let xsb = os2_floor(xs);
+	let ysb = os2_floor(ys);
+	let xi = xs - xsb as f64;
+	let yi = ys - ysb as f64;
+
+	let xsbp = xsb.wrapping_mul(OS2_PRIME_X);
+	let ysbp = ysb.wrapping_mul(OS2_PRIME_Y);
+
+	// Unskew
+	let t = (xi + yi) * OS2_UNSKEW_2D;
+	let dx0 = xi + t;
+	let dy0 = yi + t;
+
+	let mut value = 0.0;
+
+	let a0 = OS2_RSQUARED_2D - dx0 * dx0 - dy0 * dy0;
+	if a0 > 0.0 {
+		value += os2_falloff(a0) * os2_grad_2(seed, xsbp, ysbp, dx0, dy0);
+	}
+
+	let dx1 = dx0 - (1.0 + 2.0 * OS2_UNSKEW_2D);
+	let dy1 = dy0 - (1.0 + 2.0 * OS2_UNSKEW_2D);
+	let a1 = OS2_RSQUARED_2D - dx1 * dx1 - dy1 * dy1;
+	if a1 > 0.0 {
+		value += os2_falloff(a1) * os2_grad_2(seed, xsbp.wrapping_add(OS2_PRIME_X), ysbp.wrapping_add(OS2_PRIME_Y), dx1, dy1);
+	}
+
+	if dy0 > dx0 {
+		let dx2 = dx0 - OS2_UNSKEW_2D;
+		let dy2 = dy0 - (OS2_UNSKEW_2D + 1.0);
+		let a2 = OS2_RSQUARED_2D - dx2 * dx2 - dy2 * dy2;
+		if a2 > 0.0 {
+			value += os2_falloff(a2) * os2_grad_2(seed, xsbp, ysbp.wrapping_add(OS2_PRIME_Y), dx2, dy2);
+		}
+	} else {
+		let dx2 = dx0 - (OS2_UNSKEW_2D + 1.0);
+		let dy2 = dy0 - OS2_UNSKEW_2D;
+		let a2 = OS2_RSQUARED_2D - dx2 * dx2 - dy2 * dy2;
+		if a2 > 0.0 {
+			value += os2_falloff(a2) * os2_grad_2(seed, xsbp.wrapping_add(OS2_PRIME_X), ysbp, dx2, dy2);
+		}
+	}
+
+	value / OS2_NORMALIZER_2D
+}
+
+pub fn opensimplex2_noise_3d(x: f64, y: f64, z: f64, seed: u32, _: InterpFunc) -> f64 {
+	let mut seed = seed as i64;
+
+	// Rotate the cubic lattices so the main diagonal points along the z axis
+	let r = OS2_ROTATE_3D * (x + y + z);
+	let xr = r - x;
+	let yr = r - y;
+	let zr = r - z;
+
+	let xrb = os2_round(xr);
+	let yrb = os2_round(yr);
+	let zrb = os2_round(zr);
+	let mut xri = xr - xrb as f64;
+	let mut yri = yr - yrb as f64;
+	let mut zri = zr - zrb as f64;
+
+	// -1 if positive, 1 if negative
+	let mut x_nsign = ((-1.0 - xri) as i64) | 1;
+	let mut y_nsign = ((-1.0 - yri) as i64) | 1;
+	let mut z_nsign = ((-1.0 - zri) as i64) | 1;
+
+	let mut ax0 = x_nsign as f64 * -xri;
+	let mut ay0 = y_nsign as f64 * -yri;
+	let mut az0 = z_nsign as f64 * -zri;
+
+	let mut xrbp = xrb.wrapping_mul(OS2_PRIME_X);
+	let mut yrbp = yrb.wrapping_mul(OS2_PRIME_Y);
+	let mut zrbp = zrb.wrapping_mul(OS2_PRIME_Z);
+
+	// One closest and one second-closest point on each of the two lattice copies
+	let mut value = 0.0;
+	let mut a = (OS2_RSQUARED_3D - xri * xri) - (yri * yri + zri * zri);
+	for l in 0..2 {
+		if a > 0.0 {
+			value += os2_falloff(a) * os2_grad_3(seed, xrbp, yrbp, zrbp, xri, yri, zri);
+		}
+
+		if ax0 >= ay0 && ax0 >= az0 {
+			let b = a + ax0 + ax0;
+			if b > 1.0 {
+				let b = b - 1.0;
+				value += os2_falloff(b) * os2_grad_3(seed, xrbp.wrapping_sub(x_nsign.wrapping_mul(OS2_PRIME_X)), yrbp, zrbp, xri + x_nsign as f64, yri, zri);
+			}
+		} else if ay0 > ax0 && ay0 >= az0 {
+			let b = a + ay0 + ay0;
+			if b > 1.0 {
+				let b = b - 1.0;
+				value += os2_falloff(b) * os2_grad_3(seed, xrbp, yrbp.wrapping_sub(y_nsign.wrapping_mul(OS2_PRIME_Y)), zrbp, xri, yri + y_nsign as f64, zri);
+			}
+		} else {
+			let b = a + az0 + az0;
+			if b > 1.0 {
+				let b = b - 1.0;
+				value += os2_falloff(b) * os2_grad_3(seed, xrbp, yrbp, zrbp.wrapping_sub(z_nsign.wrapping_mul(OS2_PRIME_Z)), xri, yri, zri + z_nsign as f64);
+			}
+		}
+
+		if l == 1 {
+			break;
+		}
+
+		// Move to the other lattice copy, offset by half a cell
+		ax0 = 0.5 - ax0;
+		ay0 = 0.5 - ay0;
+		az0 = 0.5 - az0;
+
+		xri = x_nsign as f64 * ax0;
+		yri = y_nsign as f64 * ay0;
+		zri = z_nsign as f64 * az0;
+
+		a += (0.75 - ax0) - (ay0 + az0);
+
+		xrbp = xrbp.wrapping_add((x_nsign >> 1) & OS2_PRIME_X);
+		yrbp = yrbp.wrapping_add((y_nsign >> 1) & OS2_PRIME_Y);
+		zrbp = zrbp.wrapping_add((z_nsign >> 1) & OS2_PRIME_Z);
+
+		x_nsign = -x_nsign;
+		y_nsign = -y_nsign;
+		z_nsign = -z_nsign;
+
+		seed ^= OS2_SEED_FLIP_3D;
+	}
+
+	value / OS2_NORMALIZER_3D
+}
+
+pub fn opensimplex2_noise_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, _: InterpFunc) -> f64 {
+	let mut seed = seed as i64;
+
+	// Skew onto the A4 lattice
+	let s = OS2_SKEW_4D * (x + y + z + w);
+	let xs = x + s;
+	let ys = y + s;
+	let zs = z + s;
+	let ws = w + s;
+
+	let xsb = os2_floor(xs);
+	let ysb = os2_floor(ys);
+	let zsb = os2_floor(zs);
+	let wsb = os2_floor(ws);
+	let mut xsi = xs - xsb as f64;
+	let mut ysi = ys - ysb as f64;
+	let mut zsi = zs - zsb as f64;
+	let mut wsi = ws - wsb as f64;
+
+	// Pick the lattice copy that is certain to have a contributing point in its base simplex
+	let si_sum = (xsi + ysi) + (zsi + wsi);
+	let starting_lattice = (si_sum * 1.25) as i64;
+
+	seed = seed.wrapping_add(starting_lattice.wrapping_mul(OS2_SEED_OFFSET_4D));
+
+	let starting_lattice_offset = starting_lattice as f64 * -OS2_LATTICE_STEP_4D;
+	xsi += starting_lattice_offset;
+	ysi += starting_lattice_offset;
+	zsi += starting_lattice_offset;
+	wsi += starting_lattice_offset;
+
+	let mut ssi = (si_sum + starting_lattice_offset * 4.0) * OS2_UNSKEW_4D;
+
+	let mut xsvp = xsb.wrapping_mul(OS2_PRIME_X);
+	let mut ysvp = ysb.wrapping_mul(OS2_PRIME_Y);
+	let mut zsvp = zsb.wrapping_mul(OS2_PRIME_Z);
+	let mut wsvp = wsb.wrapping_mul(OS2_PRIME_W);
+
+	// One point from each of five copies of the A4 lattice
+	let mut value = 0.0;
+	for i in 0..5 {
+		let score0 = 1.0 + ssi * (-1.0 / OS2_UNSKEW_4D);
+		if xsi >= ysi && xsi >= zsi && xsi >= wsi && xsi >= score0 {
+			xsvp = xsvp.wrapping_add(OS2_PRIME_X);
+			xsi -= 1.0;
+			ssi -= OS2_UNSKEW_4D;
+		} else if ysi > xsi && ysi >= zsi && ysi >= wsi && ysi >= score0 {
+			ysvp = ysvp.wrapping_add(OS2_PRIME_Y);
+			ysi -= 1.0;
+			ssi -= OS2_UNSKEW_4D;
+		} else if zsi > xsi && zsi > ysi && zsi >= wsi && zsi >= score0 {
+			zsvp = zsvp.wrapping_add(OS2_PRIME_Z);
+			zsi -= 1.0;
+			ssi -= OS2_UNSKEW_4D;
+		} else if wsi > xsi && wsi > ysi && wsi > zsi && wsi >= score0 {
+			wsvp = wsvp.wrapping_add(OS2_PRIME_W);
+			wsi -= 1.0;
+			ssi -= OS2_UNSKEW_4D;
+		}
+
+		let dx = xsi + ssi;
+		let dy = ysi + ssi;
+		let dz = zsi + ssi;
+		let dw = wsi + ssi;
+		let a = (dx * dx + dy * dy) + (dz * dz + dw * dw);
+		if a < OS2_RSQUARED_4D {
+			value += os2_falloff(a - OS2_RSQUARED_4D) * os2_grad_4(seed, xsvp, ysvp, zsvp, wsvp, dx, dy, dz, dw);
+		}
+
+		if i == 4 {
+			break;
+		}
+
+		// Next lattice copy, shifted down by (-0.2, -0.2, -0.2, -0.2)
+		xsi += OS2_LATTICE_STEP_4D;
+		ysi += OS2_LATTICE_STEP_4D;
+		zsi += OS2_LATTICE_STEP_4D;
+		wsi += OS2_LATTICE_STEP_4D;
+		ssi += OS2_LATTICE_STEP_4D * 4.0 * OS2_UNSKEW_4D;
+		seed = seed.wrapping_sub(OS2_SEED_OFFSET_4D);
+
+		if i == starting_lattice {
+			xsvp = xsvp.wrapping_sub(OS2_PRIME_X);
+			ysvp = ysvp.wrapping_sub(OS2_PRIME_Y);
+			zsvp = zsvp.wrapping_sub(OS2_PRIME_Z);
+			wsvp = wsvp.wrapping_sub(OS2_PRIME_W);
+			seed = seed.wrapping_add(OS2_SEED_OFFSET_4D.wrapping_mul(5));
+		}
+	}
+
+	value / OS2_NORMALIZER_4D
+}
+
+pub fn opensimplex2s_noise_2d(x: f64, y: f64, seed: u32, _: InterpFunc) -> f64 {
+	let seed = seed as i64;
+
+	let s = OS2_SKEW_2D * (x + y);
+	let xs = x + s;
+	let ys = y + s;
+
+	let xsb = os2_floor(xs);
+	let ysb = os2_floor(ys);
+	let xi = xs - xsb as f64;
+	let yi = ys - ysb as f64;
+
+	let xsbp = xsb.wrapping_mul(OS2_PRIME_X);
+	let ysbp = ysb.wrapping_mul(OS2_PRIME_Y);
+
+	let t = (xi + yi) * OS2_UNSKEW_2D;
+	let dx0 = xi + t;
+	let dy0 = yi + t;
+
+	// The two vertices of the base rhombus' main diagonal always contribute
+	let a0 = OS2S_RSQUARED_2D - dx0 * dx0 - dy0 * dy0;
+	let mut value = os2_falloff(a0) * os2_grad_2(seed, xsbp, ysbp, dx0, dy0);
+
+	let dx1 = dx0 - (1.0 + 2.0 * OS2_UNSKEW_2D);
+	let dy1 = dy0 - (1.0 + 2.0 * OS2_UNSKEW_2D);
+	let a1 = OS2S_RSQUARED_2D - dx1 * dx1 - dy1 * dy1;
+	value += os2_falloff(a1) * os2_grad_2(seed, xsbp.wrapping_add(OS2_PRIME_X), ysbp.wrapping_add(OS2_PRIME_Y), dx1, dy1);
+
+	// Two more out of the surrounding vertices, depending on the position inside the rhombus
+	let xmyi = xi - yi;
+	let mut contrib = |dx: f64, dy: f64, xsvp: i64, ysvp: i64| {
+		let a = OS2S_RSQUARED_2D - dx * dx - dy * dy;
+		if a > 0.0 {
+			value += os2_falloff(a) * os2_grad_2(seed, xsvp, ysvp, dx, dy);
+		}
+	};
+	if t < OS2_UNSKEW_2D {
+		if xi + xmyi > 1.0 {
+			contrib(dx0 - (3.0 * OS2_UNSKEW_2D + 2.0), dy0 - (3.0 * OS2_UNSKEW_2D + 1.0), xsbp.wrapping_add(OS2_PRIME_X << 1), ysbp.wrapping_add(OS2_PRIME_Y));
+		} else {
+			contrib(dx0 - OS2_UNSKEW_2D, dy0 - (OS2_UNSKEW_2D + 1.0), xsbp, ysbp.wrapping_add(OS2_PRIME_Y));
+		}
+
+		if yi - xmyi > 1.0 {
+			contrib(dx0 - (3.0 * OS2_UNSKEW_2D + 1.0), dy0 - (3.0 * OS2_UNSKEW_2D + 2.0), xsbp.wrapping_add(OS2_PRIME_X), ysbp.wrapping_add(OS2_PRIME_Y << 1));
+		} else {
+			contrib(dx0 - (OS2_UNSKEW_2D + 1.0), dy0 - OS2_UNSKEW_2D, xsbp.wrapping_add(OS2_PRIME_X), ysbp);
+		}
+	} else {
+		if xi + xmyi < 0.0 {
+			contrib(dx0 + (1.0 + OS2_UNSKEW_2D), dy0 + OS2_UNSKEW_2D, xsbp.wrapping_sub(OS2_PRIME_X), ysbp);
+		} else {
+			contrib(dx0 - (OS2_UNSKEW_2D + 1.0), dy0 - OS2_UNSKEW_2D, xsbp.wrapping_add(OS2_PRIME_X), ysbp);
+		}
+
+		if yi < xmyi {
+			contrib(dx0 + OS2_UNSKEW_2D, dy0 + (OS2_UNSKEW_2D + 1.0), xsbp, ysbp.wrapping_sub(OS2_PRIME_Y));
+		} else {
+			contrib(dx0 - OS2_UNSKEW_2D, dy0 - (OS2_UNSKEW_2D + 1.0), xsbp, ysbp.wrapping_add(OS2_PRIME_Y));
+		}
+	}
+
+	value / OS2S_NORMALIZER_2D
+}
+
+pub fn opensimplex2s_noise_3d(x: f64, y: f64, z: f64, seed: u32, _: InterpFunc) -> f64 {
+	let seed = seed as i64;
+	let seed2 = seed ^ OS2_SEED_FLIP_3D;
+
+	let r = OS2_ROTATE_3D * (x + y + z);
+	let xr = r - x;
+	let yr = r - y;
+	let zr = r - z;
+
+	let xrb = os2_floor(xr);
+	let yrb = os2_floor(yr);
+	let zrb = os2_floor(zr);
+	let xi = xr - xrb as f64;
+	let yi = yr - yrb as f64;
+	let zi = zr - zrb as f64;
+
+	let xrbp = xrb.wrapping_mul(OS2_PRIME_X);
+	let yrbp = yrb.wrapping_mul(OS2_PRIME_Y);
+	let zrbp = zrb.wrapping_mul(OS2_PRIME_Z);
+
+	// -1 if past the cell center, 0 otherwise
+	let x_nmask = (-0.5 - xi) as i64;
+	let y_nmask = (-0.5 - yi) as i64;
+	let z_nmask = (-0.5 - zi) as i64;
+	let x_sign = (x_nmask | 1) as f64;
+	let y_sign = (y_nmask | 1) as f64;
+	let z_sign = (z_nmask | 1) as f64;
+
+	// Closest vertex of the first lattice copy
+	let x0 = xi + x_nmask as f64;
+	let y0 = yi + y_nmask as f64;
+	let z0 = zi + z_nmask as f64;
+	let a0 = OS2S_RSQUARED_3D - x0 * x0 - y0 * y0 - z0 * z0;
+	let mut value = os2_falloff(a0) * os2_grad_3(seed, xrbp.wrapping_add(x_nmask & OS2_PRIME_X), yrbp.wrapping_add(y_nmask & OS2_PRIME_Y), zrbp.wrapping_add(z_nmask & OS2_PRIME_Z), x0, y0, z0);
+
+	// Closest vertex of the second lattice copy, at the cell center
+	let x1 = xi - 0.5;
+	let y1 = yi - 0.5;
+	let z1 = zi - 0.5;
+	let a1 = OS2S_RSQUARED_3D - x1 * x1 - y1 * y1 - z1 * z1;
+	value += os2_falloff(a1) * os2_grad_3(seed2, xrbp.wrapping_add(OS2_PRIME_X), yrbp.wrapping_add(OS2_PRIME_Y), zrbp.wrapping_add(OS2_PRIME_Z), x1, y1, z1);
+
+	// Falloff deltas for the remaining vertices, derived by subtracting the polynomials with the offsets plugged in
+	let x_aflip0 = ((x_nmask | 1) << 1) as f64 * x1;
+	let y_aflip0 = ((y_nmask | 1) << 1) as f64 * y1;
+	let z_aflip0 = ((z_nmask | 1) << 1) as f64 * z1;
+	let x_aflip1 = (-2 - (x_nmask << 2)) as f64 * x1 - 1.0;
+	let y_aflip1 = (-2 - (y_nmask << 2)) as f64 * y1 - 1.0;
+	let z_aflip1 = (-2 - (z_nmask << 2)) as f64 * z1 - 1.0;
+
+	let mut skip5 = false;
+	let a2 = x_aflip0 + a0;
+	if a2 > 0.0 {
+		value += os2_falloff(a2) * os2_grad_3(seed, xrbp.wrapping_add(!x_nmask & OS2_PRIME_X), yrbp.wrapping_add(y_nmask & OS2_PRIME_Y), zrbp.wrapping_add(z_nmask & OS2_PRIME_Z), x0 - x_sign, y0, z0);
+	} else {
+		let a3 = y_aflip0 + z_aflip0 + a0;
+		if a3 > 0.0 {
+			value += os2_falloff(a3) * os2_grad_3(seed, xrbp.wrapping_add(x_nmask & OS2_PRIME_X), yrbp.wrapping_add(!y_nmask & OS2_PRIME_Y), zrbp.wrapping_add(!z_nmask & OS2_PRIME_Z), x0, y0 - y_sign, z0 - z_sign);
+		}
+
+		let a4 = x_aflip1 + a1;
+		if a4 > 0.0 {
+			value += os2_falloff(a4) * os2_grad_3(seed2, xrbp.wrapping_add(x_nmask & OS2_PRIME_X.wrapping_mul(2)), yrbp.wrapping_add(OS2_PRIME_Y), zrbp.wrapping_add(OS2_PRIME_Z), x_sign + x1, y1, z1);
+			skip5 = true;
+		}
+	}
+
+	let mut skip9 = false;
+	let a6 = y_aflip0 + a0;
+	if a6 > 0.0 {
+		value += os2_falloff(a6) * os2_grad_3(seed, xrbp.wrapping_add(x_nmask & OS2_PRIME_X), yrbp.wrapping_add(!y_nmask & OS2_PRIME_Y), zrbp.wrapping_add(z_nmask & OS2_PRIME_Z), x0, y0 - y_sign, z0);
+	} else {
+		let a7 = x_aflip0 + z_aflip0 + a0;
+		if a7 > 0.0 {
+			value += os2_falloff(a7) * os2_grad_3(seed, xrbp.wrapping_add(!x_nmask & OS2_PRIME_X), yrbp.wrapping_add(y_nmask & OS2_PRIME_Y), zrbp.wrapping_add(!z_nmask & OS2_PRIME_Z), x0 - x_sign, y0, z0 - z_sign);
+		}
+
+		let a8 = y_aflip1 + a1;
+		if a8 > 0.0 {
+			value += os2_falloff(a8) * os2_grad_3(seed2, xrbp.wrapping_add(OS2_PRIME_X), yrbp.wrapping_add(y_nmask & OS2_PRIME_Y.wrapping_mul(2)), zrbp.wrapping_add(OS2_PRIME_Z), x1, y_sign + y1, z1);
+			skip9 = true;
+		}
+	}
+
+	let mut skip_d = false;
+	let a_a = z_aflip0 + a0;
+	if a_a > 0.0 {
+		value += os2_falloff(a_a) * os2_grad_3(seed, xrbp.wrapping_add(x_nmask & OS2_PRIME_X), yrbp.wrapping_add(y_nmask & OS2_PRIME_Y), zrbp.wrapping_add(!z_nmask & OS2_PRIME_Z), x0, y0, z0 - z_sign);
+	} else {
+		let a_b = x_aflip0 + y_aflip0 + a0;
+		if a_b > 0.0 {
+			value += os2_falloff(a_b) * os2_grad_3(seed, xrbp.wrapping_add(!x_nmask & OS2_PRIME_X), yrbp.wrapping_add(!y_nmask & OS2_PRIME_Y), zrbp.wrapping_add(z_nmask & OS2_PRIME_Z), x0 - x_sign, y0 - y_sign, z0);
+		}
+
+		let a_c = z_aflip1 + a1;
+		if a_c > 0.0 {
+			value += os2_falloff(a_c) * os2_grad_3(seed2, xrbp.wrapping_add(OS2_PRIME_X), yrbp.wrapping_add(OS2_PRIME_Y), zrbp.wrapping_add(z_nmask & OS2_PRIME_Z.wrapping_mul(2)), x1, y1, z_sign + z1);
+			skip_d = true;
+		}
+	}
+
+	if !skip5 {
+		let a5 = y_aflip1 + z_aflip1 + a1;
+		if a5 > 0.0 {
+			value += os2_falloff(a5) * os2_grad_3(seed2, xrbp.wrapping_add(OS2_PRIME_X), yrbp.wrapping_add(y_nmask & OS2_PRIME_Y.wrapping_mul(2)), zrbp.wrapping_add(z_nmask & OS2_PRIME_Z.wrapping_mul(2)), x1, y_sign + y1, z_sign + z1);
+		}
+	}
+
+	if !skip9 {
+		let a9 = x_aflip1 + z_aflip1 + a1;
+		if a9 > 0.0 {
+			value += os2_falloff(a9) * os2_grad_3(seed2, xrbp.wrapping_add(x_nmask & OS2_PRIME_X.wrapping_mul(2)), yrbp.wrapping_add(OS2_PRIME_Y), zrbp.wrapping_add(z_nmask & OS2_PRIME_Z.wrapping_mul(2)), x_sign + x1, y1, z_sign + z1);
+		}
+	}
+
+	if !skip_d {
+		let a_d = x_aflip1 + y_aflip1 + a1;
+		if a_d > 0.0 {
+			value += os2_falloff(a_d) * os2_grad_3(seed2, xrbp.wrapping_add(x_nmask & OS2_PRIME_X.wrapping_mul(2)), yrbp.wrapping_add(y_nmask & OS2_PRIME_Y.wrapping_mul(2)), zrbp.wrapping_add(OS2_PRIME_Z), x_sign + x1, y_sign + y1, z1);
+		}
+	}
+
+	value / OS2S_NORMALIZER_3D
+}
+
+// A lattice vertex that may contribute to OpenSimplex2S 4D noise, relative to the base vertex of the skewed cell: the
+// offset premultiplied by the hashing primes, and its unskewed position.
+struct Os2sVertex4 {
+	xsvp: i64,
+	ysvp: i64,
+	zsvp: i64,
+	wsvp: i64,
+	dx: f64,
+	dy: f64,
+	dz: f64,
+	dw: f64,
+}
+
+lazy_static! {
+	static ref OS2S_LOOKUP_4D: Vec<Vec<Os2sVertex4>> = os2s_lookup_4d();
+}
+
+// The skewed cell is split into 4^4 sub-cells. For each one, lists the vertices that come within the kernel radius of
+// some point of the sub-cell, out of all vertices within one cell of the base cell.
+fn os2s_lookup_4d() -> Vec<Vec<Os2sVertex4>> {
+	let unskew = -OS2_SKEW_4D;
+	let mut lookup = Vec::with_capacity(256);
+	for index in 0..256 {
+		let lo = [
+			(index & 3) as f64 * 0.25,
+			(index >> 2 & 3) as f64 * 0.25,
+			(index >> 4 & 3) as f64 * 0.25,
+			(index >> 6 & 3) as f64 * 0.25,
+		];
+		let mut vertices = Vec::new();
+		for o in 0..256 {
+			let offset = [(o & 3) as i64 - 1, (o >> 2 & 3) as i64 - 1, (o >> 4 & 3) as i64 - 1, (o >> 6 & 3) as i64 - 1];
+			let mut e_lo = [0.0; 4];
+			let mut e_hi = [0.0; 4];
+			for k in 0..4 {
+				e_lo[k] = lo[k] - offset[k] as f64;
+				e_hi[k] = e_lo[k] + 0.25;
+			}
+			if os2s_min_distance_sq_4d(e_lo, e_hi, unskew) >= OS2S_RSQUARED_4D {
+				continue;
+			}
+			let ssv = (offset[0] + offset[1] + offset[2] + offset[3]) as f64 * unskew;
+			vertices.push(Os2sVertex4 {
+				xsvp: offset[0].wrapping_mul(OS2_PRIME_X),
+				ysvp: offset[1].wrapping_mul(OS2_PRIME_Y),
+				zsvp: offset[2].wrapping_mul(OS2_PRIME_Z),
+				wsvp: offset[3].wrapping_mul(OS2_PRIME_W),
+				dx: offset[0] as f64 - ssv,
+				dy: offset[1] as f64 - ssv,
+				dz: offset[2] as f64 - ssv,
+				dw: offset[3] as f64 - ssv,
+			});
+		}
+		lookup.push(vertices);
+	}
+	lookup
+}
+
+// The smallest squared unskewed length of a skewed displacement e within the box [lo, hi]. The squared length is
+// |e|^2 + c (sum e)^2, which is convex, so its minimum is the least of its stationary points over the faces of the box
+// that lie within the box. On a face with k free coordinates these are all equal, at -c (sum of the fixed) / (1 + c k).
+fn os2s_min_distance_sq_4d(lo: [f64; 4], hi: [f64; 4], unskew: f64) -> f64 {
+	let c = 4.0 * unskew * unskew - 2.0 * unskew;
+	let mut min = f64::MAX;
+	'faces: for face in 0..81 {
+		let mut e = [0.0; 4];
+		let mut fixed_sum = 0.0;
+		let mut free = 0;
+		let mut f = face;
+		for k in 0..4 {
+			match f % 3 {
+				0 => e[k] = lo[k],
+				1 => e[k] = hi[k],
+				_ => free += 1,
+			}
+			if f % 3 != 2 {
+				fixed_sum += e[k];
+			}
+			f /= 3;
+		}
+		let free_value = -c * fixed_sum / (1.0 + c * free as f64);
+		let mut f = face;
+		for k in 0..4 {
+			if f % 3 == 2 {
+				if free_value < lo[k] || free_value > hi[k] {
+					continue 'faces;
+				}
+				e[k] = free_value;
+			}
+			f /= 3;
+		}
+		let sum = (e[0] + e[1]) + (e[2] + e[3]);
+		min = min.min((e[0] * e[0] + e[1] * e[1]) + (e[2] * e[2] + e[3] * e[3]) + c * sum * sum);
+	}
+	min
+}
+
+pub fn opensimplex2s_noise_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, _: InterpFunc) -> f64 {
+	let seed = seed as i64;
+
+	// Skew onto the A4 lattice. This is the same lattice the fast variant uses, mirrored, so the skew and unskew
+	// factors swap places.
+	let skew = OS2_UNSKEW_4D;
+	let unskew = -OS2_SKEW_4D;
+
+	let s = skew * (x + y + z + w);
+	let xs = x + s;
+	let ys = y + s;
+	let zs = z + s;
+	let ws = w + s;
+
+	let xsb = os2_floor(xs);
+	let ysb = os2_floor(ys);
+	let zsb = os2_floor(zs);
+	let wsb = os2_floor(ws);
+	let xsi = xs - xsb as f64;
+	let ysi = ys - ysb as f64;
+	let zsi = zs - zsb as f64;
+	let wsi = ws - wsb as f64;
+
+	// The larger kernel reaches past the vertices of the base simplex, so the vertices that can contribute are looked
+	// up by the sub-cell the point falls in.
+	let index = ((xsi * 4.0) as usize).min(3)
+		| ((ysi * 4.0) as usize).min(3) << 2
+		| ((zsi * 4.0) as usize).min(3) << 4
+		| ((wsi * 4.0) as usize).min(3) << 6;
+
+	// Unskewed offsets from the base vertex
+	let ssi = ((xsi + ysi) + (zsi + wsi)) * unskew;
+	let xsi = xsi - ssi;
+	let ysi = ysi - ssi;
+	let zsi = zsi - ssi;
+	let wsi = wsi - ssi;
+
+	let xsbp = xsb.wrapping_mul(OS2_PRIME_X);
+	let ysbp = ysb.wrapping_mul(OS2_PRIME_Y);
+	let zsbp = zsb.wrapping_mul(OS2_PRIME_Z);
+	let wsbp = wsb.wrapping_mul(OS2_PRIME_W);
+
+	let mut value = 0.0;
+	for v in OS2S_LOOKUP_4D[index].iter() {
+		let dx = xsi - v.dx;
+		let dy = ysi - v.dy;
+		let dz = zsi - v.dz;
+		let dw = wsi - v.dw;
+		let a = OS2S_RSQUARED_4D - (dx * dx + dy * dy) - (dz * dz + dw * dw);
+		if a > 0.0 {
+			value += os2_falloff(a) * os2_grad_4(seed, xsbp.wrapping_add(v.xsvp), ysbp.wrapping_add(v.ysvp), zsbp.wrapping_add(v.zsvp), wsbp.wrapping_add(v.wsvp), dx, dy, dz, dw);
+		}
+	}
+
+	value / OS2S_NORMALIZER_4D
+}
+
+// Periodic lattice noise. The integer lattice coordinates are wrapped at a per-axis period before hashing, so the
+// noise repeats every `period` units along that axis. A period of 0 leaves the axis unwrapped.
+
+pub type PeriodicNoiseFunc2 = fn(f64, f64, [u32; 2], u32, InterpFunc) -> f64;
+pub type PeriodicNoiseFunc3 = fn(f64, f64, f64, [u32; 3], u32, InterpFunc) -> f64;
+pub type PeriodicNoiseFunc4 = fn(f64, f64, f64, f64, [u32; 4], u32, InterpFunc) -> f64;
+pub type PeriodicNoiseFunc6 = fn(f64, f64, f64, f64, f64, f64, [u32; 6], u32, InterpFunc) -> f64;
+
+#[inline(always)]
+fn wrap_lattice(i: i32, period: u32) -> i32 {
+	if period == 0 { i } else { i.rem_euclid(period as i32) }
+}
+
+#[inline(always)]
+fn wrap_coord(x: f64, period: u32) -> f64 {
+	if period == 0 { x } else { x - period as f64 * (x / period as f64).floor() }
+}
+
+// Interpolates the corners of the lattice cell enclosing p, in the same order as the interp_* helpers above. The
+// corner function receives the unwrapped cell coordinates of the corner and the wrapped ones to hash. Axes beyond the
+// end of `period` do not wrap.
+//
+// The corners are visited in binary order and reduced as they come: partial[k] holds the interpolation along the
+// first k axes of the last half-finished block, so only one value per axis is kept instead of all 2^n corners.
+fn periodic_lattice<F: Fn(&[i32], &[i32]) -> f64>(p: &[f64], period: &[u32], interp: InterpFunc, corner: F) -> f64 {
+	let n = p.len().min(MAX_DIMENSIONS);
+	let mut cell = [0i32; MAX_DIMENSIONS];
+	let mut s = [0.0; MAX_DIMENSIONS];
+	for k in 0..n {
+		cell[k] = fast_floor(p[k]);
+		s[k] = interp(p[k] - cell[k] as f64);
+	}
+
+	let mut partial = [0.0; MAX_DIMENSIONS + 1];
+	let mut ic = [0i32; MAX_DIMENSIONS];
+	let mut iw = [0i32; MAX_DIMENSIONS];
+	for c in 0..1usize << n {
+		for k in 0..n {
+			ic[k] = cell[k] + ((c >> k) & 1) as i32;
+			iw[k] = wrap_lattice(ic[k], period.get(k).cloned().unwrap_or(0));
+		}
+		let mut v = corner(&ic[..n], &iw[..n]);
+		let mut k = 0;
+		while (c >> k) & 1 == 1 {
+			v = lerp(s[k], partial[k], v);
+			k += 1;
+		}
+		partial[k] = v;
+	}
+	partial[n]
+}
+
+pub fn value_noise_2d_periodic_with<H: LatticeHash>(x: f64, y: f64, period: [u32; 2], seed: u32, interp: InterpFunc) -> f64 {
+	periodic_lattice(&[x, y], &period, interp, |_, w| H::value(H::hash_2(w[0], w[1], seed)))
+}
+
+pub fn value_noise_3d_periodic_with<H: LatticeHash>(x: f64, y: f64, z: f64, period: [u32; 3], seed: u32, interp: InterpFunc) -> f64 {
+	periodic_lattice(&[x, y, z], &period, interp, |_, w| H::value(H::hash_3(w[0], w[1], w[2], seed)))
+}
+
+pub fn value_noise_4d_periodic_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, period: [u32; 4], seed: u32, interp: InterpFunc) -> f64 {
+	periodic_lattice(&[x, y, z, w], &period, interp, |_, w| H::value(H::hash_4(w[0], w[1], w[2], w[3], seed)))
+}
+
+pub fn value_noise_6d_periodic_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, period: [u32; 6], seed: u32, interp: InterpFunc) -> f64 {
+	periodic_lattice(&[x, y, z, w, u, v], &period, interp, |_, w| H::value(H::hash_6(w[0], w[1], w[2], w[3], w[4], w[5], seed)))
+}
+
+/// Gradient noise that repeats every `period[0]` units along x and every `period[1]` units along y. The other periodic
+/// functions repeat in the same way along each of their axes.
+///
+/// ```
+/// use anl::noise_gen::{gradient_noise_2d_periodic_with, simplex_noise_2d_periodic_with, quintic_interp, FnvHash};
+///
+/// let (x, y) = (0.375, -1.625);
+/// let a = gradient_noise_2d_periodic_with::<FnvHash>(x, y, [4, 8], 7, quintic_interp);
+/// assert_eq!(a, gradient_noise_2d_periodic_with::<FnvHash>(x + 4.0, y, [4, 8], 7, quintic_interp));
+/// assert_eq!(a, gradient_noise_2d_periodic_with::<FnvHash>(x, y + 8.0, [4, 8], 7, quintic_interp));
+///
+/// let b = simplex_noise_2d_periodic_with::<FnvHash>(x, y, [4, 8], 7, quintic_interp);
+/// assert_eq!(b, simplex_noise_2d_periodic_with::<FnvHash>(x + 4.0, y - 8.0, [4, 8], 7, quintic_interp));
+/// ```
+pub fn gradient_noise_2d_periodic_with<H: LatticeHash>(x: f64, y: f64, period: [u32; 2], seed: u32, interp: InterpFunc) -> f64 {
+	periodic_lattice(&[x, y], &period, interp, |c, w| {
+		let g = H::gradient_2(H::hash_2(w[0], w[1], seed));
+		(x - c[0] as f64) * g[0] + (y - c[1] as f64) * g[1]
+	})
+}
+
+pub fn gradient_noise_3d_periodic_with<H: LatticeHash>(x: f64, y: f64, z: f64, period: [u32; 3], seed: u32, interp: InterpFunc) -> f64 {
+	periodic_lattice(&[x, y, z], &period, interp, |c, w| {
+		let g = H::gradient_3(H::hash_3(w[0], w[1], w[2], seed));
+		(x - c[0] as f64) * g[0] + (y - c[1] as f64) * g[1] + (z - c[2] as f64) * g[2]
+	})
+}
+
+pub fn gradient_noise_4d_periodic_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, period: [u32; 4], seed: u32, interp: InterpFunc) -> f64 {
+	let p = [x, y, z, w];
+	periodic_lattice(&p, &period, interp, |c, iw| {
+		let g = H::gradient_4(H::hash_4(iw[0], iw[1], iw[2], iw[3], seed));
+		(0..4).fold(0.0, |sum, k| sum + (p[k] - c[k] as f64) * g[k])
+	})
+}
+
+pub fn gradient_noise_6d_periodic_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, period: [u32; 6], seed: u32, interp: InterpFunc) -> f64 {
+	let p = [x, y, z, w, u, v];
+	periodic_lattice(&p, &period, interp, |c, iw| {
+		let g = H::gradient_6(H::hash_6(iw[0], iw[1], iw[2], iw[3], iw[4], iw[5], seed));
+		(0..6).fold(0.0, |sum, k| sum + (p[k] - c[k] as f64) * g[k])
+	})
+}
+
+pub fn gradval_noise_2d_periodic_with<H: LatticeHash>(x: f64, y: f64, period: [u32; 2], seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_2d_periodic_with::<H>(x, y, period, seed, interp) + gradient_noise_2d_periodic_with::<H>(x, y, period, seed, interp)
+}
+
+pub fn gradval_noise_3d_periodic_with<H: LatticeHash>(x: f64, y: f64, z: f64, period: [u32; 3], seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_3d_periodic_with::<H>(x, y, z, period, seed, interp) + gradient_noise_3d_periodic_with::<H>(x, y, z, period, seed, interp)
+}
+
+pub fn gradval_noise_4d_periodic_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, period: [u32; 4], seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_4d_periodic_with::<H>(x, y, z, w, period, seed, interp) + gradient_noise_4d_periodic_with::<H>(x, y, z, w, period, seed, interp)
+}
+
+pub fn gradval_noise_6d_periodic_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, period: [u32; 6], seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_6d_periodic_with::<H>(x, y, z, w, u, v, period, seed, interp) + gradient_noise_6d_periodic_with::<H>(x, y, z, w, u, v, period, seed, interp)
+}
+
+pub fn white_noise_2d_periodic_with<H: LatticeHash>(x: f64, y: f64, period: [u32; 2], seed: u32, interp: InterpFunc) -> f64 {
+	white_noise_2d_with::<H>(wrap_coord(x, period[0]), wrap_coord(y, period[1]), seed, interp)
+}
+
+pub fn white_noise_3d_periodic_with<H: LatticeHash>(x: f64, y: f64, z: f64, period: [u32; 3], seed: u32, interp: InterpFunc) -> f64 {
+	white_noise_3d_with::<H>(wrap_coord(x, period[0]), wrap_coord(y, period[1]), wrap_coord(z, period[2]), seed, interp)
+}
+
+pub fn white_noise_4d_periodic_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, period: [u32; 4], seed: u32, interp: InterpFunc) -> f64 {
+	white_noise_4d_with::<H>(wrap_coord(x, period[0]), wrap_coord(y, period[1]), wrap_coord(z, period[2]), wrap_coord(w, period[3]), seed, interp)
+}
+
+pub fn white_noise_6d_periodic_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, period: [u32; 6], seed: u32, interp: InterpFunc) -> f64 {
+	white_noise_6d_with::<H>(wrap_coord(x, period[0]), wrap_coord(y, period[1]), wrap_coord(z, period[2]), wrap_coord(w, period[3]), wrap_coord(u, period[4]), wrap_coord(v, period[5]), seed, interp)
+}
+
+// 1D noise, for signals such as camera shake or flickering lights. The lattice is the integers, and gradient noise
+// uses slopes in [-1,1] as gradients, so these give the same results as the N-dimensional functions below with one
+// coordinate, only faster. In 1D every simplex lattice is the integer lattice, so simplex noise needs no skewing and
+// repeats with any period.
+
+pub type PeriodicNoiseFunc1 = fn(f64, u32, u32, InterpFunc) -> f64;
+
+// Largest magnitude of the sum of the two kernels (1 - d^2)^4 * d of 1D simplex noise, for unit slopes.
+const SIMPLEX_SCALE_1D: f64 = 256.0 / 81.0;
+
+fn lattice_1d<F: Fn(i32, i32) -> f64>(x: f64, period: u32, interp: InterpFunc, corner: F) -> f64 {
+	let x0 = fast_floor(x);
+	let xs = interp(x - x0 as f64);
+	let v0 = corner(x0, wrap_lattice(x0, period));
+	let v1 = corner(x0 + 1, wrap_lattice(x0 + 1, period));
+	lerp(xs, v0, v1)
+}
+
+fn simplex_1d<H: LatticeHash>(x: f64, period: u32, seed: u32) -> f64 {
+	let i0 = fast_floor(x);
+	let mut n = 0.0;
+	for i in i0..i0 + 2 {
+		let d = x - i as f64;
+		let t = 1.0 - d * d;
+		n += t * t * t * t * H::gradient_1(H::hash_n(&[wrap_lattice(i, period)], seed)) * d;
+	}
+	n * SIMPLEX_SCALE_1D
+}
+
+pub fn value_noise_1d_with<H: LatticeHash>(x: f64, seed: u32, interp: InterpFunc) -> f64 {
+	lattice_1d(x, 0, interp, |_, w| H::value(H::hash_n(&[w], seed)))
+}
+
+pub fn gradient_noise_1d_with<H: LatticeHash>(x: f64, seed: u32, interp: InterpFunc) -> f64 {
+	lattice_1d(x, 0, interp, |c, w| (x - c as f64) * H::gradient_1(H::hash_n(&[w], seed)))
+}
+
+pub fn gradval_noise_1d_with<H: LatticeHash>(x: f64, seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_1d_with::<H>(x, seed, interp) + gradient_noise_1d_with::<H>(x, seed, interp)
+}
+
+pub fn white_noise_1d_with<H: LatticeHash>(x: f64, seed: u32, _: InterpFunc) -> f64 {
+	H::white(H::hash_f64_n(&[x], seed))
+}
+
+/// 1D simplex noise, after Gustavson: the sum of a radial kernel around each of the two nearest lattice points. The
+/// kernels reach zero, with zero slope, at the neighbouring points, so unlike interpolated noise there is no
+/// visible kink at the lattice points. The interptype parameter is ignored.
+pub fn simplex_noise_1d_with<H: LatticeHash>(x: f64, seed: u32, _: InterpFunc) -> f64 {
+	simplex_1d::<H>(x, 0, seed)
+}
+
+pub fn value_noise_1d_periodic_with<H: LatticeHash>(x: f64, period: u32, seed: u32, interp: InterpFunc) -> f64 {
+	lattice_1d(x, period, interp, |_, w| H::value(H::hash_n(&[w], seed)))
+}
+
+/// 1D gradient noise that repeats every `period` units. A period of 0 does not repeat.
+///
+/// ```
+/// use anl::noise_gen::{gradient_noise_1d_periodic_with, simplex_noise_1d_periodic_with, quintic_interp, PcgHash};
+///
+/// let x = 0.375;
+/// let a = gradient_noise_1d_periodic_with::<PcgHash>(x, 5, 7, quintic_interp);
+/// assert_eq!(a, gradient_noise_1d_periodic_with::<PcgHash>(x + 5.0, 5, 7, quintic_interp));
+/// assert_eq!(a, gradient_noise_1d_periodic_with::<PcgHash>(x - 10.0, 5, 7, quintic_interp));
+///
+/// let b = simplex_noise_1d_periodic_with::<PcgHash>(x, 5, 7, quintic_interp);
+/// assert_eq!(b, simplex_noise_1d_periodic_with::<PcgHash>(x + 5.0, 5, 7, quintic_interp));
+/// ```
+pub fn gradient_noise_1d_periodic_with<H: LatticeHash>(x: f64, period: u32, seed: u32, interp: InterpFunc) -> f64 {
+	lattice_1d(x, period, interp, |c, w| (x - c as f64) * H::gradient_1(H::hash_n(&[w], seed)))
+}
+
+pub fn gradval_noise_1d_periodic_with<H: LatticeHash>(x: f64, period: u32, seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_1d_periodic_with::<H>(x, period, seed, interp) + gradient_noise_1d_periodic_with::<H>(x, period, seed, interp)
+}
+
+pub fn white_noise_1d_periodic_with<H: LatticeHash>(x: f64, period: u32, seed: u32, interp: InterpFunc) -> f64 {
+	white_noise_1d_with::<H>(wrap_coord(x, period), seed, interp)
+}
+
+pub fn simplex_noise_1d_periodic_with<H: LatticeHash>(x: f64, period: u32, seed: u32, _: InterpFunc) -> f64 {
+	simplex_1d::<H>(x, period, seed)
+}
+
+// N-dimensional lattice noise. These evaluate the same cells as the fixed-dimension functions, and give the same
+// results for 2, 3, 4 and 6 coordinates, but work for any number of coordinates up to MAX_DIMENSIONS, which makes
+// them slower. Use them for the dimensions that have no dedicated function, such as 5D or a seamless 3D volume that
+// also loops in time (8D).
+
+pub type PeriodicNoiseFuncN = fn(&[f64], &[u32], u32, InterpFunc) -> f64;
+
+fn gradient_corner<H: LatticeHash>(p: &[f64], c: &[i32], w: &[i32], seed: u32) -> f64 {
+	let mut g = [0.0; MAX_DIMENSIONS];
+	let g = &mut g[..c.len()];
+	H::gradient_n(H::hash_n(w, seed), g);
+	g.iter().enumerate().fold(0.0, |sum, (k, gk)| sum + (p[k] - c[k] as f64) * gk)
+}
+
+pub fn value_noise_nd_with<H: LatticeHash>(p: &[f64], seed: u32, interp: InterpFunc) -> f64 {
+	periodic_lattice(p, &[], interp, |_, w| H::value(H::hash_n(w, seed)))
+}
+
+pub fn gradient_noise_nd_with<H: LatticeHash>(p: &[f64], seed: u32, interp: InterpFunc) -> f64 {
+	periodic_lattice(p, &[], interp, |c, w| gradient_corner::<H>(p, c, w, seed))
+}
+
+pub fn gradval_noise_nd_with<H: LatticeHash>(p: &[f64], seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_nd_with::<H>(p, seed, interp) + gradient_noise_nd_with::<H>(p, seed, interp)
+}
+
+pub fn white_noise_nd_with<H: LatticeHash>(p: &[f64], seed: u32, _: InterpFunc) -> f64 {
+	H::white(H::hash_f64_n(p, seed))
+}
+
+/// Periodic N-dimensional value noise. Axis k repeats every `period[k]` units; axes beyond the end of `period`, or
+/// with a period of 0, do not repeat.
+pub fn value_noise_nd_periodic_with<H: LatticeHash>(p: &[f64], period: &[u32], seed: u32, interp: InterpFunc) -> f64 {
+	periodic_lattice(p, period, interp, |_, w| H::value(H::hash_n(w, seed)))
+}
+
+pub fn gradient_noise_nd_periodic_with<H: LatticeHash>(p: &[f64], period: &[u32], seed: u32, interp: InterpFunc) -> f64 {
+	periodic_lattice(p, period, interp, |c, w| gradient_corner::<H>(p, c, w, seed))
+}
+
+pub fn gradval_noise_nd_periodic_with<H: LatticeHash>(p: &[f64], period: &[u32], seed: u32, interp: InterpFunc) -> f64 {
+	value_noise_nd_periodic_with::<H>(p, period, seed, interp) + gradient_noise_nd_periodic_with::<H>(p, period, seed, interp)
+}
+
+pub fn white_noise_nd_periodic_with<H: LatticeHash>(p: &[f64], period: &[u32], seed: u32, interp: InterpFunc) -> f64 {
+	let mut q = [0.0; MAX_DIMENSIONS];
+	let n = p.len().min(MAX_DIMENSIONS);
+	for k in 0..n {
+		q[k] = wrap_coord(p[k], period.get(k).cloned().unwrap_or(0));
+	}
+	white_noise_nd_with::<H>(&q[..n], seed, interp)
+}
+
+// The skewed lattice of classic simplex noise cannot repeat along the coordinate axes, so periodic simplex noise is
+// built on the lattices of Gustavson and McEwan's psrdnoise instead. In 2D the vertices sit at (i - j/2, j): rows of
+// unit spacing, each shifted by half a cell. Vertices are identified by their doubled x coordinate 2i - j, which is
+// wrapped at twice the x period; since a shift of one row also shifts x by half a cell, the y period must be even.
+const PSIMPLEX_SCALE_2D: f64 = 10.9;
+const PSIMPLEX_SCALE_3D: f64 = 107.0;
+
+/// Simplex noise that repeats every `period[0]` units along x and every `period[1]` units along y. Odd y periods are
+/// doubled, as the triangular lattice only repeats after an even number of rows.
+pub fn simplex_noise_2d_periodic_with<H: LatticeHash>(x: f64, y: f64, period: [u32; 2], seed: u32, _: InterpFunc) -> f64 {
+	let px = period[0] * 2;
+	let py = if period[1] % 2 == 1 { period[1] * 2 } else { period[1] };
+
+	let u = x + y * 0.5;
+	let i0 = u.floor() as i32;
+	let j0 = y.floor() as i32;
+	let (i1, j1) = if u - i0 as f64 >= y - j0 as f64 { (1, 0) } else { (0, 1) };
+
+	let mut value = 0.0;
+	for &(i, j) in [(i0, j0), (i0 + i1, j0 + j1), (i0 + 1, j0 + 1)].iter() {
+		let x2 = 2 * i - j;
+		let dx = x - x2 as f64 * 0.5;
+		let dy = y - j as f64;
+		let t = 0.8 - dx * dx - dy * dy;
+		if t > 0.0 {
+			let g = H::gradient_2(H::hash_2(wrap_lattice(x2, px), wrap_lattice(j, py), seed));
+			let t2 = t * t;
+			value += t2 * t2 * array_dot(&g, dx, dy);
+		}
+	}
+	value * PSIMPLEX_SCALE_2D
+}
+
+/// Simplex noise on a body-centered cubic lattice that repeats every `period[k]` units along each axis. Vertices are
+/// either all-integer or all-half-integer points, and are identified by their doubled coordinates.
+pub fn simplex_noise_3d_periodic_with<H: LatticeHash>(x: f64, y: f64, z: f64, period: [u32; 3], seed: u32, _: InterpFunc) -> f64 {
+	// Map to the lattice basis, where the cells are unit cubes split into six tetrahedra by sorting the offsets.
+	let uvw = [y + z, x + z, x + y];
+	let i0 = [uvw[0].floor() as i32, uvw[1].floor() as i32, uvw[2].floor() as i32];
+	let f = [uvw[0] - i0[0] as f64, uvw[1] - i0[1] as f64, uvw[2] - i0[2] as f64];
+
+	let mut order = [0usize, 1, 2];
+	order.sort_by(|&a, &b| f[b].partial_cmp(&f[a]).unwrap_or(Ordering::Equal));
+
+	let mut corner = i0;
+	let mut value = 0.0;
+	for step in 0..4 {
+		if step > 0 {
+			corner[order[step - 1]] += 1;
+		}
+		let x2 = -corner[0] + corner[1] + corner[2];
+		let y2 = corner[0] - corner[1] + corner[2];
+		let z2 = corner[0] + corner[1] - corner[2];
+		let dx = x - x2 as f64 * 0.5;
+		let dy = y - y2 as f64 * 0.5;
+		let dz = z - z2 as f64 * 0.5;
+		let t = 0.5 - dx * dx - dy * dy - dz * dz;
+		if t > 0.0 {
+			let h = H::hash_3(wrap_lattice(x2, period[0] * 2), wrap_lattice(y2, period[1] * 2), wrap_lattice(z2, period[2] * 2), seed);
+			let t2 = t * t;
+			value += t2 * t2 * array_dot_3(&H::gradient_3(h), dx, dy, dz);
+		}
+	}
+	value * PSIMPLEX_SCALE_3D
+}
+
+// Wavelet noise, after Cook and DeRose, "Wavelet Noise" (SIGGRAPH 2005). A tile of Gaussian noise is split into a
+// coarse part and a fine part by downsampling and upsampling it; only the fine part is kept, so the noise is
+// band-limited to a single octave and fractals built from it neither alias nor lose contrast. The tile is evaluated
+// with a quadratic B-spline, so the interpolation type is ignored. One tile is generated per dimension, from a fixed
+// seed; the noise seed picks a different offset into the tile.
+
+const WAVELET_ARAD: i64 = 16;
+const WAVELET_ACOEFFS: [f64; 32] = [
+	0.000334, -0.001528, 0.000410, 0.003545, -0.000938, -0.008233, 0.002172, 0.019120,
+	-0.004479, -0.045985, 0.007943, 0.112596, -0.011659, -0.293132, 0.014001, 0.967477,
+	0.967477, 0.014001, -0.293132, -0.011659, 0.112596, 0.007943, -0.045985, -0.004479,
+	0.019120, 0.002172, -0.008233, -0.000938, 0.003546, 0.000410, -0.001528, 0.000334,
+];
+const WAVELET_PCOEFFS: [f64; 4] = [0.25, 0.75, 0.75, 0.25];
+const WAVELET_TILE_SEED: u32 = 20050731;
+
+// Tile sizes must be even. Higher dimensions use smaller tiles to keep memory and generation time reasonable, so they
+// repeat sooner.
+const WAVELET_SIZE_2D: usize = 128;
+const WAVELET_SIZE_3D: usize = 64;
+const WAVELET_SIZE_4D: usize = 24;
+// 8^6 texels already take 2 MiB; 16^6 would take 128 MiB. The short period is documented on BasisType::Wavelet.
+const WAVELET_SIZE_6D: usize = 8;
+
+lazy_static! {
+	static ref WAVELET_TILE_2D: Vec<f64> = wavelet_tile(2, WAVELET_SIZE_2D);
+	static ref WAVELET_TILE_3D: Vec<f64> = wavelet_tile(3, WAVELET_SIZE_3D);
+	static ref WAVELET_TILE_4D: Vec<f64> = wavelet_tile(4, WAVELET_SIZE_4D);
+	static ref WAVELET_TILE_6D: Vec<f64> = wavelet_tile(6, WAVELET_SIZE_6D);
+}
+
+// Downsamples the row of n values starting at base, with the given stride, into its first n/2 entries.
+fn wavelet_downsample(from: &[f64], to: &mut [f64], base: usize, n: usize, stride: usize) {
+	for i in 0..n / 2 {
+		let c = 2 * i as i64;
+		let mut sum = 0.0;
+		for k in (c - WAVELET_ARAD)..(c + WAVELET_ARAD) {
+			sum += WAVELET_ACOEFFS[(k - c + WAVELET_ARAD) as usize] * from[base + k.rem_euclid(n as i64) as usize * stride];
+		}
+		to[base + i * stride] = sum;
+	}
+}
+
+fn wavelet_upsample(from: &[f64], to: &mut [f64], base: usize, n: usize, stride: usize) {
+	let half = (n / 2) as i64;
+	for i in 0..n as i64 {
+		let mut sum = 0.0;
+		for k in (i / 2)..(i / 2 + 2) {
+			sum += WAVELET_PCOEFFS[(i - 2 * k + 2) as usize] * from[base + k.rem_euclid(half) as usize * stride];
+		}
+		to[base + i as usize * stride] = sum;
+	}
+}
+
+fn wavelet_tile(dims: usize, n: usize) -> Vec<f64> {
+	let size = n.pow(dims as u32);
+
+	let mut prng = CMWC4096::new();
+	prng.set_seed(WAVELET_TILE_SEED);
+	let mut noise = vec![0.0; size];
+	for v in noise.iter_mut() {
+		// Box-Muller
+		let u1 = get_01(&mut prng).max(1.0e-300);
+		let u2 = get_01(&mut prng);
+		*v = (-2.0 * u1.ln()).sqrt() * (2.0 * ::std::f64::consts::PI * u2).cos();
+	}
+
+	// Split off the coarse part by downsampling and upsampling along each axis in turn.
+	let mut temp1 = vec![0.0; size];
+	let mut temp2 = noise.clone();
+	for axis in 0..dims {
+		let stride = n.pow(axis as u32);
+		for base in (0..size).filter(|b| (b / stride).is_multiple_of(n)) {
+			wavelet_downsample(&temp2, &mut temp1, base, n, stride);
+			wavelet_upsample(&temp1, &mut temp2, base, n, stride);
+		}
+	}
+	for (v, c) in noise.iter_mut().zip(temp2.iter()) {
+		*v -= *c;
+	}
+
+	// Even and odd coefficients have different variances; adding a copy shifted by an odd offset evens them out.
+	let offset = if (n / 2).is_multiple_of(2) { n / 2 + 1 } else { n / 2 };
+	for (i, t) in temp1.iter_mut().enumerate() {
+		let mut shifted = 0;
+		let mut stride = 1;
+		for _ in 0..dims {
+			shifted += ((i / stride) % n + offset) % n * stride;
+			stride *= n;
+		}
+		*t = noise[shifted];
+	}
+	for (v, t) in noise.iter_mut().zip(temp1.iter()) {
+		*v += *t;
+	}
+
+	// Scale the tile so that the spline through it spans [-1,1].
+	let max = wavelet_max(&noise, dims, n);
+	for v in noise.iter_mut() {
+		*v /= max;
+	}
+	noise
+}
+
+// Relative accuracy of the maximum found by wavelet_max(), which is never below the actual maximum.
+const WAVELET_MAX_TOLERANCE: f64 = 1.0e-2;
+
+// A cell of the spline through the tile, or a part of one, as its Bezier control net and the axis along which to
+// split it next, ordered by the largest magnitude among the control points.
+struct WaveletCell(f64, Vec<f64>, usize);
+
+impl PartialEq for WaveletCell {
+	fn eq(&self, other: &WaveletCell) -> bool {
+		self.0 == other.0
+	}
+}
+
+impl Eq for WaveletCell {}
+
+impl PartialOrd for WaveletCell {
+	fn partial_cmp(&self, other: &WaveletCell) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for WaveletCell {
+	fn cmp(&self, other: &WaveletCell) -> Ordering {
+		self.0.partial_cmp(&other.0).unwrap_or(Ordering::Equal)
+	}
+}
+
+impl WaveletCell {
+	fn new(net: Vec<f64>, axis: usize) -> WaveletCell {
+		WaveletCell(net.iter().fold(0.0, |m: f64, v| m.max(v.abs())), net, axis)
+	}
+}
+
+// Applies f to each row of 3 control points of the net along the axis with the given stride.
+fn wavelet_net_map<F: Fn(f64, f64, f64) -> [f64; 3]>(net: &mut [f64], stride: usize, f: F) {
+	for row in (0..net.len()).step_by(3 * stride) {
+		for i in row..row + stride {
+			let r = f(net[i], net[i + stride], net[i + 2 * stride]);
+			net[i] = r[0];
+			net[i + stride] = r[1];
+			net[i + 2 * stride] = r[2];
+		}
+	}
+}
+
+// Finds the largest magnitude of the quadratic B-spline through the tile, to within the tolerance and never below it.
+// Over a cell, the spline lies within the convex hull of its Bezier control points: along each axis, the middle value
+// and its averages with the two neighbours. Its values at the cell corners and centres give a lower bound of the
+// maximum, and only the cells with a control point above that bound can reach higher. Those are subdivided, largest
+// control point first, raising the bound as they go, until no part is left with a control point more than the
+// tolerance above it.
+fn wavelet_max(tile: &[f64], dims: usize, n: usize) -> f64 {
+	let size = tile.len();
+	let strides: Vec<usize> = (0..dims).map(|k| n.pow(k as u32)).collect();
+	let average = |a: &[f64], stride: usize| -> Vec<f64> {
+		(0..size).map(|i| {
+			let k = (i / stride) % n;
+			0.5 * (a[i] + a[i - k * stride + (k + 1) % n * stride])
+		}).collect()
+	};
+
+	// The spline passes through the corner points of the cells, the tile averaged with the next value along every
+	// axis, and takes the tile weighted by 1/8, 3/4 and 1/8 along every axis at their centres.
+	let mut corners = tile.to_vec();
+	let mut centres = tile.to_vec();
+	for &stride in strides.iter() {
+		corners = average(&corners, stride);
+		centres = (0..size).map(|i| {
+			let k = (i / stride) % n;
+			let row = i - k * stride;
+			0.125 * (centres[row + (k + n - 1) % n * stride] + centres[row + (k + 1) % n * stride]) + 0.75 * centres[i]
+		}).collect();
+	}
+	let mut lower = corners.iter().chain(centres.iter()).fold(0.0, |m: f64, v| m.max(v.abs()));
+	let threshold = lower * (1.0 + WAVELET_MAX_TOLERANCE);
+
+	// The control points of all the cells are the tile averaged along any subset of the axes. A point averaged along
+	// an axis is shared by the two cells on either side of it along that axis.
+	let mut cells = Vec::new();
+	let mut stack = vec![(tile.to_vec(), 0usize, 0usize)];
+	while let Some((a, axis, mask)) = stack.pop() {
+		if axis < dims {
+			stack.push((average(&a, strides[axis]), axis + 1, mask | 1 << axis));
+			stack.push((a, axis + 1, mask));
+			continue;
+		}
+		for (i, v) in a.iter().enumerate() {
+			if v.abs() <= threshold {
+				continue;
+			}
+			for shift in 0..1usize << dims {
+				if shift & !mask != 0 {
+					continue;
+				}
+				let mut cell = 0;
+				for (k, &stride) in strides.iter().enumerate() {
+					cell += ((i / stride) % n + (shift >> k & 1)) % n * stride;
+				}
+				cells.push(cell);
+			}
+		}
+	}
+	cells.sort();
+	cells.dedup();
+
+	// The spline passes through the corner points of each part of a cell, and at its centre weighs the control points
+	// by 1/4, 1/2 and 1/4 along each axis.
+	let taps = 3usize.pow(dims as u32);
+	let corner_taps: Vec<usize> = (0..taps).filter(|&t| (0..dims).all(|k| (t / 3usize.pow(k as u32)) % 3 != 1)).collect();
+	let centre_weights: Vec<f64> = (0..taps).map(|t| (0..dims).fold(1.0, |w, k| w * if (t / 3usize.pow(k as u32)) % 3 == 1 { 0.5 } else { 0.25 })).collect();
+	let on_spline = |net: &[f64]| {
+		let centre = net.iter().zip(centre_weights.iter()).fold(0.0, |sum, (v, w)| sum + v * w);
+		corner_taps.iter().fold(centre.abs(), |m, &t| m.max(net[t].abs()))
+	};
+
+	let mut heap = BinaryHeap::new();
+	let mut index = vec![0usize; taps];
+	for &cell in cells.iter() {
+		index[0] = 0;
+		let mut len = 1;
+		for &stride in strides.iter() {
+			let c = (cell / stride) % n;
+			for j in (0..3).rev() {
+				let offset = (c + j + n - 1) % n * stride;
+				for t in 0..len {
+					index[j * len + t] = index[t] + offset;
+				}
+			}
+			len *= 3;
+		}
+		let mut net: Vec<f64> = index.iter().map(|&t| tile[t]).collect();
+		for k in 0..dims {
+			wavelet_net_map(&mut net, 3usize.pow(k as u32), |a, b, c| [0.5 * (a + b), b, 0.5 * (b + c)]);
+		}
+		lower = lower.max(on_spline(&net));
+		let cell = WaveletCell::new(net, 0);
+		if cell.0 > lower * (1.0 + WAVELET_MAX_TOLERANCE) {
+			heap.push(cell);
+		}
+	}
+
+	while let Some(WaveletCell(upper, net, axis)) = heap.pop() {
+		if upper <= lower * (1.0 + WAVELET_MAX_TOLERANCE) {
+			break;
+		}
+		// Split the part in two along one axis, taking the axes in turn.
+		let stride = 3usize.pow(axis as u32);
+		let mut left = net.clone();
+		let mut right = net;
+		wavelet_net_map(&mut left, stride, |a, b, c| [a, 0.5 * (a + b), 0.25 * (a + 2.0 * b + c)]);
+		wavelet_net_map(&mut right, stride, |a, b, c| [0.25 * (a + 2.0 * b + c), 0.5 * (b + c), c]);
+		lower = lower.max(on_spline(&left)).max(on_spline(&right));
+		for part in [left, right] {
+			let part = WaveletCell::new(part, (axis + 1) % dims);
+			if part.0 > lower * (1.0 + WAVELET_MAX_TOLERANCE) {
+				heap.push(part);
+			}
+		}
+	}
+	lower * (1.0 + WAVELET_MAX_TOLERANCE)
+}
+
+fn wavelet_eval(tile: &[f64], n: usize, p: &[f64], period: &[u32], seed: u32) -> f64 {
+	let dims = p.len();
+
+	// The seed selects an offset into the tile along every axis.
+	let mut lcg = LCG::new();
+	lcg.set_seed(seed);
+
+	let mut mid = [0i32; 6];
+	let mut w = [[0.0; 3]; 6];
+	let mut offset = [0usize; 6];
+	for k in 0..dims {
+		mid[k] = (p[k] - 0.5).ceil() as i32;
+		let t = mid[k] as f64 - (p[k] - 0.5);
+		w[k][0] = t * t * 0.5;
+		w[k][2] = (1.0 - t) * (1.0 - t) * 0.5;
+		w[k][1] = 1.0 - w[k][0] - w[k][2];
+		offset[k] = get_target(&mut lcg, n as u32) as usize;
+	}
+
+	let mut result = 0.0;
+	for tap in 0..3usize.pow(dims as u32) {
+		let mut rem = tap;
+		let mut index = 0;
+		let mut stride = 1;
+		let mut weight = 1.0;
+		for k in 0..dims {
+			let f = rem % 3;
+			rem /= 3;
+			let c = wrap_lattice(mid[k] + f as i32 - 1, period[k]);
+			index += ((c as i64 + offset[k] as i64).rem_euclid(n as i64)) as usize * stride;
+			stride *= n;
+			weight *= w[k][f];
+		}
+		result += weight * tile[index];
+	}
+	result
+}
+
+/// The tiles are scaled so that wavelet noise stays within [-1,1] and comes close to its ends:
+///
+/// ```
+/// use anl::noise_gen::{wavelet_noise_2d, linear_interp};
+///
+/// let mut max: f64 = 0.0;
+/// for i in 0..256 {
+///     for j in 0..256 {
+///         max = max.max(wavelet_noise_2d(i as f64 * 0.5, j as f64 * 0.5, 0, linear_interp).abs());
+///     }
+/// }
+/// assert!(max <= 1.0 && max > 0.9);
+/// ```
+pub fn wavelet_noise_2d(x: f64, y: f64, seed: u32, _: InterpFunc) -> f64 {
+	wavelet_eval(&WAVELET_TILE_2D, WAVELET_SIZE_2D, &[x, y], &[0; 2], seed)
+}
+
+pub fn wavelet_noise_3d(x: f64, y: f64, z: f64, seed: u32, _: InterpFunc) -> f64 {
+	wavelet_eval(&WAVELET_TILE_3D, WAVELET_SIZE_3D, &[x, y, z], &[0; 3], seed)
+}
+
+pub fn wavelet_noise_4d(x: f64, y: f64, z: f64, w: f64, seed: u32, _: InterpFunc) -> f64 {
+	wavelet_eval(&WAVELET_TILE_4D, WAVELET_SIZE_4D, &[x, y, z, w], &[0; 4], seed)
+}
+
+pub fn wavelet_noise_6d(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, seed: u32, _: InterpFunc) -> f64 {
+	wavelet_eval(&WAVELET_TILE_6D, WAVELET_SIZE_6D, &[x, y, z, w, u, v], &[0; 6], seed)
+}
+
+// Periodic wavelet noise wraps the coefficient lattice like the other periodic functions. The coefficients no longer
+// follow the tile across the seam, so the band limit is only approximate there, but the noise stays continuous.
+
+pub fn wavelet_noise_2d_periodic(x: f64, y: f64, period: [u32; 2], seed: u32, _: InterpFunc) -> f64 {
+	wavelet_eval(&WAVELET_TILE_2D, WAVELET_SIZE_2D, &[x, y], &period, seed)
+}
+
+pub fn wavelet_noise_3d_periodic(x: f64, y: f64, z: f64, period: [u32; 3], seed: u32, _: InterpFunc) -> f64 {
+	wavelet_eval(&WAVELET_TILE_3D, WAVELET_SIZE_3D, &[x, y, z], &period, seed)
+}
+
+pub fn wavelet_noise_4d_periodic(x: f64, y: f64, z: f64, w: f64, period: [u32; 4], seed: u32, _: InterpFunc) -> f64 {
+	wavelet_eval(&WAVELET_TILE_4D, WAVELET_SIZE_4D, &[x, y, z, w], &period, seed)
+}
+
+pub fn wavelet_noise_6d_periodic(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, period: [u32; 6], seed: u32, _: InterpFunc) -> f64 {
+	wavelet_eval(&WAVELET_TILE_6D, WAVELET_SIZE_6D, &[x, y, z, w, u, v], &period, seed)
+}
+
+// Analytic gradients of the lattice noise functions. Each corner contributes its value weighted by the interpolated
+// weights, so the derivative along an axis picks up the derivative of that axis' interpolant, plus the derivative of
+// the corner value itself for gradient noise.
+
+#[inline(always)]
+pub fn no_interp_deriv(_: f64) -> f64 {
+	0.0
+}
+
+#[inline(always)]
+pub fn linear_interp_deriv(_: f64) -> f64 {
+	1.0
+}
+
+#[inline(always)]
+pub fn hermite_interp_deriv(t: f64) -> f64 {
+	6.0 * t * (1.0 - t)
+}
+
+#[inline(always)]
+pub fn quintic_interp_deriv(t: f64) -> f64 {
+	30.0 * t * t * (t - 1.0) * (t - 1.0)
+}
+
+// The corner function receives the unwrapped and wrapped cell coordinates of the corner, and returns the corner value
+// and its gradient at p.
+fn lattice_gradient<F: Fn(&[i32], &[i32]) -> (f64, [f64; 6])>(p: &[f64], period: &[u32], interp: InterpFunc, interp_deriv: InterpFunc, corner: F) -> [f64; 6] {
+	let n = p.len();
+	let mut cell = [0i32; 6];
+	let mut s = [0.0; 6];
+	let mut ds = [0.0; 6];
+	for k in 0..n {
+		cell[k] = fast_floor(p[k]);
+		s[k] = interp(p[k] - cell[k] as f64);
+		ds[k] = interp_deriv(p[k] - cell[k] as f64);
+	}
+
+	let mut grad = [0.0; 6];
+	let mut ic = [0i32; 6];
+	let mut iw = [0i32; 6];
+	for c in 0..(1usize << n) {
+		for k in 0..n {
+			ic[k] = cell[k] + ((c >> k) & 1) as i32;
+			iw[k] = wrap_lattice(ic[k], period[k]);
+		}
+		let (value, cgrad) = corner(&ic[..n], &iw[..n]);
+
+		let mut weight = 1.0;
+		for (k, &sk) in s.iter().enumerate().take(n) {
+			weight *= if (c >> k) & 1 == 1 { sk } else { 1.0 - sk };
+		}
+		for a in 0..n {
+			let mut dweight = if (c >> a) & 1 == 1 { ds[a] } else { -ds[a] };
+			for k in (0..n).filter(|&k| k != a) {
+				dweight *= if (c >> k) & 1 == 1 { s[k] } else { 1.0 - s[k] };
+			}
+			grad[a] += dweight * value + weight * cgrad[a];
+		}
+	}
+	grad
+}
+
+pub fn value_noise_2d_gradient_with<H: LatticeHash>(x: f64, y: f64, period: [u32; 2], seed: u32, interp: InterpFunc, interp_deriv: InterpFunc) -> [f64; 2] {
+	let g = lattice_gradient(&[x, y], &period, interp, interp_deriv, |_, w| (H::value(H::hash_2(w[0], w[1], seed)), [0.0; 6]));
+	[g[0], g[1]]
+}
+
+pub fn value_noise_3d_gradient_with<H: LatticeHash>(x: f64, y: f64, z: f64, period: [u32; 3], seed: u32, interp: InterpFunc, interp_deriv: InterpFunc) -> [f64; 3] {
+	let g = lattice_gradient(&[x, y, z], &period, interp, interp_deriv, |_, w| (H::value(H::hash_3(w[0], w[1], w[2], seed)), [0.0; 6]));
+	[g[0], g[1], g[2]]
+}
+
+pub fn gradient_noise_2d_gradient_with<H: LatticeHash>(x: f64, y: f64, period: [u32; 2], seed: u32, interp: InterpFunc, interp_deriv: InterpFunc) -> [f64; 2] {
+	let g = lattice_gradient(&[x, y], &period, interp, interp_deriv, |c, w| {
+		let g = H::gradient_2(H::hash_2(w[0], w[1], seed));
+		((x - c[0] as f64) * g[0] + (y - c[1] as f64) * g[1], [g[0], g[1], 0.0, 0.0, 0.0, 0.0])
+	});
+	[g[0], g[1]]
+}
+
+pub fn gradient_noise_3d_gradient_with<H: LatticeHash>(x: f64, y: f64, z: f64, period: [u32; 3], seed: u32, interp: InterpFunc, interp_deriv: InterpFunc) -> [f64; 3] {
+	let g = lattice_gradient(&[x, y, z], &period, interp, interp_deriv, |c, w| {
+		let g = H::gradient_3(H::hash_3(w[0], w[1], w[2], seed));
+		((x - c[0] as f64) * g[0] + (y - c[1] as f64) * g[1] + (z - c[2] as f64) * g[2], [g[0], g[1], g[2], 0.0, 0.0, 0.0])
+	});
+	[g[0], g[1], g[2]]
+}
+
+pub fn gradval_noise_2d_gradient_with<H: LatticeHash>(x: f64, y: f64, period: [u32; 2], seed: u32, interp: InterpFunc, interp_deriv: InterpFunc) -> [f64; 2] {
+	let v = value_noise_2d_gradient_with::<H>(x, y, period, seed, interp, interp_deriv);
+	let g = gradient_noise_2d_gradient_with::<H>(x, y, period, seed, interp, interp_deriv);
+	[v[0] + g[0], v[1] + g[1]]
+}
+
+pub fn gradval_noise_3d_gradient_with<H: LatticeHash>(x: f64, y: f64, z: f64, period: [u32; 3], seed: u32, interp: InterpFunc, interp_deriv: InterpFunc) -> [f64; 3] {
+	let v = value_noise_3d_gradient_with::<H>(x, y, z, period, seed, interp, interp_deriv);
+	let g = gradient_noise_3d_gradient_with::<H>(x, y, z, period, seed, interp, interp_deriv);
+	[v[0] + g[0], v[1] + g[1], v[2] + g[2]]
+}
+
+// Flow noise, after Perlin and Neyret. This is gradient noise whose lattice gradients rotate over time, each at its own
+// rate and in its own direction, so that animating the time swirls the pattern in place instead of scrolling it.
+// Every vertex hashes a gradient as usual, plus a second vector that picks the plane of rotation and a speed.
+
+const FLOW_SEED_OFFSET: u32 = 0x9e37_79b9;
+
+// Rotates g by angle in the plane spanned by g and r. A r parallel to g falls back to the axis along which g is
+// smallest.
+fn flow_rotate(g: &[f64], r: &[f64], angle: f64) -> [f64; 6] {
+	let n = g.len();
+	let glen2 = (0..n).fold(0.0, |s, k| s + g[k] * g[k]);
+	let mut out = [0.0; 6];
+	if glen2 == 0.0 {
+		return out;
+	}
+
+	let mut b = [0.0; 6];
+	let gr = (0..n).fold(0.0, |s, k| s + g[k] * r[k]) / glen2;
+	for k in 0..n {
+		b[k] = r[k] - gr * g[k];
+	}
+	let mut blen2 = (0..n).fold(0.0, |s, k| s + b[k] * b[k]);
+	if blen2 < 1.0e-12 {
+		let axis = (0..n).fold(0, |a, k| if g[k].abs() < g[a].abs() { k } else { a });
+		for k in 0..n {
+			b[k] = -g[axis] * g[k] / glen2;
+		}
+		b[axis] += 1.0;
+		blen2 = (0..n).fold(0.0, |s, k| s + b[k] * b[k]);
+	}
+
+	let scale = (glen2 / blen2).sqrt() * angle.sin();
+	let c = angle.cos();
+	for k in 0..n {
+		out[k] = g[k] * c + b[k] * scale;
+	}
+	out
+}
+
+// Speed in [-1,-0.5] or [0.5,1], so that every vertex turns, in either direction.
+#[inline(always)]
+fn flow_speed(v: f64) -> f64 {
+	if v < 0.0 { v * 0.5 - 0.5 } else { v * 0.5 + 0.5 }
+}
+
+fn flow_corner(p: &[f64], c: &[i32], g: &[f64], r: &[f64], speed: f64, time: f64) -> (f64, [f64; 6]) {
+	let g = flow_rotate(g, r, time * flow_speed(speed));
+	let value = (0..p.len()).fold(0.0, |s, k| s + (p[k] - c[k] as f64) * g[k]);
+	(value, g)
+}
+
+pub type FlowNoiseFunc2 = fn(f64, f64, [u32; 2], f64, u32, InterpFunc) -> f64;
+pub type FlowNoiseFunc3 = fn(f64, f64, f64, [u32; 3], f64, u32, InterpFunc) -> f64;
+pub type FlowNoiseFunc4 = fn(f64, f64, f64, f64, [u32; 4], f64, u32, InterpFunc) -> f64;
+pub type FlowNoiseFunc6 = fn(f64, f64, f64, f64, f64, f64, [u32; 6], f64, u32, InterpFunc) -> f64;
+pub type FlowGradientFunc2 = fn(f64, f64, [u32; 2], f64, u32, InterpFunc, InterpFunc) -> [f64; 2];
+pub type FlowGradientFunc3 = fn(f64, f64, f64, [u32; 3], f64, u32, InterpFunc, InterpFunc) -> [f64; 3];
+
+/// Flow noise in 2D. `time` is the rotation angle in radians of the fastest turning gradients. The lattice wraps at
+/// `period` as for the periodic noise functions; pass zeros for an unbounded lattice.
+pub fn flow_noise_2d_with<H: LatticeHash>(x: f64, y: f64, period: [u32; 2], time: f64, seed: u32, interp: InterpFunc) -> f64 {
+	let p = [x, y];
+	periodic_lattice(&p, &period, interp, |c, w| {
+		let h = H::hash_2(w[0], w[1], seed.wrapping_add(FLOW_SEED_OFFSET));
+		flow_corner(&p, c, &H::gradient_2(H::hash_2(w[0], w[1], seed)), &H::gradient_2(h), H::value(h), time).0
+	})
+}
+
+pub fn flow_noise_3d_with<H: LatticeHash>(x: f64, y: f64, z: f64, period: [u32; 3], time: f64, seed: u32, interp: InterpFunc) -> f64 {
+	let p = [x, y, z];
+	periodic_lattice(&p, &period, interp, |c, w| {
+		let h = H::hash_3(w[0], w[1], w[2], seed.wrapping_add(FLOW_SEED_OFFSET));
+		flow_corner(&p, c, &H::gradient_3(H::hash_3(w[0], w[1], w[2], seed)), &H::gradient_3(h), H::value(h), time).0
+	})
+}
+
+pub fn flow_noise_4d_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, period: [u32; 4], time: f64, seed: u32, interp: InterpFunc) -> f64 {
+	let p = [x, y, z, w];
+	periodic_lattice(&p, &period, interp, |c, iw| {
+		let h = H::hash_4(iw[0], iw[1], iw[2], iw[3], seed.wrapping_add(FLOW_SEED_OFFSET));
+		flow_corner(&p, c, &H::gradient_4(H::hash_4(iw[0], iw[1], iw[2], iw[3], seed)), &H::gradient_4(h), H::value(h), time).0
+	})
+}
+
+pub fn flow_noise_6d_with<H: LatticeHash>(x: f64, y: f64, z: f64, w: f64, u: f64, v: f64, period: [u32; 6], time: f64, seed: u32, interp: InterpFunc) -> f64 {
+	let p = [x, y, z, w, u, v];
+	periodic_lattice(&p, &period, interp, |c, iw| {
+		let h = H::hash_6(iw[0], iw[1], iw[2], iw[3], iw[4], iw[5], seed.wrapping_add(FLOW_SEED_OFFSET));
+		let g = H::gradient_6(H::hash_6(iw[0], iw[1], iw[2], iw[3], iw[4], iw[5], seed));
+		flow_corner(&p, c, &g, &H::gradient_6(h), H::value(h), time).0
+	})
+}
+
+/// Analytic gradient of [`flow_noise_2d_with()`](fn.flow_noise_2d_with.html) with respect to x and y.
+pub fn flow_noise_2d_gradient_with<H: LatticeHash>(x: f64, y: f64, period: [u32; 2], time: f64, seed: u32, interp: InterpFunc, interp_deriv: InterpFunc) -> [f64; 2] {
+	let p = [x, y];
+	let g = lattice_gradient(&p, &period, interp, interp_deriv, |c, w| {
+		let h = H::hash_2(w[0], w[1], seed.wrapping_add(FLOW_SEED_OFFSET));
+		flow_corner(&p, c, &H::gradient_2(H::hash_2(w[0], w[1], seed)), &H::gradient_2(h), H::value(h), time)
+	});
+	[g[0], g[1]]
+}
+
+/// Analytic gradient of [`flow_noise_3d_with()`](fn.flow_noise_3d_with.html) with respect to x, y and z.
+pub fn flow_noise_3d_gradient_with<H: LatticeHash>(x: f64, y: f64, z: f64, period: [u32; 3], time: f64, seed: u32, interp: InterpFunc, interp_deriv: InterpFunc) -> [f64; 3] {
+	let p = [x, y, z];
+	let g = lattice_gradient(&p, &period, interp, interp_deriv, |c, w| {
+		let h = H::hash_3(w[0], w[1], w[2], seed.wrapping_add(FLOW_SEED_OFFSET));
+		flow_corner(&p, c, &H::gradient_3(H::hash_3(w[0], w[1], w[2], seed)), &H::gradient_3(h), H::value(h), time)
+	});
+	[g[0], g[1], g[2]]
+}
@@ -0,0 +1,210 @@
+//! Operations on whole buffers, in the shapes the mapping functions fill: the first index runs along x and the second along y. They process a mapped buffer in place, or, for resampling, into a new one, and the results can be sampled again as sources with [`ImplicitBuffer`](../struct.ImplicitBuffer.html). Ragged buffers, with columns of different lengths, make the filters and resampling panic.
+//!
+//! The filters that read past the edges of the buffer follow a [`WrapMode`](../enum.WrapMode.html): `Clamp` repeats the edge values, `Repeat` wraps around, for tiling buffers, and `Mirror` reflects the buffer at its edges.
+//!
+//! ```
+//! use anl::{ImplicitFractal, FractalType, BasisType, InterpType, ImplicitModule, ImplicitBuffer, WrapMode, BufferFilter};
+//! use anl::mapping::{map_2d, MappingMode, MappingRanges};
+//! use anl::raster::{gaussian_blur, dilate, normalize, resample};
+//!
+//! let mut terrain = ImplicitFractal::new(FractalType::FBM, BasisType::Gradient, InterpType::Quintic);
+//! terrain.set_seed(4);
+//! let mut ranges = MappingRanges::new();
+//! let mut heights = vec![vec![0.0; 64]; 64];
+//! map_2d(MappingMode::SeamlessNone, &mut heights, &mut terrain, &mut ranges, 0.0);
+//!
+//! gaussian_blur(&mut heights, 2.0, WrapMode::Clamp);
+//! dilate(&mut heights, 3, WrapMode::Clamp);
+//! normalize(&mut heights, 0.0, 1.0);
+//! let small = resample(&heights, 16, 16, BufferFilter::Bilinear, WrapMode::Clamp);
+//! assert_eq!(small.len(), 16);
+//!
+//! let mut source = ImplicitBuffer::with_2d(&heights);
+//! source.set_domain_from_ranges(&ranges);
+//! let v = source.get_2d(0.0, 0.0);
+//! assert!(v >= 0.0 && v <= 1.0);
+//! ```
+
+use super::buffer_sampler::{assert_rectangular, flatten_2d, wrap_index};
+use super::implicit_buffer::{BufferFilter, WrapMode};
+
+/// Blurs the buffer with a Gaussian of standard deviation `sigma`, in cells. The kernel is cut off at three standard deviations, and applied along x then y. A `sigma` of 0 leaves the buffer as it is.
+pub fn gaussian_blur<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(a: Matrix, sigma: f64, wrap: WrapMode) {
+    if sigma <= 0.0 {
+        return;
+    }
+    let r = (3.0 * sigma).ceil() as isize;
+    let mut weights: Vec<f64> = (-r..r + 1).map(|d| (-((d * d) as f64) / (2.0 * sigma * sigma)).exp()).collect();
+    let total: f64 = weights.iter().sum();
+    for w in weights.iter_mut() {
+        *w /= total;
+    }
+    separable(a, &weights, wrap);
+}
+
+/// Blurs the buffer with a box of `2 * radius + 1` cells on a side, each cell becoming the mean of the box around it. Applied repeatedly, box blurs approach a Gaussian blur.
+pub fn box_blur<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(a: Matrix, radius: usize, wrap: WrapMode) {
+    let n = 2 * radius + 1;
+    separable(a, &vec![1.0 / n as f64; n], wrap);
+}
+
+/// Convolves the buffer with a kernel, indexed as the buffer is, `kernel[dx][dy]`, and centred on its middle element, at `kernel.len() / 2` along x and likewise along y. The kernel is used as it is, not normalized, so that edge detection and sharpening kernels work; a kernel that averages should sum to 1.
+///
+/// ```
+/// use anl::WrapMode;
+/// use anl::raster::convolve;
+///
+/// // A Laplacian, which is 0 wherever the buffer is linear.
+/// let mut ramp: Vec<Vec<f64>> = (0..8).map(|x| vec![x as f64; 8]).collect();
+/// let laplacian = [[0.0, 1.0, 0.0], [1.0, -4.0, 1.0], [0.0, 1.0, 0.0]];
+/// convolve(&mut ramp, &laplacian, WrapMode::Mirror);
+/// assert_eq!(ramp[4][4], 0.0);
+/// ```
+pub fn convolve<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>, Kernel: AsRef<[KernelRow]>, KernelRow: AsRef<[f64]>>(mut a: Matrix, kernel: Kernel, wrap: WrapMode) {
+    let kernel = kernel.as_ref();
+    let kw = kernel.len();
+    let kh = if kw > 0 { kernel[0].as_ref().len() } else { 0 };
+    let mut taps = Vec::with_capacity(kw * kh);
+    for (kx, column) in kernel.iter().enumerate() {
+        for (ky, &weight) in column.as_ref().iter().enumerate() {
+            if weight != 0.0 {
+                taps.push((kx as isize - (kw / 2) as isize, ky as isize - (kh / 2) as isize, weight));
+            }
+        }
+    }
+    let (map, w, h) = read(&mut a);
+    let mut result = vec![0.0; w * h];
+    for x in 0..w {
+        for y in 0..h {
+            result[x * h + y] = taps.iter().map(|&(dx, dy, weight)| map[at(x, y, dx, dy, w, h, wrap)] * weight).sum();
+        }
+    }
+    write(&mut a, &result, h);
+}
+
+/// Replaces each cell with the smallest value in the square of `2 * radius + 1` cells on a side around it.
+pub fn min_filter<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(a: Matrix, radius: usize, wrap: WrapMode) {
+    let r = radius as isize;
+    let offsets: Vec<(isize, isize)> = (-r..r + 1).flat_map(|dx| (-r..r + 1).map(move |dy| (dx, dy))).collect();
+    rank(a, &offsets, wrap, f64::min);
+}
+
+/// Replaces each cell with the largest value in the square of `2 * radius + 1` cells on a side around it.
+pub fn max_filter<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(a: Matrix, radius: usize, wrap: WrapMode) {
+    let r = radius as isize;
+    let offsets: Vec<(isize, isize)> = (-r..r + 1).flat_map(|dx| (-r..r + 1).map(move |dy| (dx, dy))).collect();
+    rank(a, &offsets, wrap, f64::max);
+}
+
+/// Grayscale dilation by a disc of the given radius, in cells: each cell takes the largest value within the disc around it. High areas grow, and masks spread, round at the corners where a max filter leaves them square.
+pub fn dilate<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(a: Matrix, radius: usize, wrap: WrapMode) {
+    rank(a, &disc(radius), wrap, f64::max);
+}
+
+/// Grayscale erosion by a disc of the given radius, in cells: each cell takes the smallest value within the disc around it. High areas shrink, and thin details vanish. An erosion followed by a dilation, an opening, removes the peaks narrower than the disc; the converse, a closing, fills the narrow pits.
+pub fn erode<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(a: Matrix, radius: usize, wrap: WrapMode) {
+    rank(a, &disc(radius), wrap, f64::min);
+}
+
+/// Rescales the buffer linearly so that its values run from `low` to `high`. A buffer of a single value is set to `low`.
+pub fn normalize<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(mut a: Matrix, low: f64, high: f64) {
+    let (mut min, mut max) = (f64::INFINITY, f64::NEG_INFINITY);
+    for column in a.as_mut().iter_mut() {
+        for &v in column.as_mut().iter() {
+            min = min.min(v);
+            max = max.max(v);
+        }
+    }
+    let scale = if max > min { (high - low) / (max - min) } else { 0.0 };
+    for column in a.as_mut().iter_mut() {
+        for v in column.as_mut().iter_mut() {
+            *v = low + (*v - min) * scale;
+        }
+    }
+}
+
+/// Returns the buffer resampled to `w` by `h` cells over the same area, with the filtering and wrapping of an [`ImplicitBuffer`](../struct.ImplicitBuffer.html): the cell k of the result samples the buffer at k / `w` of its width. The filters interpolate without averaging, so to shrink a buffer by much, blur it first.
+pub fn resample<Matrix: AsRef<[Row]>, Row: AsRef<[f64]>>(a: Matrix, w: usize, h: usize, filter: BufferFilter, wrap: WrapMode) -> Vec<Vec<f64>> {
+    let (mut sampler, data) = flatten_2d("raster::resample", a);
+    sampler.filter = filter;
+    sampler.wrap = wrap;
+    (0..w).map(|x| {
+        (0..h).map(|y| {
+            let mut v = 0.0;
+            sampler.taps([x as f64 / w as f64, y as f64 / h as f64, 0.0], |i, weight| v += data[i] * weight);
+            v
+        }).collect()
+    }).collect()
+}
+
+// The cells within a disc of the given radius around a cell, as offsets.
+fn disc(radius: usize) -> Vec<(isize, isize)> {
+    let r = radius as isize;
+    let mut offsets = Vec::new();
+    for dx in -r..r + 1 {
+        for dy in -r..r + 1 {
+            if dx * dx + dy * dy <= r * r {
+                offsets.push((dx, dy));
+            }
+        }
+    }
+    offsets
+}
+
+// Convolves the buffer with the same 1D kernel, centred on its middle, along x and then along y.
+fn separable<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(mut a: Matrix, weights: &[f64], wrap: WrapMode) {
+    let (mut map, w, h) = read(&mut a);
+    let r = (weights.len() / 2) as isize;
+    let mut pass = vec![0.0; w * h];
+    for &(ax, ay) in [(1, 0), (0, 1)].iter() {
+        for x in 0..w {
+            for y in 0..h {
+                pass[x * h + y] = weights.iter().enumerate().map(|(k, &weight)| {
+                    let d = k as isize - r;
+                    map[at(x, y, d * ax, d * ay, w, h, wrap)] * weight
+                }).sum();
+            }
+        }
+        ::std::mem::swap(&mut map, &mut pass);
+    }
+    write(&mut a, &map, h);
+}
+
+// Replaces each cell with the fold of the cells at the given offsets around it.
+fn rank<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>, F: Fn(f64, f64) -> f64>(mut a: Matrix, offsets: &[(isize, isize)], wrap: WrapMode, f: F) {
+    let (map, w, h) = read(&mut a);
+    let mut result = vec![0.0; w * h];
+    for x in 0..w {
+        for y in 0..h {
+            result[x * h + y] = offsets.iter().fold(map[x * h + y], |v, &(dx, dy)| f(v, map[at(x, y, dx, dy, w, h, wrap)]));
+        }
+    }
+    write(&mut a, &result, h);
+}
+
+// The index of the cell at an offset from (x,y), wrapped into a buffer of w by h cells stored by columns.
+fn at(x: usize, y: usize, dx: isize, dy: isize, w: usize, h: usize, wrap: WrapMode) -> usize {
+    wrap_index(wrap, x as isize + dx, w) * h + wrap_index(wrap, y as isize + dy, h)
+}
+
+// Copies the buffer into a vector stored by columns, with its width and height. Panics if the buffer is not
+// rectangular, so that write can copy the columns back as they are.
+fn read<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(a: &mut Matrix) -> (Vec<f64>, usize, usize) {
+    let w = a.as_mut().len();
+    let h = if w > 0 { a.as_mut()[0].as_mut().len() } else { 0 };
+    assert_rectangular("raster", h, a.as_mut().iter_mut().map(|column| column.as_mut().len()));
+    let mut map = Vec::with_capacity(w * h);
+    for column in a.as_mut().iter_mut() {
+        map.extend_from_slice(column.as_mut());
+    }
+    (map, w, h)
+}
+
+fn write<Matrix: AsMut<[Row]>, Row: AsMut<[f64]>>(a: &mut Matrix, map: &[f64], h: usize) {
+    if h == 0 {
+        return;
+    }
+    for (column, values) in a.as_mut().iter_mut().zip(map.chunks(h)) {
+        column.as_mut().copy_from_slice(values);
+    }
+}
@@ -42,6 +42,11 @@ impl RgbaBlend {
 }
 
 impl RgbaModule for RgbaBlend {
+    fn set_footprint(&mut self, f: f64) {
+        self.low.set_footprint(f);
+        self.high.set_footprint(f);
+    }
+
     fn get_2d(&mut self, x: f64, y: f64) -> Rgba {
         let low = self.low.get_2d(x, y);
         let high = self.high.get_2d(x, y);
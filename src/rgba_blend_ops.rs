@@ -87,6 +87,11 @@ impl RgbaBlendOps {
 }
 
 impl RgbaModule for RgbaBlendOps {
+    fn set_footprint(&mut self, f: f64) {
+        self.source_1.set_footprint(f);
+        self.source_2.set_footprint(f);
+    }
+
     fn get_2d(&mut self, x: f64, y: f64) -> Rgba {
         let s1 = self.source_1.get_2d(x, y);
         let s2 = self.source_2.get_2d(x, y);
@@ -0,0 +1,61 @@
+use super::rgba_module_base::{RgbaModule, Rgba};
+use super::buffer_sampler::{BufferSampler, flatten_2d, flatten_3d};
+
+/// Samples a 2D or 3D buffer of colors, such as a painted texture or a buffer computed by [`map_rgba_2d()`](mapping/fn.map_rgba_2d.html). It is the RGBA counterpart of [`ImplicitBuffer`](struct.ImplicitBuffer.html), with the same indexing, domain, filtering and wrapping; each channel is filtered on its own.
+///
+/// ```
+/// use anl::{RgbaBuffer, RgbaModule, Rgba, WrapMode};
+///
+/// let texture = vec![vec![Rgba::with_all(1.0, 0.0, 0.0, 1.0), Rgba::with_all(0.0, 0.0, 1.0, 1.0)]; 2];
+/// let mut buffer = RgbaBuffer::with_2d(&texture);
+/// buffer.set_wrap(WrapMode::Repeat);
+/// let c = buffer.get_2d(0.25, 0.25);
+/// assert!((c[0] - 0.5).abs() < 1e-6 && (c[2] - 0.5).abs() < 1e-6);
+/// ```
+pub struct RgbaBuffer {
+    sampler: BufferSampler,
+    data: Vec<Rgba>,
+}
+
+impl RgbaBuffer {
+    pub fn with_2d<Matrix: AsRef<[Row]>, Row: AsRef<[Rgba]>>(a: Matrix) -> RgbaBuffer {
+        let (sampler, data) = flatten_2d("RgbaBuffer::with_2d", a);
+        RgbaBuffer {
+            sampler,
+            data,
+        }
+    }
+
+    pub fn with_3d<Matrix: AsRef<[Row]>, Row: AsRef<[Col]>, Col: AsRef<[Rgba]>>(a: Matrix) -> RgbaBuffer {
+        let (sampler, data) = flatten_3d("RgbaBuffer::with_3d", a);
+        RgbaBuffer {
+            sampler,
+            data,
+        }
+    }
+
+    buffer_sampler_methods!();
+
+    fn sample(&self, x: f64, y: f64, z: f64) -> Rgba {
+        let mut c = Rgba::new();
+        let data = &self.data;
+        self.sampler.taps([x, y, z], |i, w| c += data[i] * w as f32);
+        c
+    }
+}
+
+impl RgbaModule for RgbaBuffer {
+    fn get_2d(&mut self, x: f64, y: f64) -> Rgba {
+        let z = self.sampler.domain[2].0;
+        self.sample(x, y, z)
+    }
+    fn get_3d(&mut self, x: f64, y: f64, z: f64) -> Rgba {
+        self.sample(x, y, z)
+    }
+    fn get_4d(&mut self, x: f64, y: f64, z: f64, _: f64) -> Rgba {
+        self.sample(x, y, z)
+    }
+    fn get_6d(&mut self, x: f64, y: f64, z: f64, _: f64, _: f64, _: f64) -> Rgba {
+        self.sample(x, y, z)
+    }
+}
@@ -65,6 +65,13 @@ impl RgbaComposeChannels {
 }
 
 impl RgbaModule for RgbaComposeChannels {
+    fn set_footprint(&mut self, f: f64) {
+        self.c1.set_footprint(f);
+        self.c2.set_footprint(f);
+        self.c3.set_footprint(f);
+        self.c4.set_footprint(f);
+    }
+
     fn get_2d(&mut self, x: f64, y: f64) -> Rgba {
         let r = self.c1.get_2d(x, y);
         let g = self.c2.get_2d(x, y);
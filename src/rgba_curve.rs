@@ -59,6 +59,10 @@ impl RgbaCurve {
 }
 
 impl RgbaModule for RgbaCurve {
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
+
     fn get_2d(&mut self, x: f64, y: f64) -> Rgba {
         let t = self.source.get_2d(x, y);
         self.get(t)
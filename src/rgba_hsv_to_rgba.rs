@@ -22,6 +22,10 @@ impl RgbaHsvToRgba {
 }
 
 impl RgbaModule for RgbaHsvToRgba {
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
+
     fn get_2d(&mut self, x: f64, y: f64) -> Rgba {
         hsv_to_rgba(&self.source.get_2d(x, y))
     }
@@ -21,6 +21,12 @@ impl RgbaImplicitGreyscale {
 }
 
 impl RgbaModule for RgbaImplicitGreyscale {
+    fn set_footprint(&mut self, f: f64) {
+        if let Some(ref s) = self.source {
+            s.borrow_mut().set_footprint(f);
+        }
+    }
+
     fn get_2d(&mut self, x: f64, y: f64) -> Rgba {
     	match self.source {
     		Some(ref s) => {
@@ -11,6 +11,9 @@ use std::cell::RefCell;
 
 pub trait RgbaModule {
 	fn set_seed(&mut self, _seed: u32) {}
+
+	/// Sets the footprint of the following samples, as [`ImplicitModule::set_footprint()`](trait.ImplicitModule.html#method.set_footprint) does. Modules built on implicit sources pass it on to them.
+	fn set_footprint(&mut self, _: f64) {}
 	
     fn get_2d(&mut self, x: f64, y: f64) -> Rgba;
     fn get_3d(&mut self, x: f64, y: f64, z: f64) -> Rgba;
@@ -60,6 +63,12 @@ impl RgbaParameter {
 			&mut RgbaParameter::Module(ref m) => m.borrow_mut().get_6d(x, y, z, w, u, v),
 		}
     }
+
+    pub fn set_footprint(&mut self, f: f64) {
+    	if let RgbaParameter::Module(ref m) = *self {
+    		m.borrow_mut().set_footprint(f);
+    	}
+    }
 }
 
 
@@ -21,6 +21,10 @@ impl RgbaNormalize {
 }
 
 impl RgbaModule for RgbaNormalize {
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
+
     fn get_2d(&mut self, x: f64, y: f64) -> Rgba {
         normalize(&self.source.get_2d(x, y))
     }
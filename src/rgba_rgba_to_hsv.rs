@@ -22,6 +22,10 @@ impl RgbaRgbaToHsv {
 }
 
 impl RgbaModule for RgbaRgbaToHsv {
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
+
     fn get_2d(&mut self, x: f64, y: f64) -> Rgba {
         rgba_to_hsv(&self.source.get_2d(x, y))
     }
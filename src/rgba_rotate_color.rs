@@ -144,6 +144,10 @@ impl RgbaRotateColor {
 }
 
 impl RgbaModule for RgbaRotateColor {
+    fn set_footprint(&mut self, f: f64) {
+        self.source.set_footprint(f);
+    }
+
     fn get_2d(&mut self, x: f64, y: f64) -> Rgba {
         let mut s = self.source.get_2d(x, y);
         self.calculate_rot_matrix_2d(x, y);
@@ -65,6 +65,11 @@ impl RgbaSelect {
 }
 
 impl RgbaModule for RgbaSelect {
+    fn set_footprint(&mut self, f: f64) {
+        self.low.set_footprint(f);
+        self.high.set_footprint(f);
+    }
+
     fn get_2d(&mut self, x: f64, y: f64) -> Rgba {
         let s1 = self.low.get_2d(x, y);
         let s2 = self.high.get_2d(x, y);